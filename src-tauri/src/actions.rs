@@ -1,26 +1,43 @@
-#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-use crate::apple_intelligence;
+use crate::audio_toolkit::{apply_voice_commands, ListenEvent, VoiceCommandSet};
 use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, SoundType};
-use crate::managers::audio::AudioRecordingManager;
+use crate::managers::audio::{pad_short_recording, AudioRecordingManager};
 use crate::managers::gemini_conversation::GeminiConversationManager;
-use crate::managers::history::HistoryManager;
+use crate::managers::history::{HistoryManager, TranscriptionMetrics};
+use crate::managers::meeting::MeetingManager;
 use crate::managers::transcription::TranscriptionManager;
 use crate::gemini_client;
-use crate::settings::{get_settings, AppSettings, APPLE_INTELLIGENCE_PROVIDER_ID};
+use crate::helpers::focused_app::{self, FocusedAppState};
+use crate::post_process;
+use crate::settings::{get_dictation_settings, get_settings, AppSettings, PasteMethod};
 use crate::shortcut;
 use crate::tray::{change_tray_icon, TrayIconState};
 use crate::utils::{self, show_recording_overlay, show_transcribing_overlay};
 use crate::gemini_popup;
 use crate::ManagedToggleState;
-use ferrous_opencc::{config::BuiltinConfig, OpenCC};
 use log::{debug, error, info, warn};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use tauri::Manager;
 
+// Text inserted by the most recent dictation, so voice commands such as
+// "scratch that" can remove it from the focused field
+static LAST_DICTATION: Lazy<Mutex<Option<LastDictation>>> = Lazy::new(|| Mutex::new(None));
+
+// How long after a dictation voice commands may still reach back into it
+const UNDO_WINDOW: Duration = Duration::from_secs(120);
+
+/// What the last dictation left behind, and where.
+struct LastDictation {
+    text: String,
+    /// Application focused when it was inserted, if detection works here
+    target_app: Option<String>,
+    at: Instant,
+}
+
 // Shortcut Action Trait
 pub trait ShortcutAction: Send + Sync {
     fn start(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str);
@@ -242,186 +259,6 @@ fn should_capture_screenshot(shortcut_str: &str) -> bool {
 // Transcribe Action
 struct TranscribeAction;
 
-async fn maybe_post_process_transcription(
-    settings: &AppSettings,
-    transcription: &str,
-) -> Option<String> {
-    if !settings.post_process_enabled {
-        return None;
-    }
-
-    let provider = match settings.active_post_process_provider().cloned() {
-        Some(provider) => provider,
-        None => {
-            debug!("Post-processing enabled but no provider is selected");
-            return None;
-        }
-    };
-
-    let model = settings
-        .post_process_models
-        .get(&provider.id)
-        .cloned()
-        .unwrap_or_default();
-
-    if model.trim().is_empty() {
-        debug!(
-            "Post-processing skipped because provider '{}' has no model configured",
-            provider.id
-        );
-        return None;
-    }
-
-    let selected_prompt_id = match &settings.post_process_selected_prompt_id {
-        Some(id) => id.clone(),
-        None => {
-            debug!("Post-processing skipped because no prompt is selected");
-            return None;
-        }
-    };
-
-    let prompt = match settings
-        .post_process_prompts
-        .iter()
-        .find(|prompt| prompt.id == selected_prompt_id)
-    {
-        Some(prompt) => prompt.prompt.clone(),
-        None => {
-            debug!(
-                "Post-processing skipped because prompt '{}' was not found",
-                selected_prompt_id
-            );
-            return None;
-        }
-    };
-
-    if prompt.trim().is_empty() {
-        debug!("Post-processing skipped because the selected prompt is empty");
-        return None;
-    }
-
-    debug!(
-        "Starting LLM post-processing with provider '{}' (model: {})",
-        provider.id, model
-    );
-
-    // Replace ${output} variable in the prompt with the actual text
-    let processed_prompt = prompt.replace("${output}", transcription);
-    debug!("Processed prompt length: {} chars", processed_prompt.len());
-
-    if provider.id == APPLE_INTELLIGENCE_PROVIDER_ID {
-        #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
-        {
-            if !apple_intelligence::check_apple_intelligence_availability() {
-                debug!("Apple Intelligence selected but not currently available on this device");
-                return None;
-            }
-
-            let token_limit = model.trim().parse::<i32>().unwrap_or(0);
-            return match apple_intelligence::process_text(&processed_prompt, token_limit) {
-                Ok(result) => {
-                    if result.trim().is_empty() {
-                        debug!("Apple Intelligence returned an empty response");
-                        None
-                    } else {
-                        debug!(
-                            "Apple Intelligence post-processing succeeded. Output length: {} chars",
-                            result.len()
-                        );
-                        Some(result)
-                    }
-                }
-                Err(err) => {
-                    error!("Apple Intelligence post-processing failed: {}", err);
-                    None
-                }
-            };
-        }
-
-        #[cfg(not(all(target_os = "macos", target_arch = "aarch64")))]
-        {
-            debug!("Apple Intelligence provider selected on unsupported platform");
-            return None;
-        }
-    }
-
-    let api_key = settings
-        .post_process_api_keys
-        .get(&provider.id)
-        .cloned()
-        .unwrap_or_default();
-
-    // Send the chat completion request
-    match crate::llm_client::send_chat_completion(&provider, api_key, &model, processed_prompt)
-        .await
-    {
-        Ok(Some(content)) => {
-            debug!(
-                "LLM post-processing succeeded for provider '{}'. Output length: {} chars",
-                provider.id,
-                content.len()
-            );
-            Some(content)
-        }
-        Ok(None) => {
-            error!("LLM API response has no content");
-            None
-        }
-        Err(e) => {
-            error!(
-                "LLM post-processing failed for provider '{}': {}. Falling back to original transcription.",
-                provider.id,
-                e
-            );
-            None
-        }
-    }
-}
-
-async fn maybe_convert_chinese_variant(
-    settings: &AppSettings,
-    transcription: &str,
-) -> Option<String> {
-    // Check if language is set to Simplified or Traditional Chinese
-    let is_simplified = settings.selected_language == "zh-Hans";
-    let is_traditional = settings.selected_language == "zh-Hant";
-
-    if !is_simplified && !is_traditional {
-        debug!("selected_language is not Simplified or Traditional Chinese; skipping translation");
-        return None;
-    }
-
-    debug!(
-        "Starting Chinese translation using OpenCC for language: {}",
-        settings.selected_language
-    );
-
-    // Use OpenCC to convert based on selected language
-    let config = if is_simplified {
-        // Convert Traditional Chinese to Simplified Chinese
-        BuiltinConfig::Tw2sp
-    } else {
-        // Convert Simplified Chinese to Traditional Chinese
-        BuiltinConfig::S2twp
-    };
-
-    match OpenCC::from_config(config) {
-        Ok(converter) => {
-            let converted = converter.convert(transcription);
-            debug!(
-                "OpenCC translation completed. Input length: {}, Output length: {}",
-                transcription.len(),
-                converted.len()
-            );
-            Some(converted)
-        }
-        Err(e) => {
-            error!("Failed to initialize OpenCC converter: {}. Falling back to original transcription.", e);
-            None
-        }
-    }
-}
-
 impl ShortcutAction for TranscribeAction {
     fn start(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str) {
         let start_time = Instant::now();
@@ -439,8 +276,11 @@ impl ShortcutAction for TranscribeAction {
         let rm = app.state::<Arc<AudioRecordingManager>>();
 
         // Get the microphone mode to determine audio feedback timing
-        let settings = get_settings(app);
+        let settings = get_dictation_settings(app);
         let is_always_on = settings.always_on_microphone;
+
+        // Remember which app we are dictating into so its rules apply at paste time
+        record_focused_app(app, needs_focused_app(&settings));
         debug!("Microphone mode - always_on: {}", is_always_on);
 
         let mut recording_started = false;
@@ -515,6 +355,17 @@ impl ShortcutAction for TranscribeAction {
         let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
         let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
         let hm = Arc::clone(&app.state::<Arc<HistoryManager>>());
+        let focused_app = app
+            .state::<FocusedAppState>()
+            .0
+            .lock()
+            .ok()
+            .and_then(|mut app_name| app_name.take());
+        post_process::begin_reading_selection(
+            app,
+            &get_dictation_settings(app),
+            focused_app.clone(),
+        );
 
         // Check if using Gemini audio transcription - if so, skip local transcription overlay
         let settings = get_settings(&ah);
@@ -679,6 +530,7 @@ impl ShortcutAction for TranscribeAction {
                                 "".to_string(), // Empty transcription when using Gemini audio
                                 None,
                                 None,
+                                TranscriptionMetrics::default(),
                             )
                             .await
                         {
@@ -692,238 +544,20 @@ impl ShortcutAction for TranscribeAction {
                 // Otherwise, do local transcription as before
                 match tm.transcribe(samples) {
                     Ok(transcription) => {
+                        let transcription_elapsed = transcription_time.elapsed();
                         debug!(
                             "Transcription completed in {:?}: '{}'",
-                            transcription_time.elapsed(),
-                            transcription
+                            transcription_elapsed, transcription
                         );
-                        if !transcription.is_empty() {
-                            let settings = get_settings(&ah);
-                            let mut final_text = transcription.clone();
-                            let mut post_processed_text: Option<String> = None;
-                            let mut post_process_prompt: Option<String> = None;
-
-                            // First, check if Chinese variant conversion is needed
-                            if let Some(converted_text) =
-                                maybe_convert_chinese_variant(&settings, &transcription).await
-                            {
-                                final_text = converted_text.clone();
-                                post_processed_text = Some(converted_text);
-                            }
-                            // Then apply regular post-processing if enabled
-                            else if let Some(processed_text) =
-                                maybe_post_process_transcription(&settings, &transcription).await
-                            {
-                                final_text = processed_text.clone();
-                                post_processed_text = Some(processed_text);
-
-                                // Get the prompt that was used
-                                if let Some(prompt_id) = &settings.post_process_selected_prompt_id {
-                                    if let Some(prompt) = settings
-                                        .post_process_prompts
-                                        .iter()
-                                        .find(|p| &p.id == prompt_id)
-                                    {
-                                        post_process_prompt = Some(prompt.prompt.clone());
-                                    }
-                                }
-                            }
-
-                            // Save to history with post-processed text and prompt
-                            let hm_clone = Arc::clone(&hm);
-                            let transcription_for_history = transcription.clone();
-                            let samples_for_history = samples_clone.clone();
-                            tauri::async_runtime::spawn(async move {
-                                if let Err(e) = hm_clone
-                                    .save_transcription(
-                                        samples_for_history,
-                                        transcription_for_history,
-                                        post_processed_text,
-                                        post_process_prompt,
-                                    )
-                                    .await
-                                {
-                                    error!("Failed to save transcription to history: {}", e);
-                                }
-                            });
-
-                            // Send to Gemini if enabled
-                            info!("Gemini setting check: enabled={}, model={}, send_audio={}", settings.gemini_enabled, settings.gemini_model, settings.gemini_send_audio);
-                            let gemini_enabled = settings.gemini_enabled && !settings.gemini_api_key.is_empty();
-                            if gemini_enabled {
-                                let ah_clone = ah.clone();
-                                let gemini_model = settings.gemini_model.clone();
-                                let gemini_api_key = settings.gemini_api_key.clone();
-                                let send_audio = settings.gemini_send_audio;
-                                
-                                // Get conversation manager and history
-                                let conv_mgr = Arc::clone(&ah.state::<Arc<GeminiConversationManager>>());
-                                let conversation_history: Vec<gemini_client::ConversationMessage> = conv_mgr
-                                    .get_history()
-                                    .into_iter()
-                                    .map(|msg| gemini_client::ConversationMessage {
-                                        role: msg.role.clone(),
-                                        text: msg.text.clone(),
-                                    })
-                                    .collect();
-                                
-                                if send_audio {
-                                    // Send audio directly to Gemini for server-side transcription
-                                    info!("Gemini send_audio enabled, sending audio samples to Gemini");
-                                    
-                                    // Show "Sending to Gemini" status on overlay
-                                    utils::show_gemini_sending_overlay(&ah);
-                                    
-                                    let audio_samples = samples_for_gemini.clone();
-                                    let conv_mgr_clone = Arc::clone(&conv_mgr);
-                                    let screenshot_for_gemini = screenshot.clone();
-                                    tauri::async_runtime::spawn(async move {
-                                        // Prepare context images if screenshot was captured
-                                        let context_images = screenshot_for_gemini.map(|img| vec![img]);
-                                        
-                                        match gemini_client::ask_gemini(
-                                            &ah_clone,
-                                            "", // Empty text when sending audio
-                                            &gemini_model,
-                                            &gemini_api_key,
-                                            context_images, // Screenshot if Ctrl was pressed
-                                            Some(audio_samples), // Send audio samples
-                                            Some(16000), // Sample rate (16kHz, standard for Whisper)
-                                            Some(conversation_history.clone()),
-                                        )
-                                        .await
-                                        {
-                                            Ok(gemini_response_data) => {
-                                                info!("Received Gemini response from audio (answer length: {} chars)", gemini_response_data.answer.len());
-                                                
-                                                // Show "Answer is ready" status before hiding
-                                                utils::show_gemini_ready_overlay(&ah_clone);
-                                                
-                                                // Small delay to show "ready" status, then hide overlay
-                                                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                                                
-                                                // Hide overlay and update tray icon when response is received
-                                                utils::hide_recording_overlay(&ah_clone);
-                                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                                                
-                                                // Get transcription (from Gemini or use local as fallback)
-                                                let question_text = gemini_response_data.transcription
-                                                    .as_ref()
-                                                    .map(|t| t.clone())
-                                                    .unwrap_or_else(|| transcription.clone());
-                                                
-                                                // Add to conversation history
-                                                conv_mgr_clone.add_user_message(question_text.clone());
-                                                conv_mgr_clone.add_model_message(gemini_response_data.answer.clone());
-                                                
-                                                // Format response to include Gemini's transcription and answer
-                                                let formatted_response = format!("**Q:** {}\n\n**A:** {}", question_text, gemini_response_data.answer);
-                                                // Show Gemini popup with formatted response
-                                                gemini_popup::show_gemini_popup(&ah_clone, formatted_response);
-                                            }
-                                            Err(e) => {
-                                                error!("Failed to get Gemini response from audio: {}", e);
-                                                // Hide overlay and update tray icon on error too
-                                                utils::hide_recording_overlay(&ah_clone);
-                                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                                            }
-                                        }
-                                    });
-                                } else {
-                                    // Send transcribed text to Gemini
-                                    info!("Gemini is enabled, sending transcription to Gemini");
-                                    
-                                    // Show "Sending to Gemini" status on overlay
-                                    utils::show_gemini_sending_overlay(&ah);
-                                    
-                                    let transcription_for_gemini = transcription.clone();
-                                    let conv_mgr_clone = Arc::clone(&conv_mgr);
-                                    let screenshot_for_gemini = screenshot.clone();
-                                    tauri::async_runtime::spawn(async move {
-                                        info!("Sending transcription to Gemini: {}", transcription_for_gemini);
-                                        
-                                        // Add user message to conversation history
-                                        conv_mgr_clone.add_user_message(transcription_for_gemini.clone());
-                                        
-                                        // Prepare context images if screenshot was captured
-                                        let context_images = screenshot_for_gemini.map(|img| vec![img]);
-                                        
-                                        match gemini_client::ask_gemini(
-                                            &ah_clone,
-                                            &transcription_for_gemini,
-                                            &gemini_model,
-                                            &gemini_api_key,
-                                            context_images, // Screenshot if Ctrl was pressed
-                                            None, // No audio context for now
-                                            None, // No sample rate
-                                            Some(conversation_history.clone()),
-                                        )
-                                        .await
-                                        {
-                                            Ok(gemini_response_data) => {
-                                                info!("Received Gemini response (answer length: {} chars)", gemini_response_data.answer.len());
-                                                
-                                                // Show "Answer is ready" status before hiding
-                                                utils::show_gemini_ready_overlay(&ah_clone);
-                                                
-                                                // Small delay to show "ready" status, then hide overlay
-                                                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                                                
-                                                // Hide overlay and update tray icon when response is received
-                                                utils::hide_recording_overlay(&ah_clone);
-                                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                                                
-                                                // Add model response to conversation history
-                                                conv_mgr_clone.add_model_message(gemini_response_data.answer.clone());
-                                                
-                                                // Format response to include question and answer
-                                                let formatted_response = format!("**Q:** {}\n\n**A:** {}", transcription_for_gemini, gemini_response_data.answer);
-                                                // Show Gemini popup with formatted response
-                                                gemini_popup::show_gemini_popup(&ah_clone, formatted_response);
-                                            }
-                                            Err(e) => {
-                                                error!("Failed to get Gemini response: {}", e);
-                                                // Hide overlay and update tray icon on error too
-                                                utils::hide_recording_overlay(&ah_clone);
-                                                change_tray_icon(&ah_clone, TrayIconState::Idle);
-                                            }
-                                        }
-                                    });
-                                }
-                            } else {
-                                info!("Gemini is disabled, skipping Gemini API call");
-                            }
-
-                            // Paste the final text (either processed or original) - skip if Gemini is enabled
-                            if !gemini_enabled {
-                                let ah_clone = ah.clone();
-                                let paste_time = Instant::now();
-                                ah.run_on_main_thread(move || {
-                                    match utils::paste(final_text, ah_clone.clone()) {
-                                        Ok(()) => debug!(
-                                            "Text pasted successfully in {:?}",
-                                            paste_time.elapsed()
-                                        ),
-                                        Err(e) => error!("Failed to paste transcription: {}", e),
-                                    }
-                                    // Hide the overlay after transcription is complete
-                                    utils::hide_recording_overlay(&ah_clone);
-                                    change_tray_icon(&ah_clone, TrayIconState::Idle);
-                                })
-                                .unwrap_or_else(|e| {
-                                    error!("Failed to run paste on main thread: {:?}", e);
-                                    utils::hide_recording_overlay(&ah);
-                                    change_tray_icon(&ah, TrayIconState::Idle);
-                                });
-                            } else {
-                                info!("Gemini is enabled, skipping paste - overlay and tray icon will be hidden when Gemini response is received");
-                                // Don't hide overlay/tray icon here - they will be hidden in the async task callbacks
-                                // when the Gemini response is received (or on error)
-                            }
-                        } else {
-                            utils::hide_recording_overlay(&ah);
-                            change_tray_icon(&ah, TrayIconState::Idle);
-                        }
+                        deliver_transcription(
+                            ah.clone(),
+                            transcription,
+                            samples_clone,
+                            transcription_elapsed,
+                            focused_app,
+                            screenshot,
+                        )
+                        .await;
                     }
                     Err(err) => {
                         debug!("Global Shortcut Transcription error: {}", err);
@@ -950,6 +584,618 @@ impl ShortcutAction for TranscribeAction {
     }
 }
 
+/// Applies voice commands and post-processing to a finished transcription,
+/// saves it to history and pastes it, or hands it to Gemini when enabled.
+/// Hides the overlay once the text is delivered. Returns whether the user
+/// said the command that ends hands-free listening.
+async fn deliver_transcription(
+    ah: AppHandle,
+    transcription: String,
+    samples: Vec<f32>,
+    transcription_elapsed: Duration,
+    focused_app: Option<String>,
+    screenshot: Option<Vec<u8>>,
+) -> bool {
+    let tm = Arc::clone(&ah.state::<Arc<TranscriptionManager>>());
+    let hm = Arc::clone(&ah.state::<Arc<HistoryManager>>());
+
+    let mut stop_listening = false;
+    if !transcription.is_empty() {
+        tm.remember_dictation(&transcription);
+        let mut settings = get_dictation_settings(&ah);
+        if let Some(app_name) = &focused_app {
+            if let Some(rule) = settings.app_rule_for(app_name).cloned() {
+                info!("Applying app rule '{}' for {}", rule.app_match, app_name);
+                rule.apply_to(&mut settings);
+            }
+        }
+        // Resolve spoken editing commands before any post-processing
+        let mut command_text = transcription.clone();
+        let mut delete_before = 0;
+        if settings.voice_commands_enabled {
+            let commands = VoiceCommandSet::for_language(
+                &settings.selected_language,
+                &settings.custom_voice_commands,
+            );
+            // Editing commands only reach into a recent dictation made in
+            // the same application; anything else is not ours to delete
+            let previous = undoable_dictation(focused_app.as_deref());
+            let output = apply_voice_commands(
+                &transcription,
+                &commands,
+                previous.as_deref(),
+            );
+            delete_before = output.delete_before;
+            stop_listening = output.stop_listening;
+            command_text = output.text;
+        }
+
+        let mut final_text = command_text.clone();
+        let mut post_processed_text: Option<String> =
+            (command_text != transcription).then(|| command_text.clone());
+        let mut post_process_prompt: Option<String> = None;
+        let mut streamed = String::new();
+        let mut post_process_notice = None;
+        let mut metrics = TranscriptionMetrics {
+            model_id: tm.get_current_model(),
+            transcription_ms: Some(transcription_elapsed.as_millis() as i64),
+            ..Default::default()
+        };
+
+        if command_text.trim().is_empty() {
+            debug!("Only voice commands were spoken, skipping post-processing");
+        } else {
+            let previous_output = last_dictation();
+            let context = post_process::dictation_prompt_context(
+                &ah,
+                &settings,
+                &command_text,
+                focused_app.clone(),
+                previous_output,
+            );
+
+            // Type streamed LLM output straight into the focused field, unless
+            // Gemini takes over the answer
+            let stream_typing = settings.post_process_streaming
+                && settings.paste_method != PasteMethod::None
+                && (!settings.gemini_enabled || settings.gemini_api_key.is_empty());
+            let ah_typing = ah.clone();
+            let mut pending_delete = delete_before;
+            let mut type_delta = move |delta: &str| {
+                let ah_clone = ah_typing.clone();
+                let delta = delta.to_string();
+                let delete = std::mem::take(&mut pending_delete);
+                let _ = ah_typing.run_on_main_thread(move || {
+                    if let Err(e) =
+                        utils::delete_previous_text(delete, &ah_clone)
+                    {
+                        error!("Failed to delete previous dictation: {}", e);
+                    }
+                    if let Err(e) = utils::type_text(&delta, &ah_clone) {
+                        error!("Failed to type streamed text: {}", e);
+                    }
+                });
+            };
+            let outcome = post_process::run_pipeline(
+                &ah,
+                &settings,
+                &command_text,
+                &context,
+                stream_typing.then_some(
+                    &mut type_delta as post_process::StreamSink<'_>,
+                ),
+            )
+            .await;
+            streamed = outcome.streamed;
+            if let Some(notice) = outcome.notice {
+                utils::show_post_process_notice_overlay(&ah, notice);
+                post_process_notice = Some(notice);
+            }
+            if outcome.text != command_text {
+                final_text = outcome.text.clone();
+                post_processed_text = Some(outcome.text);
+            }
+            post_process_prompt = outcome.prompt;
+            metrics.post_process_provider = outcome.provider;
+            metrics.post_process_steps = outcome.steps;
+        }
+
+        // Save to history with post-processed text and prompt
+        let hm_clone = Arc::clone(&hm);
+        let transcription_for_history = transcription.clone();
+        let samples_for_history = samples.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = hm_clone
+                .save_transcription(
+                    samples_for_history,
+                    transcription_for_history,
+                    post_processed_text,
+                    post_process_prompt,
+                    metrics,
+                )
+                .await
+            {
+                error!("Failed to save transcription to history: {}", e);
+            }
+        });
+
+        // Send to Gemini if enabled
+        info!("Gemini setting check: enabled={}, model={}, send_audio={}", settings.gemini_enabled, settings.gemini_model, settings.gemini_send_audio);
+        let gemini_enabled = settings.gemini_enabled && !settings.gemini_api_key.is_empty();
+        if gemini_enabled {
+            let ah_clone = ah.clone();
+            let gemini_model = settings.gemini_model.clone();
+            let gemini_api_key = settings.gemini_api_key.clone();
+            let send_audio = settings.gemini_send_audio;
+
+            // Get conversation manager and history
+            let conv_mgr = Arc::clone(&ah.state::<Arc<GeminiConversationManager>>());
+            let conversation_history: Vec<gemini_client::ConversationMessage> = conv_mgr
+                .get_history()
+                .into_iter()
+                .map(|msg| gemini_client::ConversationMessage {
+                    role: msg.role.clone(),
+                    text: msg.text.clone(),
+                })
+                .collect();
+
+            if send_audio {
+                // Send audio directly to Gemini for server-side transcription
+                info!("Gemini send_audio enabled, sending audio samples to Gemini");
+
+                // Show "Sending to Gemini" status on overlay
+                utils::show_gemini_sending_overlay(&ah);
+
+                let audio_samples = samples.clone();
+                let conv_mgr_clone = Arc::clone(&conv_mgr);
+                let screenshot_for_gemini = screenshot.clone();
+                tauri::async_runtime::spawn(async move {
+                    // Prepare context images if screenshot was captured
+                    let context_images = screenshot_for_gemini.map(|img| vec![img]);
+
+                    match gemini_client::ask_gemini(
+                        &ah_clone,
+                        "", // Empty text when sending audio
+                        &gemini_model,
+                        &gemini_api_key,
+                        context_images, // Screenshot if Ctrl was pressed
+                        Some(audio_samples), // Send audio samples
+                        Some(16000), // Sample rate (16kHz, standard for Whisper)
+                        Some(conversation_history.clone()),
+                    )
+                    .await
+                    {
+                        Ok(gemini_response_data) => {
+                            info!("Received Gemini response from audio (answer length: {} chars)", gemini_response_data.answer.len());
+
+                            // Show "Answer is ready" status before hiding
+                            utils::show_gemini_ready_overlay(&ah_clone);
+
+                            // Small delay to show "ready" status, then hide overlay
+                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+                            // Hide overlay and update tray icon when response is received
+                            utils::hide_recording_overlay(&ah_clone);
+                            change_tray_icon(&ah_clone, TrayIconState::Idle);
+
+                            // Get transcription (from Gemini or use local as fallback)
+                            let question_text = gemini_response_data.transcription
+                                .as_ref()
+                                .map(|t| t.clone())
+                                .unwrap_or_else(|| transcription.clone());
+
+                            // Add to conversation history
+                            conv_mgr_clone.add_user_message(question_text.clone());
+                            conv_mgr_clone.add_model_message(gemini_response_data.answer.clone());
+
+                            // Format response to include Gemini's transcription and answer
+                            let formatted_response = format!("**Q:** {}\n\n**A:** {}", question_text, gemini_response_data.answer);
+                            // Show Gemini popup with formatted response
+                            gemini_popup::show_gemini_popup(&ah_clone, formatted_response);
+                        }
+                        Err(e) => {
+                            error!("Failed to get Gemini response from audio: {}", e);
+                            // Hide overlay and update tray icon on error too
+                            utils::hide_recording_overlay(&ah_clone);
+                            change_tray_icon(&ah_clone, TrayIconState::Idle);
+                        }
+                    }
+                });
+            } else {
+                // Send transcribed text to Gemini
+                info!("Gemini is enabled, sending transcription to Gemini");
+
+                // Show "Sending to Gemini" status on overlay
+                utils::show_gemini_sending_overlay(&ah);
+
+                let transcription_for_gemini = transcription.clone();
+                let conv_mgr_clone = Arc::clone(&conv_mgr);
+                let screenshot_for_gemini = screenshot.clone();
+                tauri::async_runtime::spawn(async move {
+                    info!("Sending transcription to Gemini: {}", transcription_for_gemini);
+
+                    // Add user message to conversation history
+                    conv_mgr_clone.add_user_message(transcription_for_gemini.clone());
+
+                    // Prepare context images if screenshot was captured
+                    let context_images = screenshot_for_gemini.map(|img| vec![img]);
+
+                    match gemini_client::ask_gemini(
+                        &ah_clone,
+                        &transcription_for_gemini,
+                        &gemini_model,
+                        &gemini_api_key,
+                        context_images, // Screenshot if Ctrl was pressed
+                        None, // No audio context for now
+                        None, // No sample rate
+                        Some(conversation_history.clone()),
+                    )
+                    .await
+                    {
+                        Ok(gemini_response_data) => {
+                            info!("Received Gemini response (answer length: {} chars)", gemini_response_data.answer.len());
+
+                            // Show "Answer is ready" status before hiding
+                            utils::show_gemini_ready_overlay(&ah_clone);
+
+                            // Small delay to show "ready" status, then hide overlay
+                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+                            // Hide overlay and update tray icon when response is received
+                            utils::hide_recording_overlay(&ah_clone);
+                            change_tray_icon(&ah_clone, TrayIconState::Idle);
+
+                            // Add model response to conversation history
+                            conv_mgr_clone.add_model_message(gemini_response_data.answer.clone());
+
+                            // Format response to include question and answer
+                            let formatted_response = format!("**Q:** {}\n\n**A:** {}", transcription_for_gemini, gemini_response_data.answer);
+                            // Show Gemini popup with formatted response
+                            gemini_popup::show_gemini_popup(&ah_clone, formatted_response);
+                        }
+                        Err(e) => {
+                            error!("Failed to get Gemini response: {}", e);
+                            // Hide overlay and update tray icon on error too
+                            utils::hide_recording_overlay(&ah_clone);
+                            change_tray_icon(&ah_clone, TrayIconState::Idle);
+                        }
+                    }
+                });
+            }
+        } else {
+            info!("Gemini is disabled, skipping Gemini API call");
+        }
+
+        // Paste the final text (either processed or original) - skip if Gemini is enabled
+        if !gemini_enabled {
+            let ah_clone = ah.clone();
+            let paste_time = Instant::now();
+            let paste_settings = settings.clone();
+            // Streaming already typed the text and removed what voice
+            // commands deleted; a broken stream has to be taken back
+            let fully_typed = !streamed.is_empty() && streamed == final_text;
+            let delete_count = if streamed.is_empty() {
+                delete_before
+            } else if fully_typed {
+                0
+            } else {
+                streamed.chars().count()
+            };
+            ah.run_on_main_thread(move || {
+                if paste_settings.paste_method != PasteMethod::None {
+                    if let Err(e) =
+                        utils::delete_previous_text(delete_count, &ah_clone)
+                    {
+                        error!("Failed to delete previous dictation: {}", e);
+                    }
+                }
+                if fully_typed {
+                    let mut inserted = final_text;
+                    if paste_settings.append_trailing_space {
+                        if let Err(e) = utils::type_text(" ", &ah_clone) {
+                            error!("Failed to type trailing space: {}", e);
+                        }
+                        inserted.push(' ');
+                    }
+                    debug!("Streamed text typed in {:?}", paste_time.elapsed());
+                    remember_dictation(0, Some(inserted), focused_app);
+                } else if !final_text.is_empty() {
+                    let inserted = if paste_settings.append_trailing_space {
+                        format!("{} ", final_text)
+                    } else {
+                        final_text.clone()
+                    };
+                    match utils::paste_with_settings(
+                        final_text,
+                        ah_clone.clone(),
+                        &paste_settings,
+                    ) {
+                        Ok(()) => debug!(
+                            "Text pasted successfully in {:?}",
+                            paste_time.elapsed()
+                        ),
+                        Err(e) => {
+                            error!("Failed to paste transcription: {}", e)
+                        }
+                    }
+                    remember_dictation(0, Some(inserted), focused_app);
+                } else {
+                    remember_dictation(delete_before, None, focused_app);
+                }
+                // Hide the overlay after transcription is complete,
+                // leaving a post-processing notice up long enough to read
+                if post_process_notice.is_some() {
+                    utils::hide_recording_overlay_after(
+                        &ah_clone,
+                        Duration::from_secs(2),
+                    );
+                } else {
+                    utils::hide_recording_overlay(&ah_clone);
+                }
+                change_tray_icon(&ah_clone, TrayIconState::Idle);
+            })
+            .unwrap_or_else(|e| {
+                error!("Failed to run paste on main thread: {:?}", e);
+                utils::hide_recording_overlay(&ah);
+                change_tray_icon(&ah, TrayIconState::Idle);
+            });
+        } else {
+            info!("Gemini is enabled, skipping paste - overlay and tray icon will be hidden when Gemini response is received");
+            // Don't hide overlay/tray icon here - they will be hidden in the async task callbacks
+            // when the Gemini response is received (or on error)
+        }
+    } else {
+        utils::hide_recording_overlay(&ah);
+        change_tray_icon(&ah, TrayIconState::Idle);
+    }
+    stop_listening
+}
+
+/// Text inserted by the last dictation, if it is still in the focused field.
+pub fn last_dictation() -> Option<String> {
+    LAST_DICTATION
+        .lock()
+        .ok()
+        .and_then(|last| last.as_ref().map(|last| last.text.clone()))
+}
+
+/// The last dictation's text if voice commands may still delete it: it is
+/// recent and went into the application that has focus now. When the
+/// focused app cannot be detected on either side, only the age counts.
+fn undoable_dictation(focused_app: Option<&str>) -> Option<String> {
+    let last = LAST_DICTATION.lock().ok()?;
+    let last = last.as_ref()?;
+    if last.at.elapsed() > UNDO_WINDOW {
+        debug!("Last dictation is too old to edit by voice");
+        return None;
+    }
+    if last.target_app.as_deref() != focused_app {
+        debug!(
+            "Last dictation went to {:?}, not {:?}; not editing it",
+            last.target_app, focused_app
+        );
+        return None;
+    }
+    Some(last.text.clone())
+}
+
+/// Track what the last dictation left in the focused field. A dictation that
+/// inserted text replaces it; one that only deleted trims the remembered text.
+fn remember_dictation(deleted: usize, inserted: Option<String>, target_app: Option<String>) {
+    let Ok(mut last) = LAST_DICTATION.lock() else {
+        return;
+    };
+    let text = match inserted {
+        Some(text) => Some(text),
+        None => last.take().map(|last| {
+            let keep = last.text.chars().count().saturating_sub(deleted);
+            last.text.chars().take(keep).collect()
+        }),
+    };
+    *last = text.map(|text| LastDictation {
+        text,
+        target_app,
+        at: Instant::now(),
+    });
+}
+
+/// Whether the recording needs to know the focused application: for app
+/// rules, a prompt's `${app_name}`, to keep voice commands from editing
+/// a dictation made in another application, or to avoid copying the
+/// `${selection}` from a terminal.
+fn needs_focused_app(settings: &AppSettings) -> bool {
+    !settings.app_rules.is_empty()
+        || settings.voice_commands_enabled
+        || post_process::pipeline_uses_variable(settings, "app_name")
+        || post_process::pipeline_uses_variable(settings, "selection")
+}
+
+/// Store the focused application for the recording that is starting.
+/// Detection spawns helper processes on some platforms, so it runs off-thread
+/// and only when the caller says something will read the result.
+fn record_focused_app(app: &AppHandle, enabled: bool) {
+    if let Ok(mut focused) = app.state::<FocusedAppState>().0.lock() {
+        *focused = None;
+    }
+    if !enabled {
+        return;
+    }
+
+    let app_clone = app.clone();
+    std::thread::spawn(move || {
+        let app_name = focused_app::get_focused_app();
+        debug!("Focused application at recording start: {:?}", app_name);
+        if let Ok(mut focused) = app_clone.state::<FocusedAppState>().0.lock() {
+            *focused = app_name;
+        }
+    });
+}
+
+// Hands-free Action
+struct HandsFreeAction;
+
+impl ShortcutAction for HandsFreeAction {
+    fn start(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        let (events_tx, events_rx) = mpsc::channel();
+        if !rm.try_start_listening(binding_id, events_tx) {
+            debug!("Failed to start hands-free listening");
+            clear_toggle(app, binding_id);
+            return;
+        }
+
+        app.state::<Arc<TranscriptionManager>>().initiate_model_load();
+        play_feedback_sound(app, SoundType::Start);
+        shortcut::register_cancel_shortcut(app);
+
+        let app_clone = app.clone();
+        let binding_id = binding_id.to_string();
+        std::thread::spawn(move || run_hands_free_session(app_clone, binding_id, events_rx));
+    }
+
+    fn stop(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        if rm.stop_listening(binding_id) {
+            play_feedback_sound(app, SoundType::Stop);
+        }
+    }
+}
+
+/// Transcribes and pastes each utterance of a hands-free session in order.
+/// The session ends when the user stops or cancels it, says the stop
+/// command, or stays silent for `hands_free_silence_timeout_secs`.
+fn run_hands_free_session(
+    app: AppHandle,
+    binding_id: String,
+    events: mpsc::Receiver<ListenEvent>,
+) {
+    let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    let mut speaking = false;
+
+    loop {
+        let settings = get_settings(&app);
+        let timeout = settings.hands_free_silence_timeout_secs;
+        let event = if speaking || timeout == 0 {
+            events.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            events.recv_timeout(Duration::from_secs(timeout))
+        };
+
+        match event {
+            Ok(ListenEvent::SpeechStart) => {
+                speaking = true;
+                record_focused_app(&app, needs_focused_app(&settings));
+                change_tray_icon(&app, TrayIconState::Recording);
+                show_recording_overlay(&app);
+            }
+            Ok(ListenEvent::Utterance(samples)) => {
+                speaking = false;
+                change_tray_icon(&app, TrayIconState::Transcribing);
+                show_transcribing_overlay(&app);
+                let focused_app = app
+                    .state::<FocusedAppState>()
+                    .0
+                    .lock()
+                    .ok()
+                    .and_then(|mut app_name| app_name.take());
+                post_process::begin_reading_selection(&app, &settings, focused_app.clone());
+
+                let samples = pad_short_recording(samples);
+                let transcription_time = Instant::now();
+                let stop_requested = match tm.transcribe(samples.clone()) {
+                    Ok(transcription) => {
+                        let transcription_elapsed = transcription_time.elapsed();
+                        debug!(
+                            "Hands-free utterance transcribed in {:?}: '{}'",
+                            transcription_elapsed, transcription
+                        );
+                        tauri::async_runtime::block_on(deliver_transcription(
+                            app.clone(),
+                            transcription,
+                            samples,
+                            transcription_elapsed,
+                            focused_app,
+                            None,
+                        ))
+                    }
+                    Err(err) => {
+                        debug!("Hands-free transcription error: {}", err);
+                        utils::hide_recording_overlay(&app);
+                        change_tray_icon(&app, TrayIconState::Idle);
+                        false
+                    }
+                };
+                if stop_requested && rm.stop_listening(&binding_id) {
+                    info!("Stop command spoken, ending hands-free session");
+                    play_feedback_sound(&app, SoundType::Stop);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if rm.stop_listening(&binding_id) {
+                    info!("No speech for {}s, ending hands-free session", timeout);
+                    play_feedback_sound(&app, SoundType::Stop);
+                }
+            }
+            // The recorder drops the channel once listening has stopped
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    shortcut::unregister_cancel_shortcut(&app);
+    clear_toggle(&app, &binding_id);
+    debug!("Hands-free session for binding {} finished", binding_id);
+}
+
+// Meeting Action
+struct MeetingAction;
+
+impl ShortcutAction for MeetingAction {
+    fn start(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        let (frames_tx, frames_rx) = mpsc::channel();
+        if !rm.try_start_capture(binding_id, frames_tx) {
+            debug!("Failed to start meeting recording");
+            clear_toggle(app, binding_id);
+            return;
+        }
+
+        app.state::<Arc<TranscriptionManager>>().initiate_model_load();
+        play_feedback_sound(app, SoundType::Start);
+        change_tray_icon(app, TrayIconState::Recording);
+
+        let app_clone = app.clone();
+        let binding_id = binding_id.to_string();
+        std::thread::spawn(move || {
+            let mm = Arc::clone(&app_clone.state::<Arc<MeetingManager>>());
+            if let Err(e) = mm.record(frames_rx) {
+                error!("Meeting recording failed: {}", e);
+                // Writing failed while audio was still coming in
+                app_clone
+                    .state::<Arc<AudioRecordingManager>>()
+                    .stop_capture(&binding_id);
+            }
+            change_tray_icon(&app_clone, TrayIconState::Idle);
+            clear_toggle(&app_clone, &binding_id);
+        });
+    }
+
+    fn stop(&self, app: &AppHandle, binding_id: &str, _shortcut_str: &str) {
+        let rm = app.state::<Arc<AudioRecordingManager>>();
+        if rm.stop_capture(binding_id) {
+            play_feedback_sound(app, SoundType::Stop);
+            // The rest of the meeting is transcribed before it is saved
+            change_tray_icon(app, TrayIconState::Transcribing);
+        }
+    }
+}
+
+fn clear_toggle(app: &AppHandle, binding_id: &str) {
+    if let Ok(mut states) = app.state::<ManagedToggleState>().lock() {
+        states.active_toggles.insert(binding_id.to_string(), false);
+    }
+}
+
 // Cancel Action
 struct CancelAction;
 
@@ -986,6 +1232,23 @@ impl ShortcutAction for TestAction {
     }
 }
 
+// Profile Action
+struct ProfileAction;
+
+impl ShortcutAction for ProfileAction {
+    fn start(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str) {
+        // Profile bindings use the profile id as their binding id
+        if let Err(e) = crate::commands::profiles::activate_profile(app, Some(binding_id)) {
+            warn!("Failed to activate profile for binding {}: {}", binding_id, e);
+        }
+        TranscribeAction.start(app, binding_id, shortcut_str);
+    }
+
+    fn stop(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str) {
+        TranscribeAction.stop(app, binding_id, shortcut_str);
+    }
+}
+
 // Static Action Map
 pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::new(|| {
     let mut map = HashMap::new();
@@ -993,6 +1256,14 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "transcribe".to_string(),
         Arc::new(TranscribeAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "hands_free".to_string(),
+        Arc::new(HandsFreeAction) as Arc<dyn ShortcutAction>,
+    );
+    map.insert(
+        "meeting".to_string(),
+        Arc::new(MeetingAction) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "cancel".to_string(),
         Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
@@ -1001,5 +1272,18 @@ pub static ACTION_MAP: Lazy<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy::ne
         "test".to_string(),
         Arc::new(TestAction) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "profile".to_string(),
+        Arc::new(ProfileAction) as Arc<dyn ShortcutAction>,
+    );
     map
-});
\ No newline at end of file
+});
+
+/// Look up the action for a binding. Profile bindings are created at runtime,
+/// so they all resolve to the shared profile action.
+pub fn action_for_binding(binding_id: &str) -> Option<&'static Arc<dyn ShortcutAction>> {
+    if binding_id.starts_with(crate::settings::PROFILE_ID_PREFIX) {
+        return ACTION_MAP.get("profile");
+    }
+    ACTION_MAP.get(binding_id)
+}
\ No newline at end of file
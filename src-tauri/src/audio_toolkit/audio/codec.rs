@@ -0,0 +1,434 @@
+use anyhow::{anyhow, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+
+/// Container/codec used when writing recordings to disk.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioCodec {
+    #[default]
+    Wav,
+    Flac,
+    Opus,
+}
+
+impl AudioCodec {
+    pub const ALL: [AudioCodec; 3] = [AudioCodec::Wav, AudioCodec::Flac, AudioCodec::Opus];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioCodec::Wav => "wav",
+            AudioCodec::Flac => "flac",
+            AudioCodec::Opus => "opus",
+        }
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_lowercase();
+        AudioCodec::ALL
+            .into_iter()
+            .find(|codec| codec.extension() == ext)
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+// Opus works on 20 ms frames; at 16 kHz that is 320 samples.
+const OPUS_FRAME_SAMPLES: usize = 320;
+// Ogg Opus granule positions are always expressed at 48 kHz.
+const OPUS_GRANULE_RATE: u64 = 48_000;
+const OPUS_GRANULE_SCALE: u64 = OPUS_GRANULE_RATE / WHISPER_SAMPLE_RATE as u64;
+// Encoder lookahead at 48 kHz (6.5 ms), written as the stream's pre-skip.
+const OPUS_PRE_SKIP: u16 = 312;
+const OPUS_BITRATE: i32 = 24_000;
+const OPUS_SERIAL: u32 = 0x4861_6e64; // "Hand"
+
+/// Save 16 kHz mono samples using the given codec. Encoding a long recording
+/// takes a while, so async callers should run this on the blocking pool.
+pub fn save_audio_file<P: AsRef<Path>>(
+    file_path: P,
+    samples: &[f32],
+    codec: AudioCodec,
+) -> Result<()> {
+    save_audio_stream(file_path, samples.iter().copied(), codec)
+}
+
+/// Like `save_audio_file`, but reads the samples from an iterator, so a long
+/// recording can be encoded without holding all of it in memory.
+pub fn save_audio_stream<P: AsRef<Path>>(
+    file_path: P,
+    samples: impl Iterator<Item = f32>,
+    codec: AudioCodec,
+) -> Result<()> {
+    match codec {
+        AudioCodec::Wav => save_wav_file(file_path.as_ref(), samples),
+        AudioCodec::Flac => save_flac_file(file_path.as_ref(), samples),
+        AudioCodec::Opus => save_opus_file(file_path.as_ref(), samples),
+    }
+}
+
+/// Load a recording written by `save_audio_file` back into 16 kHz mono samples.
+/// The codec is picked from the file extension. Like saving, this blocks.
+pub fn load_audio_file<P: AsRef<Path>>(file_path: P) -> Result<Vec<f32>> {
+    let path = file_path.as_ref();
+    match AudioCodec::from_path(path) {
+        Some(AudioCodec::Wav) => load_wav_file(path),
+        Some(AudioCodec::Flac) => load_flac_file(path),
+        Some(AudioCodec::Opus) => load_opus_file(path),
+        None => Err(anyhow!("Unsupported audio file: {:?}", path)),
+    }
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+fn save_wav_file(path: &Path, samples: impl Iterator<Item = f32>) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: WHISPER_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec)?;
+    for sample in samples {
+        writer.write_sample(to_i16(sample))?;
+    }
+    writer.finalize()?;
+
+    debug!("Saved WAV file: {:?}", path);
+    Ok(())
+}
+
+/// Hands 16-bit samples from an iterator to the FLAC encoder a block at a time.
+struct FlacSource<I> {
+    samples: I,
+    block: Vec<i32>,
+}
+
+impl<I: Iterator<Item = f32>> flacenc::source::Source for FlacSource<I> {
+    fn channels(&self) -> usize {
+        1
+    }
+
+    fn bits_per_sample(&self) -> usize {
+        16
+    }
+
+    fn sample_rate(&self) -> usize {
+        WHISPER_SAMPLE_RATE as usize
+    }
+
+    fn read_samples<F: flacenc::source::Fill>(
+        &mut self,
+        block_size: usize,
+        dest: &mut F,
+    ) -> Result<usize, flacenc::error::SourceError> {
+        self.block.clear();
+        self.block.extend(
+            self.samples
+                .by_ref()
+                .take(block_size)
+                .map(|s| to_i16(s) as i32),
+        );
+        dest.fill_interleaved(&self.block)?;
+        Ok(self.block.len())
+    }
+}
+
+fn save_flac_file(path: &Path, samples: impl Iterator<Item = f32>) -> Result<()> {
+    use flacenc::component::BitRepr;
+    use flacenc::error::Verify;
+
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| anyhow!("Invalid FLAC encoder config: {:?}", e))?;
+    let source = FlacSource {
+        samples,
+        block: Vec::with_capacity(config.block_size),
+    };
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| anyhow!("FLAC encoding failed: {:?}", e))?;
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| anyhow!("FLAC serialization failed: {:?}", e))?;
+    std::fs::write(path, sink.as_slice())?;
+
+    debug!("Saved FLAC file: {:?}", path);
+    Ok(())
+}
+
+fn save_opus_file(path: &Path, mut samples: impl Iterator<Item = f32>) -> Result<()> {
+    use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+
+    let mut encoder = opus::Encoder::new(
+        WHISPER_SAMPLE_RATE,
+        opus::Channels::Mono,
+        opus::Application::Voip,
+    )?;
+    encoder.set_bitrate(opus::Bitrate::Bits(OPUS_BITRATE))?;
+
+    let mut writer = PacketWriter::new(BufWriter::new(File::create(path)?));
+
+    // Identification header (RFC 7845 §5.1)
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(1); // channel count
+    head.extend_from_slice(&OPUS_PRE_SKIP.to_le_bytes());
+    head.extend_from_slice(&WHISPER_SAMPLE_RATE.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family
+    writer.write_packet(
+        head.into_boxed_slice(),
+        OPUS_SERIAL,
+        PacketWriteEndInfo::EndPage,
+        0,
+    )?;
+
+    // Comment header (RFC 7845 §5.2)
+    let vendor = b"Handy";
+    let mut tags = Vec::new();
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    writer.write_packet(
+        tags.into_boxed_slice(),
+        OPUS_SERIAL,
+        PacketWriteEndInfo::EndPage,
+        0,
+    )?;
+
+    // Keep encoding silence past the end until the lookahead has been flushed,
+    // so the final granule position can trim back to the exact sample count.
+    // Each packet is written once the next one exists, so the last one can be
+    // marked as the end of the stream.
+    let final_granule = |total: usize| OPUS_PRE_SKIP as u64 + total as u64 * OPUS_GRANULE_SCALE;
+    let frame_granule = |frames: usize| (frames * OPUS_FRAME_SAMPLES) as u64 * OPUS_GRANULE_SCALE;
+
+    let mut frame = [0f32; OPUS_FRAME_SAMPLES];
+    let mut packet = [0u8; 4000];
+    let mut pending: Option<Box<[u8]>> = None;
+    let mut total = 0;
+    let mut encoded = 0;
+    let mut exhausted = false;
+    while !(exhausted && encoded > 0 && frame_granule(encoded) >= final_granule(total)) {
+        frame.fill(0.0);
+        if !exhausted {
+            let read = frame
+                .iter_mut()
+                .zip(samples.by_ref())
+                .map(|(slot, sample)| *slot = sample)
+                .count();
+            total += read;
+            exhausted = read < OPUS_FRAME_SAMPLES;
+        }
+
+        let len = encoder.encode_float(&frame, &mut packet)?;
+        if let Some(previous) = pending.replace(packet[..len].into()) {
+            writer.write_packet(
+                previous,
+                OPUS_SERIAL,
+                PacketWriteEndInfo::NormalPacket,
+                frame_granule(encoded),
+            )?;
+        }
+        encoded += 1;
+    }
+    if let Some(last) = pending {
+        writer.write_packet(
+            last,
+            OPUS_SERIAL,
+            PacketWriteEndInfo::EndStream,
+            final_granule(total),
+        )?;
+    }
+
+    debug!("Saved Opus file: {:?}", path);
+    Ok(())
+}
+
+fn load_wav_file(path: &Path) -> Result<Vec<f32>> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    ensure_recording_rate(path, spec.sample_rate)?;
+
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<_>, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    Ok(downmix(samples, spec.channels as usize))
+}
+
+fn load_flac_file(path: &Path) -> Result<Vec<f32>> {
+    let mut reader = claxon::FlacReader::open(path)?;
+    let info = reader.streaminfo();
+    ensure_recording_rate(path, info.sample_rate)?;
+
+    let scale = (1i64 << (info.bits_per_sample - 1)) as f32;
+    let mut samples = reader
+        .samples()
+        .map(|s| s.map(|v| v as f32 / scale))
+        .collect::<Result<Vec<_>, _>>()?;
+    // The last frame is padded to the full block size; the stream info
+    // knows how many samples are real
+    if let Some(total) = info.samples {
+        samples.truncate(total as usize * info.channels as usize);
+    }
+
+    Ok(downmix(samples, info.channels as usize))
+}
+
+fn load_opus_file(path: &Path) -> Result<Vec<f32>> {
+    let mut reader = ogg::reading::PacketReader::new(BufReader::new(File::open(path)?));
+    let mut decoder = opus::Decoder::new(WHISPER_SAMPLE_RATE, opus::Channels::Mono)?;
+
+    let head = reader
+        .read_packet()?
+        .ok_or_else(|| anyhow!("Missing Opus header in {:?}", path))?;
+    if head.data.len() < 19 || &head.data[..8] != b"OpusHead" {
+        return Err(anyhow!("Invalid Opus header in {:?}", path));
+    }
+    let pre_skip = u16::from_le_bytes([head.data[10], head.data[11]]) as u64;
+    // Comment header carries nothing we need
+    reader.read_packet()?;
+
+    let mut samples = Vec::new();
+    let mut buf = [0f32; OPUS_FRAME_SAMPLES * 6]; // up to 120 ms per packet
+    let mut last_granule = 0u64;
+    while let Some(packet) = reader.read_packet()? {
+        let decoded = decoder.decode_float(&packet.data, &mut buf, false)?;
+        samples.extend_from_slice(&buf[..decoded]);
+        last_granule = packet.absgp_page();
+    }
+
+    let skip = (pre_skip / OPUS_GRANULE_SCALE) as usize;
+    let total = (last_granule.saturating_sub(pre_skip) / OPUS_GRANULE_SCALE) as usize;
+    let end = (skip + total).min(samples.len());
+    Ok(samples[skip.min(end)..end].to_vec())
+}
+
+fn ensure_recording_rate(path: &Path, sample_rate: u32) -> Result<()> {
+    if sample_rate != WHISPER_SAMPLE_RATE {
+        return Err(anyhow!(
+            "Unexpected sample rate {} Hz in {:?}, expected {} Hz",
+            sample_rate,
+            path,
+            WHISPER_SAMPLE_RATE
+        ));
+    }
+    Ok(())
+}
+
+fn downmix(samples: Vec<f32>, channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return samples;
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A little over a second, so neither encoder sees whole frames only
+    const LEN: usize = WHISPER_SAMPLE_RATE as usize + 123;
+
+    fn tone() -> Vec<f32> {
+        (0..LEN)
+            .map(|i| {
+                let t = i as f32 / WHISPER_SAMPLE_RATE as f32;
+                0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin()
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn temp_path(codec: AudioCodec, name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "handy-codec-{}-{}.{}",
+            name,
+            std::process::id(),
+            codec.extension()
+        ))
+    }
+
+    fn load_and_remove(path: &Path) -> Vec<f32> {
+        let loaded = load_audio_file(path);
+        let _ = std::fs::remove_file(path);
+        loaded.unwrap()
+    }
+
+    fn round_trip(codec: AudioCodec, samples: &[f32]) -> Vec<f32> {
+        let path = temp_path(codec, "file");
+        save_audio_file(&path, samples, codec).unwrap();
+        load_and_remove(&path)
+    }
+
+    fn assert_lossless(codec: AudioCodec) {
+        let input = tone();
+        let output = round_trip(codec, &input);
+        assert_eq!(output.len(), input.len());
+        // 16-bit quantisation is the only loss
+        for (a, b) in input.iter().zip(&output) {
+            assert!((a - b).abs() < 1e-4, "{:?}: {} vs {}", codec, a, b);
+        }
+    }
+
+    #[test]
+    fn wav_round_trips() {
+        assert_lossless(AudioCodec::Wav);
+    }
+
+    #[test]
+    fn flac_round_trips_without_padding() {
+        assert_lossless(AudioCodec::Flac);
+    }
+
+    #[test]
+    fn streamed_samples_come_back_complete() {
+        let input = tone();
+        for codec in AudioCodec::ALL {
+            let path = temp_path(codec, "stream");
+            save_audio_stream(&path, input.iter().copied(), codec).unwrap();
+            assert_eq!(load_and_remove(&path).len(), input.len(), "{:?}", codec);
+        }
+    }
+
+    #[test]
+    fn opus_trims_pre_skip_and_end_padding() {
+        let input = tone();
+        let output = round_trip(AudioCodec::Opus, &input);
+        assert_eq!(output.len(), input.len());
+        let (expected, actual) = (rms(&input), rms(&output));
+        assert!(
+            (actual - expected).abs() < expected * 0.2,
+            "rms {} vs {}",
+            actual,
+            expected
+        );
+    }
+}
@@ -1,5 +1,22 @@
 use cpal::traits::{DeviceTrait, HostTrait};
 
+/// A device to record from. `loopback` captures what an output device plays
+/// instead of an input (WASAPI loopback, Windows only).
+#[derive(Clone)]
+pub struct CaptureDevice {
+    pub device: cpal::Device,
+    pub loopback: bool,
+}
+
+impl CaptureDevice {
+    pub fn input(device: cpal::Device) -> Self {
+        Self {
+            device,
+            loopback: false,
+        }
+    }
+}
+
 pub struct CpalDeviceInfo {
     pub index: String,
     pub name: String,
@@ -50,3 +67,132 @@ pub fn list_output_devices() -> Result<Vec<CpalDeviceInfo>, Box<dyn std::error::
 
     Ok(out)
 }
+
+/// Devices that carry what the system plays. On Windows that is every output
+/// device, recorded through loopback. Elsewhere it is the inputs that expose
+/// the system mix: PulseAudio/PipeWire monitors made available to ALSA, or
+/// virtual devices such as BlackHole on macOS.
+pub fn list_loopback_devices() -> Result<Vec<CpalDeviceInfo>, Box<dyn std::error::Error>> {
+    #[cfg(target_os = "windows")]
+    {
+        list_output_devices()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        const NAME_HINTS: &[&str] = &["monitor", "loopback", "blackhole", "soundflower"];
+        Ok(list_input_devices()?
+            .into_iter()
+            .filter(|d| {
+                let name = d.name.to_lowercase();
+                NAME_HINTS.iter().any(|hint| name.contains(hint))
+            })
+            .collect())
+    }
+}
+
+/// The loopback device called `name`, or the default one (the first one
+/// outside Windows) when no name is given. The error says why there is none.
+pub fn find_loopback_device(name: Option<&str>) -> Result<CaptureDevice, String> {
+    let devices = list_loopback_devices()
+        .map_err(|e| format!("Failed to list system audio devices: {}", e))?;
+    let found = match name {
+        Some(name) => devices.into_iter().find(|d| d.name == name),
+        None => {
+            let default = devices.iter().position(|d| d.is_default).unwrap_or(0);
+            devices.into_iter().nth(default)
+        }
+    };
+    match (found, name) {
+        (Some(d), _) => Ok(CaptureDevice {
+            device: d.device,
+            loopback: cfg!(target_os = "windows"),
+        }),
+        (None, Some(name)) => Err(format!("System audio device '{}' is not available", name)),
+        (None, None) => Err(no_loopback_device_reason()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn no_loopback_device_reason() -> String {
+    // Monitors are PulseAudio/PipeWire sources; cpal only sees the ones that
+    // are also exposed as ALSA capture devices
+    match pulse_monitor_sources() {
+        Ok(monitors) if !monitors.is_empty() => format!(
+            "No system audio device found. PulseAudio/PipeWire has the monitor \
+             source(s) {}, but none is exposed to ALSA; define an ALSA pcm of type \
+             pulse for one of them, with a hint so it is listed",
+            monitors.join(", ")
+        ),
+        Ok(_) => "No system audio device found, and PulseAudio/PipeWire has no monitor sources"
+            .to_string(),
+        Err(e) => format!(
+            "No system audio device found, and PulseAudio/PipeWire could not be asked for \
+             monitor sources: {}",
+            e
+        ),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn no_loopback_device_reason() -> String {
+    "No system audio device found; install a loopback device such as BlackHole".to_string()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn no_loopback_device_reason() -> String {
+    "No system audio device found".to_string()
+}
+
+/// Names of the monitor sources PulseAudio (or PipeWire's pulse server)
+/// offers, from `pactl list short sources`.
+#[cfg(target_os = "linux")]
+fn pulse_monitor_sources() -> Result<Vec<String>, String> {
+    let output = std::process::Command::new("pactl")
+        .args(["list", "short", "sources"])
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| format!("Failed to run pactl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "pactl list short sources failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(parse_monitor_sources(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Lines are `index<TAB>name<TAB>driver<TAB>format<TAB>state`; monitors are
+/// the sources named `<sink>.monitor`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_monitor_sources(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .filter(|name| name.ends_with(".monitor"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_monitor_sources() {
+        let output = "\
+49\talsa_output.pci-0000_00_1f.3.analog-stereo.monitor\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED
+50\talsa_input.pci-0000_00_1f.3.analog-stereo\tPipeWire\ts32le 2ch 48000Hz\tRUNNING
+51\tbluez_output.AA_BB.1.monitor\tPipeWire\ts16le 2ch 48000Hz\tIDLE
+";
+        assert_eq!(
+            parse_monitor_sources(output),
+            vec![
+                "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor",
+                "bluez_output.AA_BB.1.monitor",
+            ]
+        );
+        assert!(parse_monitor_sources("").is_empty());
+    }
+}
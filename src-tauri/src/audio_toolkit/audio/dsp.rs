@@ -0,0 +1,484 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+
+// Noise suppressor
+/// Frames the first noise estimate is taken from (600 ms)
+const NOISE_INIT_FRAMES: usize = 20;
+/// Per bin, the first estimate is this percentile of the initial frames, so
+/// speech in some of them does not end up in the noise profile
+const NOISE_INIT_PERCENTILE: f32 = 0.2;
+/// A bin this many times above its noise estimate is treated as speech
+const NOISE_SPEECH_RATIO: f32 = 3.0;
+/// Per-frame smoothing of the noise estimate on noise-like bins
+const NOISE_TRACK_RATE: f32 = 0.05;
+/// Per-frame smoothing on speech-like bins; slow, so speech barely moves it
+/// but a lasting rise in noise is still picked up
+const NOISE_RISE_RATE: f32 = 0.002;
+const OVER_SUBTRACTION: f32 = 1.5;
+/// Strongest attenuation applied to a bin (-20 dB)
+const MIN_SUPPRESSION_GAIN: f32 = 0.1;
+/// Smoothing of the per-bin gain between frames; limits musical noise
+const GAIN_SMOOTHING: f32 = 0.5;
+
+// Automatic gain control
+const AGC_TARGET_RMS: f32 = 0.1;
+const AGC_MIN_GAIN: f32 = 0.25;
+const AGC_MAX_GAIN: f32 = 10.0;
+/// Frames quieter than this keep the current gain instead of being boosted
+const AGC_GATE_RMS: f32 = 0.003;
+const AGC_ATTACK: f32 = 0.5;
+const AGC_RELEASE: f32 = 0.05;
+
+/// Which clean-up stages run on captured audio before the VAD.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DspConfig {
+    pub noise_suppression: bool,
+    pub high_pass: bool,
+    pub auto_gain: bool,
+}
+
+impl DspConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.noise_suppression || self.high_pass || self.auto_gain
+    }
+}
+
+/// High-pass, noise suppression and gain control on fixed-size frames, in
+/// that order. Only the noise suppressor adds latency, one frame.
+pub struct DspChain {
+    config: DspConfig,
+    high_pass: HighPassFilter,
+    suppressor: NoiseSuppressor,
+    agc: AutoGain,
+    out: Vec<f32>,
+}
+
+impl DspChain {
+    pub fn new(sample_rate: u32, frame_samples: usize) -> Self {
+        Self {
+            config: DspConfig::default(),
+            high_pass: HighPassFilter::new(sample_rate, HIGH_PASS_CUTOFF_HZ),
+            suppressor: NoiseSuppressor::new(frame_samples),
+            agc: AutoGain::new(),
+            out: Vec::with_capacity(frame_samples),
+        }
+    }
+
+    /// The noise suppressor's learned background noise, once it has one.
+    pub fn noise_profile(&self) -> Option<Vec<f32>> {
+        self.suppressor.noise_profile()
+    }
+
+    /// Starts the noise suppressor from a profile learned earlier, such as on
+    /// a previous stream.
+    pub fn set_noise_profile(&mut self, profile: &[f32]) {
+        self.suppressor.set_noise_profile(profile);
+    }
+
+    /// Stages that get switched on start from a clean state.
+    pub fn set_config(&mut self, config: DspConfig) {
+        if config == self.config {
+            return;
+        }
+        if config.high_pass && !self.config.high_pass {
+            self.high_pass.reset();
+        }
+        if config.noise_suppression && !self.config.noise_suppression {
+            self.suppressor.reset();
+        }
+        if config.auto_gain && !self.config.auto_gain {
+            self.agc.reset();
+        }
+        self.config = config;
+    }
+
+    pub fn process<'a>(&'a mut self, frame: &'a [f32]) -> &'a [f32] {
+        if !self.config.is_enabled() {
+            return frame;
+        }
+
+        self.out.clear();
+        self.out.extend_from_slice(frame);
+        if self.config.high_pass {
+            self.high_pass.process(&mut self.out);
+        }
+        if self.config.noise_suppression {
+            self.suppressor.process(&mut self.out);
+        }
+        if self.config.auto_gain {
+            self.agc.process(&mut self.out);
+        }
+        &self.out
+    }
+}
+
+/// Second-order Butterworth high-pass that removes rumble and DC offset.
+pub struct HighPassFilter {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl HighPassFilter {
+    pub fn new(sample_rate: u32, cutoff_hz: f32) -> Self {
+        let w0 = 2.0 * PI * cutoff_hz / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 + cos) / 2.0 / a0,
+            b1: -(1.0 + cos) / a0,
+            b2: (1.0 + cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples {
+            let x = *sample;
+            let y = self.b0 * x + self.z1;
+            self.z1 = self.b1 * x - self.a1 * y + self.z2;
+            self.z2 = self.b2 * x - self.a2 * y;
+            *sample = y;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+/// Spectral noise suppressor: tracks the background noise per frequency bin
+/// and attenuates bins that do not rise clearly above it. Works on 50 %
+/// overlapping windows of two frames, so the output lags by one frame.
+pub struct NoiseSuppressor {
+    hop: usize,
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    input: Vec<f32>,
+    overlap: Vec<f32>,
+    spectrum: Vec<Complex32>,
+    noise: Vec<f32>,
+    gains: Vec<f32>,
+    /// Bin powers of the initial frames, frame after frame
+    init_powers: Vec<f32>,
+    scratch: Vec<f32>,
+    frames_seen: usize,
+}
+
+impl NoiseSuppressor {
+    pub fn new(frame_samples: usize) -> Self {
+        let size = frame_samples * 2;
+        let mut planner = FftPlanner::<f32>::new();
+
+        // Square-root Hann: applied before and after, the overlapping
+        // windows add back up to one
+        let window = (0..size)
+            .map(|i| (PI * i as f32 / size as f32).sin())
+            .collect();
+
+        Self {
+            hop: frame_samples,
+            fft: planner.plan_fft_forward(size),
+            ifft: planner.plan_fft_inverse(size),
+            window,
+            input: vec![0.0; size],
+            overlap: vec![0.0; frame_samples],
+            spectrum: vec![Complex32::new(0.0, 0.0); size],
+            noise: vec![0.0; frame_samples + 1],
+            gains: vec![1.0; frame_samples + 1],
+            init_powers: Vec::with_capacity(NOISE_INIT_FRAMES * (frame_samples + 1)),
+            scratch: Vec::with_capacity(NOISE_INIT_FRAMES),
+            frames_seen: 0,
+        }
+    }
+
+    /// The background noise per bin, once the initial frames are in.
+    pub fn noise_profile(&self) -> Option<Vec<f32>> {
+        (self.frames_seen >= NOISE_INIT_FRAMES).then(|| self.noise.clone())
+    }
+
+    /// Skips the initial estimate and tracks on from `profile`. A profile
+    /// for another frame size is ignored.
+    pub fn set_noise_profile(&mut self, profile: &[f32]) {
+        if profile.len() != self.noise.len() {
+            return;
+        }
+        self.noise.copy_from_slice(profile);
+        self.init_powers.clear();
+        self.frames_seen = self.frames_seen.max(NOISE_INIT_FRAMES);
+    }
+
+    /// Frames of any other length than the one given to `new` pass through.
+    pub fn process(&mut self, frame: &mut [f32]) {
+        let hop = self.hop;
+        if frame.len() != hop {
+            return;
+        }
+        let size = hop * 2;
+
+        self.input.copy_within(hop.., 0);
+        self.input[hop..].copy_from_slice(frame);
+        for (bin, (&x, &w)) in self
+            .spectrum
+            .iter_mut()
+            .zip(self.input.iter().zip(&self.window))
+        {
+            *bin = Complex32::new(x * w, 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        let initial = self.frames_seen < NOISE_INIT_FRAMES;
+        if initial {
+            self.init_powers
+                .extend(self.spectrum[..=hop].iter().map(|bin| bin.norm_sqr()));
+        }
+
+        for k in 0..=hop {
+            let power = self.spectrum[k].norm_sqr();
+            if initial {
+                self.scratch.clear();
+                self.scratch
+                    .extend(self.init_powers.iter().skip(k).step_by(hop + 1));
+                let rank = ((self.scratch.len() - 1) as f32 * NOISE_INIT_PERCENTILE) as usize;
+                let (_, low, _) = self.scratch.select_nth_unstable_by(rank, f32::total_cmp);
+                self.noise[k] = *low;
+            } else {
+                let noise = &mut self.noise[k];
+                let rate = if power < *noise * NOISE_SPEECH_RATIO {
+                    NOISE_TRACK_RATE
+                } else {
+                    NOISE_RISE_RATE
+                };
+                *noise += (power - *noise) * rate;
+            }
+
+            let gain = (1.0 - OVER_SUBTRACTION * self.noise[k] / power.max(f32::EPSILON))
+                .max(MIN_SUPPRESSION_GAIN);
+            let gain = GAIN_SMOOTHING * self.gains[k] + (1.0 - GAIN_SMOOTHING) * gain;
+            self.gains[k] = gain;
+
+            self.spectrum[k] *= gain;
+            if k > 0 && k < hop {
+                self.spectrum[size - k] *= gain;
+            }
+        }
+        self.frames_seen = self.frames_seen.saturating_add(1);
+        if self.frames_seen == NOISE_INIT_FRAMES {
+            self.init_powers.clear();
+        }
+
+        self.ifft.process(&mut self.spectrum);
+        let scale = 1.0 / size as f32;
+        for (i, out) in frame.iter_mut().enumerate() {
+            *out = self.spectrum[i].re * scale * self.window[i] + self.overlap[i];
+            self.overlap[i] = self.spectrum[hop + i].re * scale * self.window[hop + i];
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.input.fill(0.0);
+        self.overlap.fill(0.0);
+        self.noise.fill(0.0);
+        self.gains.fill(1.0);
+        self.init_powers.clear();
+        self.frames_seen = 0;
+    }
+}
+
+/// Brings speech towards a steady level. Gain drops quickly on loud input
+/// and rises slowly, and is held during silence so noise is not pumped up.
+pub struct AutoGain {
+    gain: f32,
+}
+
+impl AutoGain {
+    pub fn new() -> Self {
+        Self { gain: 1.0 }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+
+        let target = if rms > AGC_GATE_RMS {
+            let wanted = (AGC_TARGET_RMS / rms).clamp(AGC_MIN_GAIN, AGC_MAX_GAIN);
+            let rate = if wanted < self.gain {
+                AGC_ATTACK
+            } else {
+                AGC_RELEASE
+            };
+            self.gain + (wanted - self.gain) * rate
+        } else {
+            self.gain
+        };
+
+        // Ramp across the frame to avoid steps in the waveform
+        let step = (target - self.gain) / samples.len() as f32;
+        for (i, sample) in samples.iter_mut().enumerate() {
+            let gain = self.gain + step * (i + 1) as f32;
+            *sample = (*sample * gain).clamp(-1.0, 1.0);
+        }
+        self.gain = target;
+    }
+
+    pub fn reset(&mut self) {
+        self.gain = 1.0;
+    }
+}
+
+impl Default for AutoGain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+    const FRAME: usize = 480;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn sine(freq: f32, amplitude: f32, start: usize) -> Vec<f32> {
+        (start..start + FRAME)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn high_pass_removes_rumble_and_keeps_voice() {
+        let mut rumble_filter = HighPassFilter::new(RATE, HIGH_PASS_CUTOFF_HZ);
+        let mut voice_filter = HighPassFilter::new(RATE, HIGH_PASS_CUTOFF_HZ);
+        let (mut rumble, mut voice) = (Vec::new(), Vec::new());
+
+        for n in 0..20 {
+            let mut frame = sine(20.0, 0.5, n * FRAME);
+            rumble_filter.process(&mut frame);
+            rumble = frame;
+            let mut frame = sine(500.0, 0.5, n * FRAME);
+            voice_filter.process(&mut frame);
+            voice = frame;
+        }
+
+        assert!(rms(&rumble) < 0.05);
+        assert!(rms(&voice) > 0.3);
+    }
+
+    fn noise_source() -> impl FnMut() -> Vec<f32> {
+        let mut state = 12345u32;
+        move || {
+            (0..FRAME)
+                .map(|_| {
+                    state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                    (state >> 8) as f32 / (1 << 24) as f32 * 0.02 - 0.01
+                })
+                .collect()
+        }
+    }
+
+    fn tone_over(noise: Vec<f32>, n: usize) -> Vec<f32> {
+        sine(440.0, 0.3, n * FRAME)
+            .iter()
+            .zip(noise)
+            .map(|(a, b)| a + b)
+            .collect()
+    }
+
+    #[test]
+    fn noise_suppressor_attenuates_steady_noise() {
+        let mut suppressor = NoiseSuppressor::new(FRAME);
+        let mut noise_frame = noise_source();
+
+        let mut last = Vec::new();
+        for _ in 0..100 {
+            let mut frame = noise_frame();
+            suppressor.process(&mut frame);
+            last = frame;
+        }
+        assert!(rms(&last) < rms(&noise_frame()) * 0.5);
+
+        // A loud tone on top of the noise comes through
+        let mut frame = Vec::new();
+        for n in 0..3 {
+            frame = tone_over(noise_frame(), n);
+            suppressor.process(&mut frame);
+        }
+        assert!(rms(&frame) > 0.15);
+    }
+
+    #[test]
+    fn speech_at_the_start_stays_out_of_the_noise_profile() {
+        let mut suppressor = NoiseSuppressor::new(FRAME);
+        let mut noise_frame = noise_source();
+
+        for n in 0..4 {
+            suppressor.process(&mut tone_over(noise_frame(), n));
+        }
+        for _ in 4..NOISE_INIT_FRAMES {
+            suppressor.process(&mut noise_frame());
+        }
+
+        let mut frame = Vec::new();
+        for n in 0..3 {
+            frame = tone_over(noise_frame(), n);
+            suppressor.process(&mut frame);
+        }
+        assert!(rms(&frame) > 0.15);
+    }
+
+    #[test]
+    fn saved_noise_profile_suppresses_from_the_first_frame() {
+        let mut trained = NoiseSuppressor::new(FRAME);
+        let mut noise_frame = noise_source();
+        assert!(trained.noise_profile().is_none());
+        for _ in 0..100 {
+            trained.process(&mut noise_frame());
+        }
+        let profile = trained.noise_profile().unwrap();
+
+        let mut fresh = NoiseSuppressor::new(FRAME);
+        fresh.set_noise_profile(&profile);
+        let mut frame = Vec::new();
+        for _ in 0..8 {
+            frame = noise_frame();
+            fresh.process(&mut frame);
+        }
+        assert!(rms(&frame) < rms(&noise_frame()) * 0.5);
+    }
+
+    #[test]
+    fn auto_gain_boosts_quiet_speech_but_not_silence() {
+        let mut agc = AutoGain::new();
+        let mut frame = Vec::new();
+        for n in 0..200 {
+            frame = sine(300.0, 0.01, n * FRAME);
+            agc.process(&mut frame);
+        }
+        assert!(rms(&frame) > 0.05);
+
+        let gain = agc.gain;
+        let mut silence = vec![0.0001; FRAME];
+        agc.process(&mut silence);
+        assert_eq!(agc.gain, gain);
+    }
+}
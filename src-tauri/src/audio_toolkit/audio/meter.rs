@@ -0,0 +1,127 @@
+const MIN_LEVEL_DB: f32 = -100.0;
+/// Samples at or above this magnitude count as clipped
+const CLIP_LEVEL: f32 = 0.999;
+/// Share of the quietest frames that make up the noise floor
+const NOISE_PERCENTILE: f32 = 0.1;
+/// Share of the frames louder than the level reported for speech
+const SPEECH_PERCENTILE: f32 = 0.95;
+
+/// Collects level statistics over a stretch of audio, frame by frame, to
+/// check how a microphone performs.
+#[derive(Default)]
+pub struct LevelMeter {
+    frame_levels: Vec<f32>,
+    sum_squares: f64,
+    samples: usize,
+    peak: f32,
+    clipped: usize,
+    speech_frames: usize,
+}
+
+/// Levels in dBFS.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelStats {
+    pub frames: usize,
+    pub rms_db: f32,
+    pub peak_db: f32,
+    pub noise_floor_db: f32,
+    pub speech_level_db: f32,
+    pub clipped_samples: usize,
+    pub speech_frames: usize,
+}
+
+impl LevelMeter {
+    /// Add a frame; `speech` is the VAD's verdict for it.
+    pub fn push(&mut self, frame: &[f32], speech: bool) {
+        if frame.is_empty() {
+            return;
+        }
+        let squares: f64 = frame.iter().map(|s| (*s as f64) * (*s as f64)).sum();
+        self.sum_squares += squares;
+        self.samples += frame.len();
+        self.frame_levels
+            .push(to_db((squares / frame.len() as f64).sqrt() as f32));
+        for sample in frame {
+            let magnitude = sample.abs();
+            self.peak = self.peak.max(magnitude);
+            if magnitude >= CLIP_LEVEL {
+                self.clipped += 1;
+            }
+        }
+        if speech {
+            self.speech_frames += 1;
+        }
+    }
+
+    pub fn stats(&self) -> LevelStats {
+        let mut levels = self.frame_levels.clone();
+        levels.sort_by(f32::total_cmp);
+        let percentile = |p: f32| {
+            if levels.is_empty() {
+                MIN_LEVEL_DB
+            } else {
+                levels[((levels.len() - 1) as f32 * p).round() as usize]
+            }
+        };
+        let rms = if self.samples == 0 {
+            0.0
+        } else {
+            (self.sum_squares / self.samples as f64).sqrt() as f32
+        };
+
+        LevelStats {
+            frames: levels.len(),
+            rms_db: to_db(rms),
+            peak_db: to_db(self.peak),
+            noise_floor_db: percentile(NOISE_PERCENTILE),
+            speech_level_db: percentile(SPEECH_PERCENTILE),
+            clipped_samples: self.clipped,
+            speech_frames: self.speech_frames,
+        }
+    }
+}
+
+fn to_db(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return MIN_LEVEL_DB;
+    }
+    (20.0 * amplitude.log10()).max(MIN_LEVEL_DB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(amplitude: f32) -> Vec<f32> {
+        (0..480)
+            .map(|i| amplitude * (i as f32 * 0.3).sin())
+            .collect()
+    }
+
+    #[test]
+    fn quiet_room_and_speech_are_told_apart() {
+        let mut meter = LevelMeter::default();
+        for i in 0..100 {
+            if i % 2 == 0 {
+                meter.push(&tone(0.001), false);
+            } else {
+                meter.push(&tone(0.1), true);
+            }
+        }
+        let stats = meter.stats();
+        assert_eq!(stats.frames, 100);
+        assert_eq!(stats.speech_frames, 50);
+        assert_eq!(stats.clipped_samples, 0);
+        assert!((stats.noise_floor_db - -63.0).abs() < 1.0);
+        assert!((stats.speech_level_db - -23.0).abs() < 1.0);
+        assert!((stats.peak_db - -20.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn full_scale_samples_count_as_clipped() {
+        let mut meter = LevelMeter::default();
+        meter.push(&[0.5, 1.0, -1.0, 0.2], true);
+        assert_eq!(meter.stats().clipped_samples, 2);
+        assert_eq!(LevelMeter::default().stats().rms_db, MIN_LEVEL_DB);
+    }
+}
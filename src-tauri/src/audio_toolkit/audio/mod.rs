@@ -1,12 +1,19 @@
 // Re-export all audio components
+mod codec;
 mod device;
+mod dsp;
+mod meter;
 mod recorder;
 mod resampler;
-mod utils;
 mod visualizer;
 
-pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
-pub use recorder::AudioRecorder;
+pub use codec::{load_audio_file, save_audio_file, save_audio_stream, AudioCodec};
+pub use device::{
+    find_loopback_device, list_input_devices, list_loopback_devices, list_output_devices,
+    CaptureDevice, CpalDeviceInfo,
+};
+pub use dsp::{AutoGain, DspChain, DspConfig, HighPassFilter, NoiseSuppressor};
+pub use meter::{LevelMeter, LevelStats};
+pub use recorder::{AudioRecorder, CaptureFrame, ListenEvent};
 pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
 pub use visualizer::AudioVisualiser;
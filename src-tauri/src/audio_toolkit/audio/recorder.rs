@@ -1,6 +1,10 @@
 use std::{
+    collections::VecDeque,
     io::Error,
-    sync::{mpsc, Arc, Mutex},
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
     time::Duration,
 };
 
@@ -10,24 +14,169 @@ use cpal::{
 };
 
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{AudioVisualiser, CaptureDevice, DspChain, DspConfig, FrameResampler},
     constants,
     vad::{self, VadFrame},
     VoiceActivityDetector,
 };
 
+/// Length of the frames handed to the DSP stage and the VAD
+const FRAME_MS: u64 = 30;
+/// Longest utterance handed out while listening; longer speech is split
+const MAX_UTTERANCE_SECS: usize = 30;
+/// Most extra-source audio queued for mixing (200 ms); older audio is dropped
+/// so the two sources cannot drift apart
+const MAX_EXTRA_LAG_SAMPLES: usize = constants::WHISPER_SAMPLE_RATE as usize / 5;
+
+/// Gains applied to the main capture source and the one mixed into it.
+#[derive(Clone, Copy)]
+struct MixGains {
+    main: f32,
+    extra: f32,
+}
+
+impl Default for MixGains {
+    fn default() -> Self {
+        Self {
+            main: 1.0,
+            extra: 1.0,
+        }
+    }
+}
+
+/// Settings the consumer thread picks up while it runs, and what it
+/// reports back.
+#[derive(Clone, Default)]
+struct LiveSettings {
+    dsp: Arc<Mutex<DspConfig>>,
+    pre_roll_frames: Arc<AtomicUsize>,
+    gains: Arc<Mutex<MixGains>>,
+    /// Rate of the open input stream; 0 while closed
+    sample_rate: Arc<AtomicU32>,
+    /// Background noise learned by the last stream, so the next one does
+    /// not start over
+    noise_profile: Arc<Mutex<Option<Vec<f32>>>>,
+    /// Called when a capture device goes away under an open stream
+    device_lost: Option<DeviceLostCallback>,
+}
+
+type DeviceLostCallback = Arc<dyn Fn() + Send + Sync + 'static>;
+
+/// A running capture stream, the samples it delivers and their rate.
+struct CaptureInput {
+    sample_rate: u32,
+    rx: mpsc::Receiver<Vec<f32>>,
+    /// Kept alive for as long as the samples are read
+    _stream: cpal::Stream,
+}
+
+impl CaptureInput {
+    fn open(
+        source: &CaptureDevice,
+        device_lost: Option<DeviceLostCallback>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let (tx, rx) = mpsc::channel::<Vec<f32>>();
+        let (stream, sample_rate) = AudioRecorder::start_stream(source, tx, device_lost)?;
+        Ok(Self {
+            sample_rate,
+            rx,
+            _stream: stream,
+        })
+    }
+}
+
+/// Mixes the extra source into the 16 kHz frames of the main one. The main
+/// source sets the pace; extra audio that has not arrived counts as silence.
+struct Mixer {
+    extra: Option<(CaptureInput, FrameResampler)>,
+    queue: VecDeque<f32>,
+    out: Vec<f32>,
+}
+
+impl Mixer {
+    fn new(extra: Option<CaptureInput>, frame_dur: Duration) -> Self {
+        let extra = extra.map(|input| {
+            let resampler = FrameResampler::new(
+                input.sample_rate as usize,
+                constants::WHISPER_SAMPLE_RATE as usize,
+                frame_dur,
+            );
+            (input, resampler)
+        });
+        Self {
+            extra,
+            queue: VecDeque::new(),
+            out: Vec::new(),
+        }
+    }
+
+    /// Queues whatever the extra source has delivered so far.
+    fn pull(&mut self) {
+        let Some((input, resampler)) = self.extra.as_mut() else {
+            return;
+        };
+        let queue = &mut self.queue;
+        while let Ok(raw) = input.rx.try_recv() {
+            resampler.push(&raw, |frame: &[f32]| queue.extend(frame));
+        }
+        let excess = queue.len().saturating_sub(MAX_EXTRA_LAG_SAMPLES);
+        queue.drain(..excess);
+    }
+
+    fn mix<'a>(&'a mut self, frame: &'a [f32], gains: MixGains) -> &'a [f32] {
+        if self.extra.is_none() && gains.main == 1.0 {
+            return frame;
+        }
+        self.out.clear();
+        for &sample in frame {
+            let extra = self.queue.pop_front().unwrap_or(0.0);
+            self.out
+                .push((sample * gains.main + extra * gains.extra).clamp(-1.0, 1.0));
+        }
+        &self.out
+    }
+}
+
 enum Cmd {
     Start,
     Stop(mpsc::Sender<Vec<f32>>),
+    Listen(mpsc::Sender<ListenEvent>),
+    /// Stop listening; `true` hands out the utterance in progress first
+    StopListening(bool),
+    Capture(mpsc::Sender<CaptureFrame>),
+    StopCapture,
+    /// Move to another device without interrupting what is in progress
+    SwitchSource(CaptureDevice),
     Shutdown,
 }
 
+/// What the recorder reports while listening hands-free.
+#[derive(Debug)]
+pub enum ListenEvent {
+    /// The VAD detected the start of an utterance
+    SpeechStart,
+    /// A finished utterance, including the VAD's pre-roll and hangover
+    Utterance(Vec<f32>),
+}
+
+/// A frame of unfiltered audio streamed while capturing continuously.
+#[derive(Debug)]
+pub struct CaptureFrame {
+    pub samples: Vec<f32>,
+    /// The same frame as it came from the device, before gain, mixing and
+    /// clean-up; `None` for the pre-roll, which is only kept processed
+    pub raw: Option<Vec<f32>>,
+    /// The VAD's verdict for the frame; always `true` without a VAD
+    pub speech: bool,
+}
+
 pub struct AudioRecorder {
     device: Option<Device>,
     cmd_tx: Option<mpsc::Sender<Cmd>>,
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    live: LiveSettings,
 }
 
 impl AudioRecorder {
@@ -38,6 +187,7 @@ impl AudioRecorder {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            live: LiveSettings::default(),
         })
     }
 
@@ -46,6 +196,12 @@ impl AudioRecorder {
         self
     }
 
+    /// Swap the VAD, or drop it with `None`. Takes effect the next time the
+    /// stream is opened.
+    pub fn set_vad(&mut self, vad: Option<Box<dyn VoiceActivityDetector>>) {
+        self.vad = vad.map(|vad| Arc::new(Mutex::new(vad)));
+    }
+
     pub fn with_level_callback<F>(mut self, cb: F) -> Self
     where
         F: Fn(Vec<f32>) + Send + Sync + 'static,
@@ -54,71 +210,90 @@ impl AudioRecorder {
         self
     }
 
+    /// Called, possibly many times in a row, when the device an open stream
+    /// records from is unplugged or otherwise stops being available.
+    pub fn with_device_lost_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.live.device_lost = Some(Arc::new(cb));
+        self
+    }
+
+    /// Choose the clean-up stages run ahead of the VAD; applies immediately.
+    pub fn set_dsp(&self, config: DspConfig) {
+        *self.live.dsp.lock().unwrap() = config;
+    }
+
+    /// Keep this much audio from before `start()` and put it at the front
+    /// of the recording. Zero turns the pre-roll off.
+    pub fn set_pre_roll(&self, duration: Duration) {
+        let frames = (duration.as_millis() as u64).div_ceil(FRAME_MS) as usize;
+        self.live.pre_roll_frames.store(frames, Ordering::Relaxed);
+    }
+
+    /// Gains for the main source and the one mixed into it; applies
+    /// immediately.
+    pub fn set_gains(&self, gain: f32, extra_gain: f32) {
+        *self.live.gains.lock().unwrap() = MixGains {
+            main: gain,
+            extra: extra_gain,
+        };
+    }
+
+    /// Native rate of the device being recorded, once the stream runs.
+    pub fn sample_rate(&self) -> Option<u32> {
+        match self.live.sample_rate.load(Ordering::Relaxed) {
+            0 => None,
+            rate => Some(rate),
+        }
+    }
+
     pub fn open(&mut self, device: Option<Device>) -> Result<(), Box<dyn std::error::Error>> {
+        self.open_sources(device.map(CaptureDevice::input), None)
+    }
+
+    /// Open `source` (the default input when `None`) and optionally mix
+    /// `extra` into it, e.g. system audio under the microphone. The extra
+    /// source is best effort: if it fails to open, recording goes on without.
+    pub fn open_sources(
+        &mut self,
+        source: Option<CaptureDevice>,
+        extra: Option<CaptureDevice>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         if self.worker_handle.is_some() {
             return Ok(()); // already open
         }
 
-        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
         let (cmd_tx, cmd_rx) = mpsc::channel::<Cmd>();
 
         let host = crate::audio_toolkit::get_cpal_host();
-        let device = match device {
-            Some(dev) => dev,
-            None => host
-                .default_input_device()
-                .ok_or_else(|| Error::new(std::io::ErrorKind::NotFound, "No input device found"))?,
+        let source = match source {
+            Some(source) => source,
+            None => CaptureDevice::input(host.default_input_device().ok_or_else(|| {
+                Error::new(std::io::ErrorKind::NotFound, "No input device found")
+            })?),
         };
 
-        let thread_device = device.clone();
+        let device = source.device.clone();
         let vad = self.vad.clone();
         // Move the optional level callback into the worker thread
         let level_cb = self.level_cb.clone();
+        let live = self.live.clone();
 
         let worker = std::thread::spawn(move || {
-            let config = AudioRecorder::get_preferred_config(&thread_device)
-                .expect("failed to fetch preferred config");
-
-            let sample_rate = config.sample_rate().0;
-            let channels = config.channels() as usize;
-
-            log::info!(
-                "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
-                thread_device.name(),
-                sample_rate,
-                channels,
-                config.sample_format()
-            );
-
-            let stream = match config.sample_format() {
-                cpal::SampleFormat::U8 => {
-                    AudioRecorder::build_stream::<u8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
+            let input = CaptureInput::open(&source, live.device_lost.clone())
+                .expect("failed to start stream");
+            let extra = extra.and_then(|extra| match CaptureInput::open(&extra, None) {
+                Ok(input) => Some(input),
+                Err(e) => {
+                    log::error!("Failed to open extra capture source: {}", e);
+                    None
                 }
-                cpal::SampleFormat::I8 => {
-                    AudioRecorder::build_stream::<i8>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I16 => {
-                    AudioRecorder::build_stream::<i16>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::I32 => {
-                    AudioRecorder::build_stream::<i32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                cpal::SampleFormat::F32 => {
-                    AudioRecorder::build_stream::<f32>(&thread_device, &config, sample_tx, channels)
-                        .unwrap()
-                }
-                _ => panic!("unsupported sample format"),
-            };
-
-            stream.play().expect("failed to start stream");
+            });
 
-            // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb);
-            // stream is dropped here, after run_consumer returns
+            // the streams live in the inputs and close when run_consumer returns
+            run_consumer(input, extra, vad, cmd_rx, level_cb, live);
         });
 
         self.device = Some(device);
@@ -135,6 +310,60 @@ impl AudioRecorder {
         Ok(())
     }
 
+    /// Listen continuously and report each utterance the VAD finds to
+    /// `events` until `stop_listening` is called.
+    pub fn listen(
+        &self,
+        events: mpsc::Sender<ListenEvent>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.vad.is_none() {
+            return Err("Listening needs a voice activity detector".into());
+        }
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Listen(events))?;
+        }
+        Ok(())
+    }
+
+    pub fn stop_listening(&self, flush: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::StopListening(flush))?;
+        }
+        Ok(())
+    }
+
+    /// Streams every frame to `frames` until `stop_capture`, for recordings
+    /// too long to keep in memory.
+    pub fn capture(
+        &self,
+        frames: mpsc::Sender<CaptureFrame>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::Capture(frames))?;
+        }
+        Ok(())
+    }
+
+    pub fn stop_capture(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            tx.send(Cmd::StopCapture)?;
+        }
+        Ok(())
+    }
+
+    /// Record from `source` from now on. A recording, listening session or
+    /// capture in progress carries on with the new device.
+    pub fn switch_source(
+        &mut self,
+        source: CaptureDevice,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(tx) = &self.cmd_tx {
+            self.device = Some(source.device.clone());
+            tx.send(Cmd::SwitchSource(source))?;
+        }
+        Ok(())
+    }
+
     pub fn stop(&self) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
         let (resp_tx, resp_rx) = mpsc::channel();
         if let Some(tx) = &self.cmd_tx {
@@ -150,15 +379,86 @@ impl AudioRecorder {
         if let Some(h) = self.worker_handle.take() {
             let _ = h.join();
         }
+        self.live.sample_rate.store(0, Ordering::Relaxed);
         self.device = None;
         Ok(())
     }
 
+    /// Builds and starts a capture stream that sends mono samples to
+    /// `sample_tx`. Returns the stream, which must be kept alive, and its rate.
+    fn start_stream(
+        source: &CaptureDevice,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+        device_lost: Option<DeviceLostCallback>,
+    ) -> Result<(cpal::Stream, u32), Box<dyn std::error::Error>> {
+        let device = &source.device;
+        let config = if source.loopback {
+            device.default_output_config()?
+        } else {
+            AudioRecorder::get_preferred_config(device)?
+        };
+
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+
+        log::info!(
+            "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}\nLoopback: {}",
+            device.name(),
+            sample_rate,
+            channels,
+            config.sample_format(),
+            source.loopback
+        );
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::U8 => AudioRecorder::build_stream::<u8>(
+                device,
+                &config,
+                sample_tx,
+                channels,
+                device_lost,
+            )?,
+            cpal::SampleFormat::I8 => AudioRecorder::build_stream::<i8>(
+                device,
+                &config,
+                sample_tx,
+                channels,
+                device_lost,
+            )?,
+            cpal::SampleFormat::I16 => AudioRecorder::build_stream::<i16>(
+                device,
+                &config,
+                sample_tx,
+                channels,
+                device_lost,
+            )?,
+            cpal::SampleFormat::I32 => AudioRecorder::build_stream::<i32>(
+                device,
+                &config,
+                sample_tx,
+                channels,
+                device_lost,
+            )?,
+            cpal::SampleFormat::F32 => AudioRecorder::build_stream::<f32>(
+                device,
+                &config,
+                sample_tx,
+                channels,
+                device_lost,
+            )?,
+            format => return Err(format!("unsupported sample format {:?}", format).into()),
+        };
+
+        stream.play()?;
+        Ok((stream, sample_rate))
+    }
+
     fn build_stream<T>(
         device: &cpal::Device,
         config: &cpal::SupportedStreamConfig,
         sample_tx: mpsc::Sender<Vec<f32>>,
         channels: usize,
+        device_lost: Option<DeviceLostCallback>,
     ) -> Result<cpal::Stream, cpal::BuildStreamError>
     where
         T: Sample + SizedSample + Send + 'static,
@@ -195,7 +495,12 @@ impl AudioRecorder {
         device.build_input_stream(
             &config.clone().into(),
             stream_cb,
-            |err| log::error!("Stream error: {}", err),
+            move |err| {
+                log::error!("Stream error: {}", err);
+                if let (cpal::StreamError::DeviceNotAvailable, Some(cb)) = (&err, &device_lost) {
+                    cb();
+                }
+            },
             None,
         )
     }
@@ -240,31 +545,54 @@ impl AudioRecorder {
 }
 
 fn run_consumer(
-    in_sample_rate: u32,
+    mut input: CaptureInput,
+    extra: Option<CaptureInput>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
-    sample_rx: mpsc::Receiver<Vec<f32>>,
     cmd_rx: mpsc::Receiver<Cmd>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    live: LiveSettings,
 ) {
-    let mut frame_resampler = FrameResampler::new(
-        in_sample_rate as usize,
-        constants::WHISPER_SAMPLE_RATE as usize,
-        Duration::from_millis(30),
+    let frame_dur = Duration::from_millis(FRAME_MS);
+    let new_resampler = |sample_rate: u32| {
+        FrameResampler::new(
+            sample_rate as usize,
+            constants::WHISPER_SAMPLE_RATE as usize,
+            frame_dur,
+        )
+    };
+    let mut frame_resampler = new_resampler(input.sample_rate);
+    live.sample_rate.store(input.sample_rate, Ordering::Relaxed);
+    let mut dsp = DspChain::new(
+        constants::WHISPER_SAMPLE_RATE,
+        (constants::WHISPER_SAMPLE_RATE as f64 * frame_dur.as_secs_f64()).round() as usize,
     );
+    dsp.set_config(*live.dsp.lock().unwrap());
+    if let Some(profile) = live.noise_profile.lock().unwrap().as_deref() {
+        dsp.set_noise_profile(profile);
+    }
+    let mut mixer = Mixer::new(extra, frame_dur);
 
     let mut processed_samples = Vec::<f32>::new();
+    // Most recent frames heard while idle, oldest first
+    let mut pre_roll: VecDeque<Vec<f32>> = VecDeque::new();
     let mut recording = false;
+    let mut listener: Option<mpsc::Sender<ListenEvent>> = None;
+    let mut capture: Option<mpsc::Sender<CaptureFrame>> = None;
+    let mut in_utterance = false;
 
     // ---------- spectrum visualisation setup ---------------------------- //
     const BUCKETS: usize = 16;
     const WINDOW_SIZE: usize = 512;
-    let mut visualizer = AudioVisualiser::new(
-        in_sample_rate,
-        WINDOW_SIZE,
-        BUCKETS,
-        400.0,  // vocal_min_hz
-        4000.0, // vocal_max_hz
-    );
+    let new_visualizer = |sample_rate: u32| {
+        AudioVisualiser::new(
+            sample_rate,
+            WINDOW_SIZE,
+            BUCKETS,
+            400.0,  // vocal_min_hz
+            4000.0, // vocal_max_hz
+        )
+    };
+    let mut visualizer = new_visualizer(input.sample_rate);
 
     fn handle_frame(
         samples: &[f32],
@@ -287,10 +615,67 @@ fn run_consumer(
         }
     }
 
-    loop {
-        let raw = match sample_rx.recv() {
+    fn handle_listen_frame(
+        samples: &[f32],
+        vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+        utterance: &mut Vec<f32>,
+        in_utterance: &mut bool,
+        events: &mpsc::Sender<ListenEvent>,
+    ) {
+        let Some(vad_arc) = vad else {
+            return;
+        };
+        let mut det = vad_arc.lock().unwrap();
+        match det.push_frame(samples).unwrap_or(VadFrame::Noise) {
+            VadFrame::Speech(buf) => {
+                if !*in_utterance {
+                    *in_utterance = true;
+                    let _ = events.send(ListenEvent::SpeechStart);
+                }
+                utterance.extend_from_slice(buf);
+                let max_samples = constants::WHISPER_SAMPLE_RATE as usize * MAX_UTTERANCE_SECS;
+                if utterance.len() >= max_samples {
+                    *in_utterance = false;
+                    let _ = events.send(ListenEvent::Utterance(std::mem::take(utterance)));
+                }
+            }
+            VadFrame::Noise => {
+                if *in_utterance {
+                    *in_utterance = false;
+                    let _ = events.send(ListenEvent::Utterance(std::mem::take(utterance)));
+                }
+            }
+        }
+    }
+
+    fn handle_capture_frame(
+        raw: &[f32],
+        samples: &[f32],
+        vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+        frames: &mpsc::Sender<CaptureFrame>,
+    ) -> bool {
+        let speech = match vad {
+            Some(vad_arc) => matches!(
+                vad_arc.lock().unwrap().push_frame(samples),
+                Ok(VadFrame::Speech(_))
+            ),
+            None => true,
+        };
+        frames
+            .send(CaptureFrame {
+                samples: samples.to_vec(),
+                raw: Some(raw.to_vec()),
+                speech,
+            })
+            .is_ok()
+    }
+
+    'consumer: loop {
+        let raw = match input.rx.recv_timeout(frame_dur) {
             Ok(s) => s,
-            Err(_) => break, // stream closed
+            // Loopback streams go quiet while nothing plays; keep serving commands
+            Err(mpsc::RecvTimeoutError::Timeout) => Vec::new(),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break, // stream closed
         };
 
         // ---------- spectrum processing ---------------------------------- //
@@ -301,8 +686,48 @@ fn run_consumer(
         }
 
         // ---------- existing pipeline ------------------------------------ //
-        frame_resampler.push(&raw, &mut |frame: &[f32]| {
-            handle_frame(frame, recording, &vad, &mut processed_samples)
+        dsp.set_config(*live.dsp.lock().unwrap());
+        let pre_roll_frames = live.pre_roll_frames.load(Ordering::Relaxed);
+        let gains = *live.gains.lock().unwrap();
+        mixer.pull();
+        frame_resampler.push(&raw, &mut |raw_frame: &[f32]| {
+            let frame = mixer.mix(raw_frame, gains);
+            let frame = dsp.process(frame);
+            match &listener {
+                Some(events) => handle_listen_frame(
+                    frame,
+                    &vad,
+                    &mut processed_samples,
+                    &mut in_utterance,
+                    events,
+                ),
+                None if capture.is_some() => {
+                    let frames = capture.as_ref().unwrap();
+                    if !handle_capture_frame(raw_frame, frame, &vad, frames) {
+                        // The receiving end is gone; nobody wants the audio
+                        capture = None;
+                    }
+                }
+                None if recording => handle_frame(frame, recording, &vad, &mut processed_samples),
+                None => {
+                    if pre_roll_frames > 0 {
+                        while pre_roll.len() > pre_roll_frames {
+                            pre_roll.pop_front();
+                        }
+                        // Reuse the oldest buffer once the ring is full
+                        let mut buf = if pre_roll.len() == pre_roll_frames {
+                            pre_roll.pop_front().unwrap_or_default()
+                        } else {
+                            Vec::with_capacity(frame.len())
+                        };
+                        buf.clear();
+                        buf.extend_from_slice(frame);
+                        pre_roll.push_back(buf);
+                    } else {
+                        pre_roll.clear();
+                    }
+                }
+            }
         });
 
         // non-blocking check for a command
@@ -311,23 +736,103 @@ fn run_consumer(
                 Cmd::Start => {
                     processed_samples.clear();
                     recording = true;
+                    listener = None;
+                    capture = None;
                     visualizer.reset(); // Reset visualization buffer
                     if let Some(v) = &vad {
                         v.lock().unwrap().reset();
                     }
+                    // Audio from just before the key press goes through the
+                    // VAD first, so the opening syllable is not lost
+                    for frame in pre_roll.drain(..) {
+                        handle_frame(&frame, true, &vad, &mut processed_samples);
+                    }
                 }
                 Cmd::Stop(reply_tx) => {
                     recording = false;
 
                     frame_resampler.finish(&mut |frame: &[f32]| {
                         // we still want to process the last few frames
-                        handle_frame(frame, true, &vad, &mut processed_samples)
+                        let frame = mixer.mix(frame, gains);
+                        let frame = dsp.process(frame);
+                        handle_frame(frame, true, &vad, &mut processed_samples);
                     });
 
                     let _ = reply_tx.send(std::mem::take(&mut processed_samples));
                 }
-                Cmd::Shutdown => return,
+                Cmd::Listen(events) => {
+                    processed_samples.clear();
+                    pre_roll.clear();
+                    recording = false;
+                    in_utterance = false;
+                    capture = None;
+                    listener = Some(events);
+                    if let Some(v) = &vad {
+                        v.lock().unwrap().reset();
+                    }
+                }
+                Cmd::StopListening(flush) => {
+                    if let Some(events) = listener.take() {
+                        if flush && in_utterance {
+                            let _ = events.send(ListenEvent::Utterance(std::mem::take(
+                                &mut processed_samples,
+                            )));
+                        }
+                    }
+                    in_utterance = false;
+                    processed_samples.clear();
+                }
+                Cmd::Capture(frames) => {
+                    processed_samples.clear();
+                    recording = false;
+                    listener = None;
+                    // Audio from just before the start belongs to the capture
+                    for frame in pre_roll.drain(..) {
+                        if frames
+                            .send(CaptureFrame {
+                                samples: frame,
+                                raw: None,
+                                speech: false,
+                            })
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    if let Some(v) = &vad {
+                        v.lock().unwrap().reset();
+                    }
+                    capture = Some(frames);
+                }
+                Cmd::StopCapture => {
+                    if let Some(frames) = capture.take() {
+                        frame_resampler.finish(&mut |raw_frame: &[f32]| {
+                            let frame = mixer.mix(raw_frame, gains);
+                            let frame = dsp.process(frame);
+                            handle_capture_frame(raw_frame, frame, &vad, &frames);
+                        });
+                    }
+                }
+                Cmd::SwitchSource(source) => {
+                    match CaptureInput::open(&source, live.device_lost.clone()) {
+                        Ok(new_input) => {
+                            // Whatever the old device still had buffered is lost
+                            frame_resampler = new_resampler(new_input.sample_rate);
+                            visualizer = new_visualizer(new_input.sample_rate);
+                            live.sample_rate
+                                .store(new_input.sample_rate, Ordering::Relaxed);
+                            input = new_input;
+                            log::info!("Capture moved to another device");
+                        }
+                        Err(e) => log::error!("Failed to switch capture device: {}", e),
+                    }
+                }
+                Cmd::Shutdown => break 'consumer,
             }
         }
     }
+
+    if let Some(profile) = dsp.noise_profile() {
+        *live.noise_profile.lock().unwrap() = Some(profile);
+    }
 }
@@ -1,12 +1,20 @@
 pub mod audio;
 pub mod constants;
+pub mod speaker;
 pub mod text;
 pub mod utils;
 pub mod vad;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    find_loopback_device, list_input_devices, list_loopback_devices, list_output_devices,
+    load_audio_file, save_audio_file, save_audio_stream, AudioCodec, AudioRecorder, CaptureDevice,
+    CaptureFrame, CpalDeviceInfo, DspConfig, LevelMeter, LevelStats, ListenEvent,
+};
+pub use speaker::{OnnxSpeakerEmbedder, SpeakerClusterer, SpeakerEmbedder};
+pub use text::{
+    apply_custom_words, apply_vocabulary, apply_vocabulary_index, apply_voice_commands,
+    normalize_text, scorer_for_language, CaseRule, NormalizationOptions, PhoneticScorer, RegexRule,
+    VocabularyEntry, VocabularyIndex, VoiceCommandPhrase, VoiceCommandSet,
 };
-pub use text::apply_custom_words;
 pub use utils::get_cpal_host;
-pub use vad::{SileroVad, VoiceActivityDetector};
+pub use vad::{EnergyVad, SileroVad, VoiceActivityDetector};
@@ -0,0 +1,253 @@
+use anyhow::Result;
+use ort::session::Session;
+use ort::value::Tensor;
+use rustfft::{num_complex::Complex32, FftPlanner};
+use std::path::Path;
+
+use crate::audio_toolkit::constants;
+
+/// Kaldi-style filterbank settings the common speaker models are trained on
+const FRAME_LEN: usize = 400; // 25 ms
+const FRAME_SHIFT: usize = 160; // 10 ms
+const FFT_LEN: usize = 512;
+pub const MEL_BINS: usize = 80;
+const LOW_FREQ_HZ: f32 = 20.0;
+const PREEMPHASIS: f32 = 0.97;
+
+pub trait SpeakerEmbedder: Send {
+    /// One fixed-size voice print for a stretch of 16 kHz speech.
+    fn embed(&mut self, samples: &[f32]) -> Result<Vec<f32>>;
+}
+
+/// Speaker embedding model (WeSpeaker/3D-Speaker style ONNX export) that
+/// takes `[1, frames, 80]` filterbank features and returns one embedding.
+pub struct OnnxSpeakerEmbedder {
+    session: Session,
+    fbank: Fbank,
+}
+
+impl OnnxSpeakerEmbedder {
+    pub fn new<P: AsRef<Path>>(model_path: P) -> Result<Self> {
+        let session = Session::builder()?
+            .with_intra_threads(1)?
+            .commit_from_file(model_path.as_ref())
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to load {}: {e}", model_path.as_ref().display())
+            })?;
+        Ok(Self {
+            session,
+            fbank: Fbank::new(),
+        })
+    }
+}
+
+impl SpeakerEmbedder for OnnxSpeakerEmbedder {
+    fn embed(&mut self, samples: &[f32]) -> Result<Vec<f32>> {
+        let features = self.fbank.compute(samples);
+        let frames = features.len() / MEL_BINS;
+        if frames == 0 {
+            anyhow::bail!("Too little audio for a speaker embedding");
+        }
+        let input = Tensor::from_array(([1usize, frames, MEL_BINS], features))?;
+        let outputs = self.session.run(ort::inputs![input])?;
+        let (_, embedding) = outputs[0].try_extract_tensor::<f32>()?;
+        Ok(embedding.to_vec())
+    }
+}
+
+/// Log mel filterbank with per-utterance mean normalisation.
+struct Fbank {
+    fft: std::sync::Arc<dyn rustfft::Fft<f32>>,
+    window: Vec<f32>,
+    /// One triangular filter per mel bin: first FFT bin and its weights
+    filters: Vec<(usize, Vec<f32>)>,
+}
+
+impl Fbank {
+    fn new() -> Self {
+        let window = (0..FRAME_LEN)
+            .map(|i| {
+                0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LEN - 1) as f32).cos()
+            })
+            .collect();
+
+        let mel = |hz: f32| 1127.0 * (1.0 + hz / 700.0).ln();
+        let nyquist = constants::WHISPER_SAMPLE_RATE as f32 / 2.0;
+        let (mel_low, mel_high) = (mel(LOW_FREQ_HZ), mel(nyquist));
+        let mel_step = (mel_high - mel_low) / (MEL_BINS + 1) as f32;
+        let bin_hz = constants::WHISPER_SAMPLE_RATE as f32 / FFT_LEN as f32;
+        let filters = (0..MEL_BINS)
+            .map(|m| {
+                let left = mel_low + m as f32 * mel_step;
+                let center = left + mel_step;
+                let right = center + mel_step;
+                let mut first = None;
+                let mut weights = Vec::new();
+                for bin in 0..FFT_LEN / 2 {
+                    let m = mel(bin as f32 * bin_hz);
+                    if m <= left || m >= right {
+                        continue;
+                    }
+                    first.get_or_insert(bin);
+                    weights.push(if m <= center {
+                        (m - left) / (center - left)
+                    } else {
+                        (right - m) / (right - center)
+                    });
+                }
+                (first.unwrap_or(0), weights)
+            })
+            .collect();
+
+        Self {
+            fft: FftPlanner::new().plan_fft_forward(FFT_LEN),
+            window,
+            filters,
+        }
+    }
+
+    /// Features for `samples`, `MEL_BINS` values per 10 ms frame.
+    fn compute(&self, samples: &[f32]) -> Vec<f32> {
+        if samples.len() < FRAME_LEN {
+            return Vec::new();
+        }
+        let frames = 1 + (samples.len() - FRAME_LEN) / FRAME_SHIFT;
+        let mut features = Vec::with_capacity(frames * MEL_BINS);
+        let mut frame = [0.0f32; FRAME_LEN];
+        let mut buf = vec![Complex32::new(0.0, 0.0); FFT_LEN];
+
+        for f in 0..frames {
+            let start = f * FRAME_SHIFT;
+            // Models expect 16-bit sample values
+            for (dst, &s) in frame.iter_mut().zip(&samples[start..start + FRAME_LEN]) {
+                *dst = s * 32768.0;
+            }
+            let mean = frame.iter().sum::<f32>() / FRAME_LEN as f32;
+            frame.iter_mut().for_each(|s| *s -= mean);
+            for i in (1..FRAME_LEN).rev() {
+                frame[i] -= PREEMPHASIS * frame[i - 1];
+            }
+            frame[0] -= PREEMPHASIS * frame[0];
+
+            for (i, slot) in buf.iter_mut().enumerate() {
+                let s = if i < FRAME_LEN {
+                    frame[i] * self.window[i]
+                } else {
+                    0.0
+                };
+                *slot = Complex32::new(s, 0.0);
+            }
+            self.fft.process(&mut buf);
+
+            for (first, weights) in &self.filters {
+                let energy: f32 = weights
+                    .iter()
+                    .zip(&buf[*first..])
+                    .map(|(w, c)| w * c.norm_sqr())
+                    .sum();
+                features.push(energy.max(f32::EPSILON).ln());
+            }
+        }
+
+        for bin in 0..MEL_BINS {
+            let mean = (0..frames)
+                .map(|f| features[f * MEL_BINS + bin])
+                .sum::<f32>()
+                / frames as f32;
+            for f in 0..frames {
+                features[f * MEL_BINS + bin] -= mean;
+            }
+        }
+        features
+    }
+}
+
+/// Online speaker clustering: each embedding joins the closest known voice
+/// when it is similar enough, otherwise it starts a new one.
+pub struct SpeakerClusterer {
+    threshold: f32,
+    max_speakers: usize,
+    /// Running sum of the normalised embeddings of each speaker
+    centroids: Vec<Vec<f32>>,
+}
+
+impl SpeakerClusterer {
+    /// `threshold` is the cosine similarity needed to count as the same voice.
+    pub fn new(threshold: f32, max_speakers: usize) -> Self {
+        Self {
+            threshold,
+            max_speakers: max_speakers.max(1),
+            centroids: Vec::new(),
+        }
+    }
+
+    /// Zero-based speaker index for `embedding`.
+    pub fn assign(&mut self, embedding: &[f32]) -> usize {
+        let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+        let embedding: Vec<f32> = embedding.iter().map(|v| v / norm.max(1e-9)).collect();
+
+        let best = self
+            .centroids
+            .iter()
+            .map(|c| cosine(c, &embedding))
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let speaker = match best {
+            Some((i, similarity)) if similarity >= self.threshold => i,
+            Some((i, _)) if self.centroids.len() >= self.max_speakers => i,
+            _ => {
+                self.centroids.push(vec![0.0; embedding.len()]);
+                self.centroids.len() - 1
+            }
+        };
+        for (c, v) in self.centroids[speaker].iter_mut().zip(&embedding) {
+            *c += v;
+        }
+        speaker
+    }
+
+    pub fn speaker_count(&self) -> usize {
+        self.centroids.len()
+    }
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let na = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+    let nb = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+    dot / (na * nb).max(1e-9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fbank_yields_one_row_per_shift() {
+        let samples: Vec<f32> = (0..16_000).map(|i| (i as f32 * 0.05).sin() * 0.1).collect();
+        let features = Fbank::new().compute(&samples);
+        assert_eq!(features.len(), 98 * MEL_BINS);
+        assert!(features.iter().all(|v| v.is_finite()));
+        assert!(Fbank::new().compute(&samples[..FRAME_LEN - 1]).is_empty());
+    }
+
+    #[test]
+    fn similar_voices_share_a_speaker() {
+        let mut clusterer = SpeakerClusterer::new(0.7, 4);
+        assert_eq!(clusterer.assign(&[1.0, 0.1, 0.0]), 0);
+        assert_eq!(clusterer.assign(&[0.0, 1.0, 0.1]), 1);
+        assert_eq!(clusterer.assign(&[0.9, 0.2, 0.0]), 0);
+        assert_eq!(clusterer.assign(&[0.1, 2.0, 0.0]), 1);
+        assert_eq!(clusterer.speaker_count(), 2);
+    }
+
+    #[test]
+    fn speaker_limit_reuses_the_closest_voice() {
+        let mut clusterer = SpeakerClusterer::new(0.99, 2);
+        clusterer.assign(&[1.0, 0.0, 0.0]);
+        clusterer.assign(&[0.0, 1.0, 0.0]);
+        assert_eq!(clusterer.assign(&[0.2, 1.0, 0.5]), 1);
+        assert_eq!(clusterer.speaker_count(), 2);
+    }
+}
@@ -1,6 +1,82 @@
-use natural::phonetics::soundex;
+use log::warn;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
 use strsim::levenshtein;
 
+mod normalize;
+mod phonetic;
+mod voice_commands;
+
+pub use normalize::{normalize_text, NormalizationOptions};
+pub use phonetic::{
+    double_metaphone, fold_diacritics, scorer_for_language, DoubleMetaphone, LanguagePhonetic,
+    PhoneticScorer, Soundex,
+};
+pub use voice_commands::{
+    apply_voice_commands, VoiceCommand, VoiceCommandOutput, VoiceCommandPhrase, VoiceCommandSet,
+};
+
+/// How a vocabulary replacement is cased in the output
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseRule {
+    /// Follow the case of what was transcribed (all caps, capitalized)
+    #[default]
+    Preserve,
+    /// Always write the entry exactly as configured, e.g. "iPhone"
+    Exact,
+    Upper,
+    Lower,
+}
+
+/// A word or phrase in the custom vocabulary.
+///
+/// `heard` lists explicit spellings that are always replaced with `written`
+/// (e.g. "jay son" -> "JSON"). When `fuzzy` is set, near misses of `written`
+/// are corrected as well, the same way plain custom words are.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Type)]
+pub struct VocabularyEntry {
+    pub written: String,
+    #[serde(default)]
+    pub heard: Vec<String>,
+    #[serde(default)]
+    pub case: CaseRule,
+    #[serde(default)]
+    pub fuzzy: bool,
+}
+
+impl VocabularyEntry {
+    /// Entry for a plain custom word: fuzzy matched, case preserved
+    pub fn fuzzy_word(word: &str) -> Self {
+        Self {
+            written: word.to_string(),
+            heard: Vec::new(),
+            case: CaseRule::Preserve,
+            fuzzy: true,
+        }
+    }
+}
+
+/// A regex replacement applied to the whole transcription after vocabulary
+/// matching. `replacement` may reference capture groups as `$1` or `${name}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct RegexRule {
+    pub pattern: String,
+    pub replacement: String,
+    #[serde(default)]
+    pub case_insensitive: bool,
+}
+
+impl RegexRule {
+    pub fn compile(&self) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(&self.pattern)
+            .case_insensitive(self.case_insensitive)
+            .build()
+    }
+}
+
 /// Applies custom word corrections to transcribed text using fuzzy matching
 ///
 /// This function corrects words in the input text by finding the best matches
@@ -20,77 +96,386 @@ pub fn apply_custom_words(text: &str, custom_words: &[String], threshold: f64) -
         return text.to_string();
     }
 
-    // Pre-compute lowercase versions to avoid repeated allocations
-    let custom_words_lower: Vec<String> = custom_words.iter().map(|w| w.to_lowercase()).collect();
+    let entries: Vec<VocabularyEntry> = custom_words
+        .iter()
+        .map(|word| VocabularyEntry::fuzzy_word(word))
+        .collect();
+    apply_vocabulary(text, &entries, &[], threshold)
+}
+
+/// Applies the custom vocabulary to transcribed text
+///
+/// Builds a one-off [`VocabularyIndex`] with Soundex; callers that run on every
+/// transcription should keep an index around and use
+/// [`apply_vocabulary_index`] instead.
+pub fn apply_vocabulary(
+    text: &str,
+    entries: &[VocabularyEntry],
+    regex_rules: &[RegexRule],
+    threshold: f64,
+) -> String {
+    let index = VocabularyIndex::new(entries, Box::new(Soundex));
+    apply_vocabulary_index(text, &index, regex_rules, threshold)
+}
 
-    let words: Vec<&str> = text.split_whitespace().collect();
-    let mut corrected_words = Vec::new();
+/// Applies an indexed vocabulary to transcribed text
+///
+/// Words are matched over n-gram windows so multi-word phrases can be
+/// corrected as a unit. At each position explicit `heard` spellings win,
+/// otherwise the closest fuzzy entry below `threshold` is used (scored as in
+/// [`apply_custom_words`]). Regex rules run last, in order; invalid patterns
+/// are skipped.
+pub fn apply_vocabulary_index(
+    text: &str,
+    index: &VocabularyIndex,
+    regex_rules: &[RegexRule],
+    threshold: f64,
+) -> String {
+    let text = if index.is_empty() {
+        text.to_string()
+    } else {
+        index.apply(text, threshold)
+    };
 
-    for word in words {
-        let cleaned_word = word
-            .trim_matches(|c: char| !c.is_alphabetic())
-            .to_lowercase();
+    regex_rules
+        .iter()
+        .fold(text, |text, rule| match rule.compile() {
+            Ok(regex) => regex
+                .replace_all(&text, rule.replacement.as_str())
+                .into_owned(),
+            Err(e) => {
+                warn!(
+                    "Skipping invalid vocabulary regex '{}': {}",
+                    rule.pattern, e
+                );
+                text
+            }
+        })
+}
 
-        if cleaned_word.is_empty() {
-            corrected_words.push(word.to_string());
-            continue;
-        }
+/// Lowercased, punctuation-free tokens of a vocabulary phrase
+fn phrase_tokens(phrase: &str) -> Vec<String> {
+    phrase
+        .split_whitespace()
+        .map(clean_word)
+        .filter(|token| !token.is_empty())
+        .collect()
+}
 
-        // Skip extremely long words to avoid performance issues
-        if cleaned_word.len() > 50 {
-            corrected_words.push(word.to_string());
-            continue;
-        }
+fn clean_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
 
-        let mut best_match: Option<&String> = None;
-        let mut best_score = f64::MAX;
+struct IndexedEntry {
+    entry: VocabularyEntry,
+    written_tokens: usize,
+    // Written form with spaces removed, so "post gress" can fuzzy match "postgres"
+    written_joined: String,
+    written_len: usize,
+    keys: Vec<String>,
+}
 
-        for (i, custom_word_lower) in custom_words_lower.iter().enumerate() {
-            // Skip if lengths are too different (optimization)
-            let len_diff = (cleaned_word.len() as i32 - custom_word_lower.len() as i32).abs();
-            if len_diff > 5 {
+/// Vocabulary prepared for matching: phonetic keys and lengths are computed
+/// once, and lookups by key or length narrow each window down to the entries
+/// that could possibly pass the threshold instead of scoring all of them.
+pub struct VocabularyIndex {
+    entries: Vec<IndexedEntry>,
+    heard: HashMap<Vec<String>, usize>,
+    max_heard_tokens: usize,
+    by_key: HashMap<String, Vec<usize>>,
+    by_length: BTreeMap<usize, Vec<usize>>,
+    max_fuzzy_tokens: usize,
+    scorer: Box<dyn PhoneticScorer>,
+}
+
+impl VocabularyIndex {
+    pub fn new(entries: &[VocabularyEntry], scorer: Box<dyn PhoneticScorer>) -> Self {
+        let mut index = Self {
+            entries: Vec::new(),
+            heard: HashMap::new(),
+            max_heard_tokens: 0,
+            by_key: HashMap::new(),
+            by_length: BTreeMap::new(),
+            max_fuzzy_tokens: 0,
+            scorer,
+        };
+
+        for entry in entries {
+            let written = phrase_tokens(&entry.written);
+            if written.is_empty() {
                 continue;
             }
+            let id = index.entries.len();
+
+            for heard in entry.heard.iter().map(|h| phrase_tokens(h)) {
+                if heard.is_empty() {
+                    continue;
+                }
+                index.max_heard_tokens = index.max_heard_tokens.max(heard.len());
+                // The first entry claiming a spelling wins
+                index.heard.entry(heard).or_insert(id);
+            }
 
-            // Calculate Levenshtein distance (normalized by length)
-            let levenshtein_dist = levenshtein(&cleaned_word, custom_word_lower);
-            let max_len = cleaned_word.len().max(custom_word_lower.len()) as f64;
-            let levenshtein_score = if max_len > 0.0 {
-                levenshtein_dist as f64 / max_len
+            let written_joined = written.concat();
+            let written_len = written_joined.chars().count();
+            let keys = if entry.fuzzy {
+                index.max_fuzzy_tokens = index.max_fuzzy_tokens.max(written.len());
+                index.by_length.entry(written_len).or_default().push(id);
+                let keys = index.scorer.keys(&written_joined);
+                for key in &keys {
+                    index.by_key.entry(key.clone()).or_default().push(id);
+                }
+                keys
             } else {
-                1.0
+                Vec::new()
             };
 
-            // Calculate phonetic similarity using Soundex
-            let phonetic_match = soundex(&cleaned_word, custom_word_lower);
+            index.entries.push(IndexedEntry {
+                entry: entry.clone(),
+                written_tokens: written.len(),
+                written_joined,
+                written_len,
+                keys,
+            });
+        }
+
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn scorer_name(&self) -> &'static str {
+        self.scorer.name()
+    }
+
+    fn apply(&self, text: &str, threshold: f64) -> String {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let cleaned: Vec<String> = words.iter().map(|w| clean_word(w)).collect();
+
+        let mut corrected_words = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            if cleaned[i].is_empty() {
+                corrected_words.push(words[i].to_string());
+                i += 1;
+                continue;
+            }
+
+            let matched = self
+                .match_heard(&words, &cleaned, i)
+                .or_else(|| self.match_fuzzy(&words, &cleaned, i, threshold));
+
+            match matched {
+                Some((len, entry)) => {
+                    let window = &words[i..i + len];
+                    let (prefix, _) = extract_punctuation(window[0]);
+                    let (_, suffix) = extract_punctuation(window[len - 1]);
+                    let original: String = cleaned_original(window);
+                    let written = apply_case_rule(&original, &entry.written, entry.case);
+                    corrected_words.push(format!("{}{}{}", prefix, written, suffix));
+                    i += len;
+                }
+                None => {
+                    corrected_words.push(words[i].to_string());
+                    i += 1;
+                }
+            }
+        }
+
+        corrected_words.join(" ")
+    }
+
+    fn match_heard(
+        &self,
+        words: &[&str],
+        cleaned: &[String],
+        start: usize,
+    ) -> Option<(usize, &VocabularyEntry)> {
+        // Longest phrase first so "jay son file" beats "jay son"
+        for len in (1..=self.max_heard_tokens).rev() {
+            if !is_clean_window(words, cleaned, start, len) {
+                continue;
+            }
+            if let Some(&id) = self.heard.get(&cleaned[start..start + len]) {
+                return Some((len, &self.entries[id].entry));
+            }
+        }
+        None
+    }
+
+    fn match_fuzzy(
+        &self,
+        words: &[&str],
+        cleaned: &[String],
+        start: usize,
+        threshold: f64,
+    ) -> Option<(usize, &VocabularyEntry)> {
+        if threshold <= 0.0 {
+            return None;
+        }
+
+        // The transcriber may split a term into one more token than it has
+        // ("post gress" for "Postgres"), so windows go one past the longest entry
+        let mut candidates: Vec<(usize, usize, String, Vec<String>)> = Vec::new();
+        for len in 1..=self.max_fuzzy_tokens + 1 {
+            if !is_clean_window(words, cleaned, start, len) {
+                break;
+            }
+            let candidate = cleaned[start..start + len].concat();
+
+            // Skip extremely long candidates to avoid performance issues
+            if candidate.len() > 50 {
+                break;
+            }
+
+            let keys = self.scorer.keys(&candidate);
+            let mut ids: Vec<usize> = keys
+                .iter()
+                .filter_map(|key| self.by_key.get(key))
+                .flatten()
+                .copied()
+                .collect();
+            let (min_len, max_len) = plausible_lengths(candidate.chars().count(), threshold);
+            ids.extend(
+                self.by_length
+                    .range(min_len..=max_len)
+                    .flat_map(|(_, ids)| ids),
+            );
+
+            for id in ids {
+                let tokens = self.entries[id].written_tokens;
+                if len == tokens || len == tokens + 1 {
+                    candidates.push((id, len, candidate.clone(), keys.clone()));
+                }
+            }
+        }
+
+        // Score in vocabulary order so ties resolve the same way regardless
+        // of which lookup produced a candidate
+        candidates.sort_by_key(|(id, len, _, _)| (*id, *len));
+        candidates.dedup_by_key(|(id, len, _, _)| (*id, *len));
 
-            // Combine scores: favor phonetic matches, but also consider string similarity
-            let combined_score = if phonetic_match {
-                levenshtein_score * 0.3 // Give significant boost to phonetic matches
+        let mut best: Option<(usize, &VocabularyEntry)> = None;
+        let mut best_score = f64::MAX;
+        for (id, len, candidate, keys) in &candidates {
+            let indexed = &self.entries[*id];
+            let score = if *len > indexed.written_tokens {
+                // The extra token is swallowed by the replacement, so the
+                // window has to spell the term out ("post gress"); sounding
+                // alike is not enough. It must also beat matching one token
+                // less and keeping the next word, or "post is" would become
+                // "Postgres".
+                let split_score = similarity_score(candidate, indexed, false);
+                let shorter = cleaned[start..start + indexed.written_tokens].concat();
+                if split_score >= self.score(&shorter, indexed) {
+                    continue;
+                }
+                split_score
             } else {
-                levenshtein_score
+                let phonetic_match = keys.iter().any(|key| indexed.keys.contains(key));
+                similarity_score(candidate, indexed, phonetic_match)
             };
-
-            // Accept if the score is good enough (configurable threshold)
-            if combined_score < threshold && combined_score < best_score {
-                best_match = Some(&custom_words[i]);
-                best_score = combined_score;
+            // Prefer the longer window when scores tie, e.g. an exact phrase
+            if score < threshold
+                && (score < best_score
+                    || (score == best_score && best.is_some_and(|(l, _)| *len > l)))
+            {
+                best = Some((*len, &indexed.entry));
+                best_score = score;
             }
         }
 
-        if let Some(replacement) = best_match {
-            // Preserve the original case pattern as much as possible
-            let corrected = preserve_case_pattern(word, replacement);
+        best
+    }
+
+    fn score(&self, candidate: &str, indexed: &IndexedEntry) -> f64 {
+        let phonetic_match = self
+            .scorer
+            .keys(candidate)
+            .iter()
+            .any(|key| indexed.keys.contains(key));
+        similarity_score(candidate, indexed, phonetic_match)
+    }
+}
+
+/// Range of term lengths (in characters) that could score below `threshold`
+/// against a candidate of `len` characters without a phonetic match. The edit
+/// distance is at least the length difference, so anything outside can't pass.
+fn plausible_lengths(len: usize, threshold: f64) -> (usize, usize) {
+    // Also bounded by the length cut-off in `similarity_score`
+    let (mut min_len, mut max_len) = (len.saturating_sub(5), len + 5);
+    if threshold < 1.0 {
+        min_len = min_len.max((len as f64 * (1.0 - threshold)).floor() as usize);
+        max_len = max_len.min((len as f64 / (1.0 - threshold)).ceil() as usize);
+    }
+    (min_len, max_len)
+}
+
+/// Original letters of a window without punctuation or spaces, used to carry
+/// over the case pattern of what was transcribed
+fn cleaned_original(window: &[&str]) -> String {
+    window
+        .iter()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .collect()
+}
 
-            // Preserve punctuation from original word
-            let (prefix, suffix) = extract_punctuation(word);
-            corrected_words.push(format!("{}{}{}", prefix, corrected, suffix));
-        } else {
-            corrected_words.push(word.to_string());
+/// Whether tokens `start..start + len` can be replaced as one unit. Punctuation
+/// is only allowed before the first and after the last token.
+fn is_clean_window(words: &[&str], cleaned: &[String], start: usize, len: usize) -> bool {
+    if start + len > words.len() {
+        return false;
+    }
+    (start..start + len).all(|k| {
+        if cleaned[k].is_empty() {
+            return false;
         }
+        let (prefix, suffix) = extract_punctuation(words[k]);
+        (k == start || prefix.is_empty()) && (k == start + len - 1 || suffix.is_empty())
+    })
+}
+
+/// Distance between a transcribed candidate and a vocabulary term, from 0.0
+/// (identical) upwards. Phonetic matches get a significant boost.
+fn similarity_score(candidate: &str, term: &IndexedEntry, phonetic_match: bool) -> f64 {
+    let candidate_len = candidate.chars().count();
+
+    // Skip if lengths are too different (optimization)
+    if candidate_len.abs_diff(term.written_len) > 5 {
+        return f64::MAX;
+    }
+
+    // Calculate Levenshtein distance (normalized by length)
+    let levenshtein_dist = levenshtein(candidate, &term.written_joined);
+    let max_len = candidate_len.max(term.written_len) as f64;
+    let levenshtein_score = if max_len > 0.0 {
+        levenshtein_dist as f64 / max_len
+    } else {
+        1.0
+    };
+
+    // Combine scores: favor phonetic matches, but also consider string similarity
+    if phonetic_match {
+        levenshtein_score * 0.3
+    } else {
+        levenshtein_score
     }
+}
 
-    corrected_words.join(" ")
+fn apply_case_rule(original: &str, written: &str, rule: CaseRule) -> String {
+    match rule {
+        CaseRule::Preserve => preserve_case_pattern(original, written),
+        CaseRule::Exact => written.to_string(),
+        CaseRule::Upper => written.to_uppercase(),
+        CaseRule::Lower => written.to_lowercase(),
+    }
 }
 
 /// Preserves the case pattern of the original word when applying a replacement
@@ -110,24 +495,11 @@ fn preserve_case_pattern(original: &str, replacement: &str) -> String {
 
 /// Extracts punctuation prefix and suffix from a word
 fn extract_punctuation(word: &str) -> (&str, &str) {
-    let prefix_end = word.chars().take_while(|c| !c.is_alphabetic()).count();
-    let suffix_start = word
-        .char_indices()
-        .rev()
-        .take_while(|(_, c)| !c.is_alphabetic())
-        .count();
-
-    let prefix = if prefix_end > 0 {
-        &word[..prefix_end]
-    } else {
-        ""
-    };
-
-    let suffix = if suffix_start > 0 {
-        &word[word.len() - suffix_start..]
-    } else {
-        ""
-    };
+    let is_punctuation = |c: char| !c.is_alphanumeric();
+    let rest = word.trim_start_matches(is_punctuation);
+    let prefix = &word[..word.len() - rest.len()];
+    let body = rest.trim_end_matches(is_punctuation);
+    let suffix = &rest[body.len()..];
 
     (prefix, suffix)
 }
@@ -164,6 +536,150 @@ mod tests {
         assert_eq!(extract_punctuation("hello"), ("", ""));
         assert_eq!(extract_punctuation("!hello?"), ("!", "?"));
         assert_eq!(extract_punctuation("...hello..."), ("...", "..."));
+        assert_eq!(extract_punctuation("¿Qué"), ("¿", ""));
+        assert_eq!(extract_punctuation("«post»"), ("«", "»"));
+        assert_eq!(extract_punctuation("café!"), ("", "!"));
+    }
+
+    fn entry(written: &str, heard: &[&str], case: CaseRule, fuzzy: bool) -> VocabularyEntry {
+        VocabularyEntry {
+            written: written.to_string(),
+            heard: heard.iter().map(|h| h.to_string()).collect(),
+            case,
+            fuzzy,
+        }
+    }
+
+    #[test]
+    fn test_vocabulary_heard_replacement() {
+        let entries = vec![entry("JSON", &["jay son"], CaseRule::Exact, false)];
+        let result = apply_vocabulary("Parse the jay son, please", &entries, &[], 0.18);
+        assert_eq!(result, "Parse the JSON, please");
+    }
+
+    #[test]
+    fn test_vocabulary_multi_word_fuzzy_phrase() {
+        let entries = vec![VocabularyEntry::fuzzy_word("Kubernetes operator")];
+        let result = apply_vocabulary("deploy the kubernetes operater now", &entries, &[], 0.18);
+        assert_eq!(result, "deploy the Kubernetes operator now");
+    }
+
+    #[test]
+    fn test_vocabulary_fuzzy_merges_split_word() {
+        let entries = vec![entry("Postgres", &[], CaseRule::Exact, true)];
+        let result = apply_vocabulary("migrate the post gress database", &entries, &[], 0.18);
+        assert_eq!(result, "migrate the Postgres database");
+    }
+
+    #[test]
+    fn test_vocabulary_fuzzy_keeps_following_word() {
+        let entries = vec![entry("Postgres", &[], CaseRule::Exact, true)];
+        let result = apply_vocabulary("post is great", &entries, &[], 0.18);
+        assert_eq!(result, "post is great");
+    }
+
+    #[test]
+    fn test_vocabulary_multibyte_punctuation() {
+        let entries = vec![entry("Postgres", &[], CaseRule::Exact, true)];
+        assert_eq!(
+            apply_vocabulary("¿Qué tal?", &entries, &[], 0.18),
+            "¿Qué tal?"
+        );
+        assert_eq!(
+            apply_vocabulary("«post gress» is great", &entries, &[], 0.18),
+            "«Postgres» is great"
+        );
+    }
+
+    #[test]
+    fn test_vocabulary_window_stops_at_punctuation() {
+        let entries = vec![entry("JSON", &["jay son"], CaseRule::Exact, false)];
+        let result = apply_vocabulary("Thanks jay. Son is here", &entries, &[], 0.18);
+        assert_eq!(result, "Thanks jay. Son is here");
+    }
+
+    #[test]
+    fn test_vocabulary_longest_heard_wins() {
+        let entries = vec![
+            entry("New York", &["new york"], CaseRule::Exact, false),
+            entry("New York City", &["new york city"], CaseRule::Exact, false),
+        ];
+        let result = apply_vocabulary("in new york city today", &entries, &[], 0.18);
+        assert_eq!(result, "in New York City today");
+    }
+
+    #[test]
+    fn test_vocabulary_case_rules() {
+        let cases = [
+            ("SQL", CaseRule::Preserve, "Sql query", "SQL query"),
+            ("sql", CaseRule::Preserve, "Sql query", "Sql query"),
+            ("sql", CaseRule::Exact, "Sql query", "sql query"),
+            ("sql", CaseRule::Upper, "sql query", "SQL query"),
+            ("SQL", CaseRule::Lower, "SQL query", "sql query"),
+        ];
+        for (written, case, input, expected) in cases {
+            let entries = vec![entry(written, &["sql"], case, false)];
+            assert_eq!(apply_vocabulary(input, &entries, &[], 0.18), expected);
+        }
+
+        // Preserve still follows an all-caps transcription
+        let entries = vec![entry("iPhone", &["i phone"], CaseRule::Preserve, false)];
+        assert_eq!(apply_vocabulary("I PHONE", &entries, &[], 0.18), "IPHONE");
+        let entries = vec![entry("iPhone", &["i phone"], CaseRule::Exact, false)];
+        assert_eq!(apply_vocabulary("I PHONE", &entries, &[], 0.18), "iPhone");
+    }
+
+    #[test]
+    fn test_vocabulary_regex_rules() {
+        let rules = vec![
+            RegexRule {
+                pattern: r"\bversion (\d+)".to_string(),
+                replacement: "v$1".to_string(),
+                case_insensitive: true,
+            },
+            RegexRule {
+                pattern: "(unclosed".to_string(),
+                replacement: "ignored".to_string(),
+                case_insensitive: false,
+            },
+        ];
+        let result = apply_vocabulary("Upgrade to Version 2 today", &[], &rules, 0.18);
+        assert_eq!(result, "Upgrade to v2 today");
+        assert!(rules[1].compile().is_err());
+    }
+
+    #[test]
+    fn test_vocabulary_index_uses_language_scorer() {
+        let entries = vec![VocabularyEntry::fuzzy_word("Javier")];
+
+        let spanish = VocabularyIndex::new(&entries, scorer_for_language("es"));
+        assert_eq!(
+            apply_vocabulary_index("hola habier", &spanish, &[], 0.18),
+            "hola Javier"
+        );
+
+        // Soundex keeps H and J apart, so English doesn't make the connection
+        let english = VocabularyIndex::new(&entries, scorer_for_language("en"));
+        assert_eq!(
+            apply_vocabulary_index("hola habier", &english, &[], 0.18),
+            "hola habier"
+        );
+    }
+
+    #[test]
+    fn test_vocabulary_index_accented_terms() {
+        let entries = vec![VocabularyEntry::fuzzy_word("Müller")];
+        let german = VocabularyIndex::new(&entries, scorer_for_language("de"));
+        assert_eq!(
+            apply_vocabulary_index("Herr Mueller kommt", &german, &[], 0.18),
+            "Herr Müller kommt"
+        );
+    }
+
+    #[test]
+    fn test_plausible_lengths() {
+        assert_eq!(plausible_lengths(10, 0.18), (8, 13));
+        assert_eq!(plausible_lengths(3, 1.0), (0, 8));
     }
 
     #[test]
@@ -0,0 +1,1635 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Which inverse text normalization rules to apply. Rules a language has no
+/// word tables for are skipped for that language.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+#[serde(default)]
+pub struct NormalizationOptions {
+    /// "twenty five" -> "25". Single words below ten stay spelled out.
+    pub numbers: bool,
+    /// "twenty first" -> "21st"
+    pub ordinals: bool,
+    /// "five dollars and fifty cents" -> "$5.50"
+    pub currency: bool,
+    /// "twelve percent" -> "12%"
+    pub percentages: bool,
+    /// "March fifth twenty twenty four" -> "March 5, 2024"
+    pub dates: bool,
+    /// "three thirty pm" -> "3:30 PM"
+    pub times: bool,
+    /// Runs of seven or more spoken digits -> "555-123-4567"
+    pub phone_numbers: bool,
+    /// "comma", "question mark", ... -> ",", "?"
+    pub punctuation: bool,
+}
+
+impl Default for NormalizationOptions {
+    fn default() -> Self {
+        Self {
+            numbers: true,
+            ordinals: true,
+            currency: true,
+            percentages: true,
+            dates: true,
+            times: true,
+            phone_numbers: true,
+            punctuation: true,
+        }
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+/* Language tables                                                  */
+/* ──────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberWord {
+    /// 0-19, plus single-word numbers such as Spanish "veinticinco"
+    Small(u64),
+    /// 20, 30, ... 90
+    Tens(u64),
+    /// Absolute hundreds such as Spanish "doscientos"
+    Hundreds(u64),
+    /// Multipliers: hundred, thousand, million, ...
+    Scale(u64),
+}
+
+use NumberWord::{Hundreds, Scale, Small, Tens};
+
+#[derive(Debug, Clone, Copy)]
+struct Mark {
+    symbol: &'static str,
+    /// Attaches to the preceding word without a space
+    glue_left: bool,
+    /// The following word attaches to it without a space
+    glue_right: bool,
+    ends_sentence: bool,
+}
+
+const fn trailing(symbol: &'static str) -> Mark {
+    Mark {
+        symbol,
+        glue_left: true,
+        glue_right: false,
+        ends_sentence: false,
+    }
+}
+
+const fn sentence_end(symbol: &'static str) -> Mark {
+    Mark {
+        symbol,
+        glue_left: true,
+        glue_right: false,
+        ends_sentence: true,
+    }
+}
+
+const fn opening(symbol: &'static str) -> Mark {
+    Mark {
+        symbol,
+        glue_left: false,
+        glue_right: true,
+        ends_sentence: false,
+    }
+}
+
+struct Currency {
+    words: &'static [&'static str],
+    symbol: &'static str,
+    /// "$5" rather than "5 €"
+    prefix: bool,
+    /// Words for the hundredth unit, e.g. "cents"
+    minor_words: &'static [&'static str],
+    /// Symbol for an amount given only in the minor unit, e.g. "50¢"
+    minor_symbol: Option<&'static str>,
+}
+
+struct Lexicon {
+    numbers: &'static [(&'static str, NumberWord)],
+    ordinals: &'static [(&'static str, NumberWord)],
+    /// "and" in "one hundred and five", "und" in "fünfundzwanzig"
+    connectors: &'static [&'static str],
+    /// Words that mean "one" in front of a scale ("a hundred")
+    articles: &'static [&'static str],
+    decimal_words: &'static [&'static str],
+    negative_words: &'static [&'static str],
+    decimal_separator: &'static str,
+    group_separator: &'static str,
+    percent_phrases: &'static [&'static str],
+    percent_suffix: &'static str,
+    currencies: &'static [Currency],
+    punctuation: &'static [(&'static str, Mark)],
+    /// Month names, enabling dates
+    months: &'static [&'static str],
+    /// Scales may stand alone ("tausend" = 1000)
+    bare_scales: bool,
+    /// Units come before tens ("fünf und zwanzig")
+    units_before_tens: bool,
+    /// French counting by twenties ("quatre-vingt-dix")
+    vigesimal: bool,
+    /// Numbers are written as one word ("dreihundertzwölf")
+    compounds: bool,
+    /// "nineteen eighty four" -> 1984
+    spoken_years: bool,
+    /// Clock times and spoken phone numbers
+    clock: bool,
+}
+
+static ENGLISH: Lexicon = Lexicon {
+    numbers: &[
+        ("zero", Small(0)),
+        ("one", Small(1)),
+        ("two", Small(2)),
+        ("three", Small(3)),
+        ("four", Small(4)),
+        ("five", Small(5)),
+        ("six", Small(6)),
+        ("seven", Small(7)),
+        ("eight", Small(8)),
+        ("nine", Small(9)),
+        ("ten", Small(10)),
+        ("eleven", Small(11)),
+        ("twelve", Small(12)),
+        ("thirteen", Small(13)),
+        ("fourteen", Small(14)),
+        ("fifteen", Small(15)),
+        ("sixteen", Small(16)),
+        ("seventeen", Small(17)),
+        ("eighteen", Small(18)),
+        ("nineteen", Small(19)),
+        ("twenty", Tens(20)),
+        ("thirty", Tens(30)),
+        ("forty", Tens(40)),
+        ("fifty", Tens(50)),
+        ("sixty", Tens(60)),
+        ("seventy", Tens(70)),
+        ("eighty", Tens(80)),
+        ("ninety", Tens(90)),
+        ("hundred", Scale(100)),
+        ("thousand", Scale(1_000)),
+        ("million", Scale(1_000_000)),
+        ("billion", Scale(1_000_000_000)),
+        ("trillion", Scale(1_000_000_000_000)),
+    ],
+    ordinals: &[
+        ("first", Small(1)),
+        ("second", Small(2)),
+        ("third", Small(3)),
+        ("fourth", Small(4)),
+        ("fifth", Small(5)),
+        ("sixth", Small(6)),
+        ("seventh", Small(7)),
+        ("eighth", Small(8)),
+        ("ninth", Small(9)),
+        ("tenth", Small(10)),
+        ("eleventh", Small(11)),
+        ("twelfth", Small(12)),
+        ("thirteenth", Small(13)),
+        ("fourteenth", Small(14)),
+        ("fifteenth", Small(15)),
+        ("sixteenth", Small(16)),
+        ("seventeenth", Small(17)),
+        ("eighteenth", Small(18)),
+        ("nineteenth", Small(19)),
+        ("twentieth", Tens(20)),
+        ("thirtieth", Tens(30)),
+        ("fortieth", Tens(40)),
+        ("fiftieth", Tens(50)),
+        ("sixtieth", Tens(60)),
+        ("seventieth", Tens(70)),
+        ("eightieth", Tens(80)),
+        ("ninetieth", Tens(90)),
+        ("hundredth", Scale(100)),
+        ("thousandth", Scale(1_000)),
+        ("millionth", Scale(1_000_000)),
+    ],
+    connectors: &["and"],
+    articles: &["a"],
+    decimal_words: &["point"],
+    negative_words: &["minus", "negative"],
+    decimal_separator: ".",
+    group_separator: ",",
+    percent_phrases: &["percent", "per cent"],
+    percent_suffix: "%",
+    currencies: &[
+        Currency {
+            words: &["dollar", "dollars"],
+            symbol: "$",
+            prefix: true,
+            minor_words: &["cent", "cents"],
+            minor_symbol: Some("¢"),
+        },
+        Currency {
+            words: &["euro", "euros"],
+            symbol: "€",
+            prefix: true,
+            minor_words: &["cent", "cents"],
+            minor_symbol: None,
+        },
+        Currency {
+            words: &["pound", "pounds"],
+            symbol: "£",
+            prefix: true,
+            minor_words: &["pence"],
+            minor_symbol: Some("p"),
+        },
+    ],
+    punctuation: &[
+        ("comma", trailing(",")),
+        ("full stop", sentence_end(".")),
+        ("question mark", sentence_end("?")),
+        ("exclamation mark", sentence_end("!")),
+        ("exclamation point", sentence_end("!")),
+        ("semicolon", trailing(";")),
+        ("open parenthesis", opening("(")),
+        ("close parenthesis", trailing(")")),
+        ("open quote", opening("\"")),
+        ("close quote", trailing("\"")),
+        ("ellipsis", trailing("…")),
+    ],
+    months: &[
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    bare_scales: false,
+    units_before_tens: false,
+    vigesimal: false,
+    compounds: false,
+    spoken_years: true,
+    clock: true,
+};
+
+static GERMAN: Lexicon = Lexicon {
+    numbers: &[
+        ("null", Small(0)),
+        ("eins", Small(1)),
+        ("ein", Small(1)),
+        ("eine", Small(1)),
+        ("zwei", Small(2)),
+        ("drei", Small(3)),
+        ("vier", Small(4)),
+        ("fünf", Small(5)),
+        ("sechs", Small(6)),
+        ("sieben", Small(7)),
+        ("acht", Small(8)),
+        ("neun", Small(9)),
+        ("zehn", Small(10)),
+        ("elf", Small(11)),
+        ("zwölf", Small(12)),
+        ("dreizehn", Small(13)),
+        ("vierzehn", Small(14)),
+        ("fünfzehn", Small(15)),
+        ("sechzehn", Small(16)),
+        ("siebzehn", Small(17)),
+        ("achtzehn", Small(18)),
+        ("neunzehn", Small(19)),
+        ("zwanzig", Tens(20)),
+        ("dreißig", Tens(30)),
+        ("vierzig", Tens(40)),
+        ("fünfzig", Tens(50)),
+        ("sechzig", Tens(60)),
+        ("siebzig", Tens(70)),
+        ("achtzig", Tens(80)),
+        ("neunzig", Tens(90)),
+        ("hundert", Scale(100)),
+        ("tausend", Scale(1_000)),
+        ("million", Scale(1_000_000)),
+        ("millionen", Scale(1_000_000)),
+        ("milliarde", Scale(1_000_000_000)),
+        ("milliarden", Scale(1_000_000_000)),
+    ],
+    ordinals: &[],
+    connectors: &["und"],
+    articles: &[],
+    decimal_words: &["komma"],
+    negative_words: &["minus"],
+    decimal_separator: ",",
+    group_separator: ".",
+    percent_phrases: &["prozent"],
+    percent_suffix: "\u{a0}%",
+    currencies: &[
+        Currency {
+            words: &["euro"],
+            symbol: "€",
+            prefix: false,
+            minor_words: &["cent"],
+            minor_symbol: None,
+        },
+        Currency {
+            words: &["dollar"],
+            symbol: "$",
+            prefix: false,
+            minor_words: &["cent"],
+            minor_symbol: None,
+        },
+    ],
+    punctuation: &[
+        ("komma", trailing(",")),
+        ("punkt", sentence_end(".")),
+        ("fragezeichen", sentence_end("?")),
+        ("ausrufezeichen", sentence_end("!")),
+        ("doppelpunkt", trailing(":")),
+        ("semikolon", trailing(";")),
+        ("klammer auf", opening("(")),
+        ("klammer zu", trailing(")")),
+    ],
+    months: &[],
+    bare_scales: true,
+    units_before_tens: true,
+    vigesimal: false,
+    compounds: true,
+    spoken_years: false,
+    clock: false,
+};
+
+static SPANISH: Lexicon = Lexicon {
+    numbers: &[
+        ("cero", Small(0)),
+        ("uno", Small(1)),
+        ("un", Small(1)),
+        ("una", Small(1)),
+        ("dos", Small(2)),
+        ("tres", Small(3)),
+        ("cuatro", Small(4)),
+        ("cinco", Small(5)),
+        ("seis", Small(6)),
+        ("siete", Small(7)),
+        ("ocho", Small(8)),
+        ("nueve", Small(9)),
+        ("diez", Small(10)),
+        ("once", Small(11)),
+        ("doce", Small(12)),
+        ("trece", Small(13)),
+        ("catorce", Small(14)),
+        ("quince", Small(15)),
+        ("dieciséis", Small(16)),
+        ("diecisiete", Small(17)),
+        ("dieciocho", Small(18)),
+        ("diecinueve", Small(19)),
+        ("veinte", Tens(20)),
+        ("veintiuno", Small(21)),
+        ("veintiún", Small(21)),
+        ("veintidós", Small(22)),
+        ("veintitrés", Small(23)),
+        ("veinticuatro", Small(24)),
+        ("veinticinco", Small(25)),
+        ("veintiséis", Small(26)),
+        ("veintisiete", Small(27)),
+        ("veintiocho", Small(28)),
+        ("veintinueve", Small(29)),
+        ("treinta", Tens(30)),
+        ("cuarenta", Tens(40)),
+        ("cincuenta", Tens(50)),
+        ("sesenta", Tens(60)),
+        ("setenta", Tens(70)),
+        ("ochenta", Tens(80)),
+        ("noventa", Tens(90)),
+        ("cien", Hundreds(100)),
+        ("ciento", Hundreds(100)),
+        ("doscientos", Hundreds(200)),
+        ("doscientas", Hundreds(200)),
+        ("trescientos", Hundreds(300)),
+        ("trescientas", Hundreds(300)),
+        ("cuatrocientos", Hundreds(400)),
+        ("cuatrocientas", Hundreds(400)),
+        ("quinientos", Hundreds(500)),
+        ("quinientas", Hundreds(500)),
+        ("seiscientos", Hundreds(600)),
+        ("seiscientas", Hundreds(600)),
+        ("setecientos", Hundreds(700)),
+        ("setecientas", Hundreds(700)),
+        ("ochocientos", Hundreds(800)),
+        ("ochocientas", Hundreds(800)),
+        ("novecientos", Hundreds(900)),
+        ("novecientas", Hundreds(900)),
+        ("mil", Scale(1_000)),
+        ("millón", Scale(1_000_000)),
+        ("millones", Scale(1_000_000)),
+    ],
+    ordinals: &[],
+    connectors: &["y"],
+    articles: &[],
+    decimal_words: &["coma"],
+    negative_words: &["menos"],
+    decimal_separator: ",",
+    group_separator: ".",
+    percent_phrases: &["por ciento"],
+    percent_suffix: "\u{a0}%",
+    currencies: &[
+        Currency {
+            words: &["euro", "euros"],
+            symbol: "€",
+            prefix: false,
+            minor_words: &["céntimo", "céntimos"],
+            minor_symbol: None,
+        },
+        Currency {
+            words: &["dólar", "dólares"],
+            symbol: "$",
+            prefix: false,
+            minor_words: &["centavo", "centavos"],
+            minor_symbol: None,
+        },
+    ],
+    punctuation: &[
+        ("coma", trailing(",")),
+        ("punto y coma", trailing(";")),
+        ("punto", sentence_end(".")),
+        ("signo de interrogación", sentence_end("?")),
+        ("signo de exclamación", sentence_end("!")),
+    ],
+    months: &[],
+    bare_scales: true,
+    units_before_tens: false,
+    vigesimal: false,
+    compounds: false,
+    spoken_years: false,
+    clock: false,
+};
+
+static FRENCH: Lexicon = Lexicon {
+    numbers: &[
+        ("zéro", Small(0)),
+        ("un", Small(1)),
+        ("une", Small(1)),
+        ("deux", Small(2)),
+        ("trois", Small(3)),
+        ("quatre", Small(4)),
+        ("cinq", Small(5)),
+        ("six", Small(6)),
+        ("sept", Small(7)),
+        ("huit", Small(8)),
+        ("neuf", Small(9)),
+        ("dix", Small(10)),
+        ("onze", Small(11)),
+        ("douze", Small(12)),
+        ("treize", Small(13)),
+        ("quatorze", Small(14)),
+        ("quinze", Small(15)),
+        ("seize", Small(16)),
+        ("vingt", Tens(20)),
+        ("vingts", Tens(20)),
+        ("trente", Tens(30)),
+        ("quarante", Tens(40)),
+        ("cinquante", Tens(50)),
+        ("soixante", Tens(60)),
+        ("cent", Scale(100)),
+        ("cents", Scale(100)),
+        ("mille", Scale(1_000)),
+        ("million", Scale(1_000_000)),
+        ("millions", Scale(1_000_000)),
+        ("milliard", Scale(1_000_000_000)),
+        ("milliards", Scale(1_000_000_000)),
+    ],
+    ordinals: &[],
+    connectors: &["et"],
+    articles: &[],
+    decimal_words: &["virgule"],
+    negative_words: &["moins"],
+    decimal_separator: ",",
+    group_separator: "\u{202f}",
+    percent_phrases: &["pour cent"],
+    percent_suffix: "\u{a0}%",
+    currencies: &[Currency {
+        words: &["euro", "euros"],
+        symbol: "€",
+        prefix: false,
+        minor_words: &["centime", "centimes"],
+        minor_symbol: None,
+    }],
+    punctuation: &[
+        ("point virgule", trailing(";")),
+        ("virgule", trailing(",")),
+        ("point d'interrogation", sentence_end("?")),
+        ("point d'exclamation", sentence_end("!")),
+        ("point", sentence_end(".")),
+        ("ouvrez la parenthèse", opening("(")),
+        ("fermez la parenthèse", trailing(")")),
+    ],
+    months: &[],
+    bare_scales: true,
+    units_before_tens: false,
+    vigesimal: true,
+    compounds: false,
+    spoken_years: false,
+    clock: false,
+};
+
+impl Lexicon {
+    /// Tables for `language` ("en", "de-AT", ...). "auto" uses English since
+    /// that is what most auto-detected dictation turns out to be.
+    fn for_language(language: &str) -> Option<&'static Lexicon> {
+        let code = language
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        match code.as_str() {
+            "en" | "auto" | "" => Some(&ENGLISH),
+            "de" => Some(&GERMAN),
+            "es" => Some(&SPANISH),
+            "fr" => Some(&FRENCH),
+            _ => None,
+        }
+    }
+
+    fn number(&self, word: &str) -> Option<NumberWord> {
+        self.numbers
+            .iter()
+            .find(|(w, _)| *w == word)
+            .map(|(_, n)| *n)
+    }
+
+    fn ordinal(&self, word: &str) -> Option<NumberWord> {
+        self.ordinals
+            .iter()
+            .find(|(w, _)| *w == word)
+            .map(|(_, n)| *n)
+    }
+
+    fn month(&self, token: &Token) -> Option<&'static str> {
+        let month = self
+            .months
+            .iter()
+            .find(|m| m.eq_ignore_ascii_case(&token.core))?;
+        // "may" and "march" are ordinary words unless the transcriber capitalised them
+        if matches!(token.core.as_str(), "may" | "march") && !token.capitalized {
+            return None;
+        }
+        Some(month)
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+/* Tokens                                                           */
+/* ──────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone)]
+struct Token {
+    /// Original text, used when the token is left alone
+    text: String,
+    /// Lowercased word without surrounding punctuation
+    core: String,
+    prefix: String,
+    suffix: String,
+    capitalized: bool,
+}
+
+impl Token {
+    fn new(prefix: &str, core: &str, suffix: &str) -> Self {
+        Self {
+            text: format!("{}{}{}", prefix, core, suffix),
+            core: core.to_lowercase().replace('’', "'"),
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+            capitalized: core.chars().next().is_some_and(char::is_uppercase),
+        }
+    }
+}
+
+fn tokenize(text: &str, lexicon: &Lexicon) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace() {
+        let core = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let start = word.find(core).unwrap_or(0);
+        let prefix = &word[..start];
+        let suffix = &word[start + core.len()..];
+
+        // "twenty-five" and "fünfundzwanzig" become one token per number word,
+        // but only when the parts make up a single number
+        if let Some(parts) = split_number_word(core, lexicon) {
+            let last = parts.len() - 1;
+            for (i, part) in parts.iter().enumerate() {
+                tokens.push(Token::new(
+                    if i == 0 { prefix } else { "" },
+                    part,
+                    if i == last { suffix } else { "" },
+                ));
+            }
+            continue;
+        }
+        tokens.push(Token::new(prefix, core, suffix));
+    }
+    tokens
+}
+
+fn split_number_word(core: &str, lexicon: &Lexicon) -> Option<Vec<String>> {
+    let lower = core.to_lowercase();
+    let parts: Vec<String> = if lower.contains('-') {
+        lower.split('-').map(str::to_string).collect()
+    } else if lexicon.compounds && lexicon.number(&lower).is_none() {
+        split_compound(&lower, lexicon)?
+    } else {
+        return None;
+    };
+    if parts.len() < 2 {
+        return None;
+    }
+
+    let tokens: Vec<Token> = parts.iter().map(|p| Token::new("", p, "")).collect();
+    let parsed = parse_number(&tokens, lexicon, false)?;
+    (parsed.len == tokens.len() && parsed.literal.is_none()).then_some(parts)
+}
+
+/// Splits a compound like "dreihundertfünfundzwanzig" into known number words.
+fn split_compound(word: &str, lexicon: &Lexicon) -> Option<Vec<String>> {
+    if word.is_empty() {
+        return Some(Vec::new());
+    }
+    let pieces = lexicon
+        .numbers
+        .iter()
+        .map(|(w, _)| *w)
+        .chain(lexicon.connectors.iter().copied());
+    let mut candidates: Vec<&str> = pieces.filter(|p| word.starts_with(p)).collect();
+    // Longest first, so "achtzehn" wins over "acht"
+    candidates.sort_by_key(|p| std::cmp::Reverse(p.len()));
+    candidates.into_iter().find_map(|piece| {
+        let mut rest = split_compound(&word[piece.len()..], lexicon)?;
+        rest.insert(0, piece.to_string());
+        Some(rest)
+    })
+}
+
+/// Longest prefix of `tokens` with punctuation only at its outer edges.
+fn clean_window(tokens: &[Token]) -> &[Token] {
+    let mut len = 1;
+    while len < tokens.len() && tokens[len - 1].suffix.is_empty() && tokens[len].prefix.is_empty() {
+        len += 1;
+    }
+    &tokens[..len.min(tokens.len())]
+}
+
+/// Number of tokens matched by a space-separated `phrase`.
+fn match_phrase(tokens: &[Token], phrase: &str) -> Option<usize> {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let candidate = tokens.get(..words.len())?;
+    candidate
+        .iter()
+        .zip(&words)
+        .all(|(token, word)| token.core == *word)
+        .then_some(words.len())
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+/* Number parsing                                                   */
+/* ──────────────────────────────────────────────────────────────── */
+
+#[derive(Debug, Clone, Default)]
+struct ParsedNumber {
+    value: u64,
+    /// Digits after the decimal separator
+    decimals: Option<String>,
+    negative: bool,
+    /// Tokens consumed
+    len: usize,
+    /// Digits the transcriber already wrote, kept as they are
+    literal: Option<String>,
+    ordinal: bool,
+}
+
+impl ParsedNumber {
+    fn is_integer(&self) -> bool {
+        self.decimals.is_none() && !self.negative && !self.ordinal
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Last {
+    Start,
+    Small(u64),
+    Tens(u64),
+    Hundred,
+    Scale,
+}
+
+#[derive(Clone, Copy)]
+struct NumberState<'a> {
+    lexicon: &'a Lexicon,
+    total: u64,
+    current: u64,
+    last: Last,
+    smallest_scale: u64,
+}
+
+impl<'a> NumberState<'a> {
+    fn new(lexicon: &'a Lexicon) -> Self {
+        Self {
+            lexicon,
+            total: 0,
+            current: 0,
+            last: Last::Start,
+            smallest_scale: u64::MAX,
+        }
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+
+    /// Adds `word` to the number if it can continue it.
+    fn accept(&mut self, word: NumberWord, after_connector: bool) -> bool {
+        let lexicon = self.lexicon;
+        match (word, self.last) {
+            (Small(n), Last::Start) if !after_connector => {
+                self.current = n;
+                self.last = Last::Small(n);
+            }
+            (Small(n), Last::Hundred | Last::Scale) if self.current.is_multiple_of(100) => {
+                self.current += n;
+                self.last = Last::Small(n);
+            }
+            (Small(n), Last::Tens(t)) if n > 0 && self.current.is_multiple_of(10) => {
+                // French counts on from sixty and eighty: "soixante-douze"
+                let limit = if lexicon.vigesimal && (t == 60 || t == 80) {
+                    20
+                } else {
+                    10
+                };
+                if n >= limit {
+                    return false;
+                }
+                self.current += n;
+                self.last = Last::Small(n);
+            }
+            // French "dix-sept"
+            (Small(n), Last::Small(10)) if lexicon.vigesimal && n > 0 && n < 10 => {
+                self.current += n;
+                self.last = Last::Small(n);
+            }
+            (Tens(t), Last::Start) if !after_connector => {
+                self.current = t;
+                self.last = Last::Tens(t);
+            }
+            (Tens(t), Last::Hundred | Last::Scale) if self.current.is_multiple_of(100) => {
+                self.current += t;
+                self.last = Last::Tens(t);
+            }
+            // German "fünf und zwanzig"
+            (Tens(t), Last::Small(n))
+                if lexicon.units_before_tens && after_connector && n > 0 && n < 10 =>
+            {
+                self.current += t;
+                self.last = Last::Tens(t);
+            }
+            // French "quatre-vingts"
+            (Tens(20), Last::Small(4)) if lexicon.vigesimal && !after_connector => {
+                self.current += 76;
+                self.last = Last::Tens(80);
+            }
+            (Hundreds(h), Last::Start) if !after_connector => {
+                self.current = h;
+                self.last = Last::Hundred;
+            }
+            (Hundreds(h), Last::Scale) if self.current == 0 => {
+                self.current = h;
+                self.last = Last::Hundred;
+            }
+            (Scale(100), last) if !after_connector => {
+                let allowed = match last {
+                    Last::Start | Last::Scale => lexicon.bare_scales && self.current == 0,
+                    Last::Small(_) | Last::Tens(_) => self.current < 100,
+                    Last::Hundred => false,
+                };
+                if !allowed {
+                    return false;
+                }
+                self.current = self.current.max(1) * 100;
+                self.last = Last::Hundred;
+            }
+            (Scale(scale), last) if !after_connector && scale < self.smallest_scale => {
+                let allowed = match last {
+                    Last::Start => lexicon.bare_scales,
+                    Last::Scale => false,
+                    _ => true,
+                };
+                if !allowed {
+                    return false;
+                }
+                self.total += self.current.max(1) * scale;
+                self.current = 0;
+                self.smallest_scale = scale;
+                self.last = Last::Scale;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Parses the number starting at `tokens[0]`, if there is one.
+fn parse_number(tokens: &[Token], lexicon: &Lexicon, years: bool) -> Option<ParsedNumber> {
+    let mut idx = 0;
+    let negative = tokens.len() > 1 && lexicon.negative_words.contains(&tokens[0].core.as_str());
+    if negative {
+        idx = 1;
+    }
+
+    if let Some(literal) = tokens.get(idx).and_then(|t| literal_number(&t.core)) {
+        return Some(ParsedNumber {
+            value: literal.parse().unwrap_or(0),
+            negative,
+            len: idx + 1,
+            literal: Some(literal),
+            ..Default::default()
+        });
+    }
+
+    let mut state = NumberState::new(lexicon);
+    let mut ordinal = false;
+    let start = idx;
+    while idx < tokens.len() {
+        let core = tokens[idx].core.as_str();
+
+        // "a hundred"
+        if state.last == Last::Start
+            && lexicon.articles.contains(&core)
+            && matches!(
+                tokens.get(idx + 1).and_then(|t| lexicon.number(&t.core)),
+                Some(Scale(_))
+            )
+        {
+            state.current = 1;
+            state.last = Last::Small(1);
+            idx += 1;
+            continue;
+        }
+
+        if let Some(word) = lexicon.ordinal(core) {
+            if state.accept(word, false) {
+                ordinal = true;
+                idx += 1;
+            }
+            break;
+        }
+
+        if let Some(word) = lexicon.number(core) {
+            if !state.accept(word, false) {
+                break;
+            }
+            idx += 1;
+            continue;
+        }
+
+        // A connector only counts when the following word continues the number
+        if lexicon.connectors.contains(&core) && state.last != Last::Start {
+            let next = tokens.get(idx + 1).map(|t| t.core.as_str());
+            let next_word = next.and_then(|n| lexicon.number(n).or_else(|| lexicon.ordinal(n)));
+            let is_ordinal = next.is_some_and(|n| lexicon.ordinal(n).is_some());
+            let allowed = match state.last {
+                Last::Hundred | Last::Scale => true,
+                Last::Tens(_) => true,
+                Last::Small(n) => lexicon.units_before_tens && n < 10,
+                Last::Start => false,
+            };
+            if let Some(word) = next_word.filter(|_| allowed) {
+                let mut trial = state;
+                if trial.accept(word, true) {
+                    state = trial;
+                    idx += 2;
+                    if is_ordinal {
+                        ordinal = true;
+                        break;
+                    }
+                    continue;
+                }
+            }
+        }
+        break;
+    }
+
+    let mut parsed = ParsedNumber {
+        value: state.value(),
+        negative,
+        len: idx,
+        ordinal,
+        ..Default::default()
+    };
+
+    if idx == start {
+        // "point five"
+        let decimals = parse_decimals(&tokens[idx..], lexicon)?;
+        parsed.decimals = Some(decimals.0);
+        parsed.len = idx + decimals.1;
+        return Some(parsed);
+    }
+    if ordinal {
+        return Some(parsed);
+    }
+
+    // "nineteen eighty four", "twenty oh five"
+    if years
+        && lexicon.spoken_years
+        && !negative
+        && idx == start + 1
+        && matches!(parsed.value, 19 | 20)
+    {
+        if let Some((low, len)) = parse_year_low(&tokens[idx..], lexicon) {
+            parsed.value = parsed.value * 100 + low;
+            parsed.len += len;
+            return Some(parsed);
+        }
+    }
+
+    if let Some((decimals, len)) = parse_decimals(&tokens[idx..], lexicon) {
+        parsed.decimals = Some(decimals);
+        parsed.len += len;
+    }
+    Some(parsed)
+}
+
+/// The "eighty four" of "nineteen eighty four".
+fn parse_year_low(tokens: &[Token], lexicon: &Lexicon) -> Option<(u64, usize)> {
+    if tokens.first()?.core == "oh" {
+        return match lexicon.number(&tokens.get(1)?.core)? {
+            Small(n) if (1..10).contains(&n) => Some((n, 2)),
+            _ => None,
+        };
+    }
+    let low = parse_number(tokens, lexicon, false)?;
+    let scaled = tokens[..low.len]
+        .iter()
+        .any(|t| matches!(lexicon.number(&t.core), Some(Scale(_) | Hundreds(_))));
+    (low.is_integer() && low.literal.is_none() && !scaled && (10..100).contains(&low.value))
+        .then_some((low.value, low.len))
+}
+
+/// "point one four" -> ("14", 3)
+fn parse_decimals(tokens: &[Token], lexicon: &Lexicon) -> Option<(String, usize)> {
+    if !lexicon
+        .decimal_words
+        .contains(&tokens.first()?.core.as_str())
+    {
+        return None;
+    }
+    let digits: String = tokens[1..]
+        .iter()
+        .map_while(|t| match lexicon.number(&t.core) {
+            Some(Small(n)) if n < 10 => char::from_digit(n as u32, 10),
+            _ => None,
+        })
+        .collect();
+    (!digits.is_empty()).then(|| {
+        let len = digits.len() + 1;
+        (digits, len)
+    })
+}
+
+/// Digits the transcriber already produced ("42", "3.5", "1,000").
+fn literal_number(core: &str) -> Option<String> {
+    let mut chars = core.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_digit())
+        && core.chars().last().is_some_and(|c| c.is_ascii_digit())
+        && core
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.' || c == ',');
+    valid.then(|| core.to_string())
+}
+
+fn format_number(number: &ParsedNumber, lexicon: &Lexicon) -> String {
+    let mut out = String::new();
+    if number.negative {
+        out.push('-');
+    }
+    match &number.literal {
+        Some(literal) => out.push_str(literal),
+        None => out.push_str(&group_digits(number.value, lexicon.group_separator)),
+    }
+    if let Some(decimals) = &number.decimals {
+        out.push_str(lexicon.decimal_separator);
+        out.push_str(decimals);
+    }
+    out
+}
+
+/// Thousands separators from five digits up, so years stay "2024".
+fn group_digits(value: u64, separator: &str) -> String {
+    let digits = value.to_string();
+    if digits.len() < 5 {
+        return digits;
+    }
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push_str(separator);
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn english_ordinal_suffix(value: u64) -> &'static str {
+    match (value % 100, value % 10) {
+        (11..=13, _) => "th",
+        (_, 1) => "st",
+        (_, 2) => "nd",
+        (_, 3) => "rd",
+        _ => "th",
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+/* Matchers                                                         */
+/* ──────────────────────────────────────────────────────────────── */
+
+enum Piece {
+    Text(String),
+    Mark(Mark),
+}
+
+fn match_punctuation(window: &[Token], lexicon: &Lexicon) -> Option<(usize, Piece)> {
+    // Tables list longer phrases before their prefixes
+    lexicon.punctuation.iter().find_map(|(phrase, mark)| {
+        let len = match_phrase(window, phrase)?;
+        Some((len, Piece::Mark(*mark)))
+    })
+}
+
+fn spoken_digit(core: &str, lexicon: &Lexicon) -> Option<u64> {
+    if core == "oh" {
+        return Some(0);
+    }
+    match lexicon.number(core)? {
+        Small(n) if n < 10 => Some(n),
+        _ => None,
+    }
+}
+
+fn match_phone_number(window: &[Token], lexicon: &Lexicon) -> Option<(usize, Piece)> {
+    let mut digits = String::new();
+    let mut idx = 0;
+    while idx < window.len() {
+        let repeat = match window[idx].core.as_str() {
+            "double" => 2,
+            "triple" => 3,
+            _ => 1,
+        };
+        let digit_idx = if repeat > 1 { idx + 1 } else { idx };
+        let Some(digit) = window
+            .get(digit_idx)
+            .and_then(|t| spoken_digit(&t.core, lexicon))
+        else {
+            break;
+        };
+        for _ in 0..repeat {
+            digits.push(char::from_digit(digit as u32, 10)?);
+        }
+        idx = digit_idx + 1;
+    }
+    if digits.len() < 7 {
+        return None;
+    }
+
+    let formatted = match digits.len() {
+        7 => format!("{}-{}", &digits[..3], &digits[3..]),
+        10 => format!("{}-{}-{}", &digits[..3], &digits[3..6], &digits[6..]),
+        11 if digits.starts_with('1') => {
+            format!("1-{}-{}-{}", &digits[1..4], &digits[4..7], &digits[7..])
+        }
+        _ => digits,
+    };
+    Some((idx, Piece::Text(formatted)))
+}
+
+/// "am", "a.m", or "a m"
+fn match_meridiem(tokens: &[Token]) -> Option<(usize, &'static str)> {
+    let first = tokens.first()?.core.as_str();
+    let single = match first {
+        "am" | "a.m" => Some("AM"),
+        "pm" | "p.m" => Some("PM"),
+        _ => None,
+    };
+    if let Some(marker) = single {
+        return Some((1, marker));
+    }
+    if tokens.get(1)?.core != "m" {
+        return None;
+    }
+    match first {
+        "a" => Some((2, "AM")),
+        "p" => Some((2, "PM")),
+        _ => None,
+    }
+}
+
+fn match_time(window: &[Token], lexicon: &Lexicon) -> Option<(usize, Piece)> {
+    let hour = parse_number(window, lexicon, false)?;
+    if !hour.is_integer() || !is_whole(&hour) || !(1..=12).contains(&hour.value) {
+        return None;
+    }
+    let rest = &window[hour.len..];
+
+    if matches!(
+        rest.first().map(|t| t.core.as_str()),
+        Some("o'clock" | "oclock")
+    ) {
+        return Some((hour.len + 1, Piece::Text(format!("{}:00", hour.value))));
+    }
+
+    let (minutes, minutes_len) = match rest.first().map(|t| t.core.as_str()) {
+        Some("oh") => match rest.get(1).and_then(|t| spoken_digit(&t.core, lexicon)) {
+            Some(m) if m > 0 => (Some(m), 2),
+            _ => return None,
+        },
+        _ => match parse_number(rest, lexicon, false) {
+            Some(m) if m.is_integer() && m.literal.is_none() && (10..60).contains(&m.value) => {
+                (Some(m.value), m.len)
+            }
+            _ => (None, 0),
+        },
+    };
+
+    let (marker_len, marker) = match_meridiem(&rest[minutes_len..])?;
+    let text = match minutes {
+        Some(m) => format!("{}:{:02} {}", hour.value, m, marker),
+        None => format!("{} {}", hour.value, marker),
+    };
+    Some((hour.len + minutes_len + marker_len, Piece::Text(text)))
+}
+
+/// Whole number without separators, as a literal or in words.
+fn is_whole(number: &ParsedNumber) -> bool {
+    number
+        .literal
+        .as_deref()
+        .is_none_or(|l| l.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Day of the month, spoken as "fifth" or "five".
+fn parse_day(tokens: &[Token], lexicon: &Lexicon) -> Option<ParsedNumber> {
+    let day = parse_number(tokens, lexicon, false)?;
+    let valid =
+        day.decimals.is_none() && !day.negative && (1..=31).contains(&day.value) && is_whole(&day);
+    valid.then_some(day)
+}
+
+fn parse_year(tokens: &[Token], lexicon: &Lexicon) -> Option<(u64, usize)> {
+    let year = parse_number(tokens, lexicon, true)?;
+    let valid = year.is_integer() && (1000..3000).contains(&year.value) && is_whole(&year);
+    valid.then_some((year.value, year.len))
+}
+
+fn match_date(window: &[Token], lexicon: &Lexicon) -> Option<(usize, Piece)> {
+    // "March fifth [twenty twenty four]"
+    let (month, day, len) = if let Some(month) = lexicon.month(window.first()?) {
+        let day = parse_day(&window[1..], lexicon)?;
+        let len = 1 + day.len;
+        (month, day, len)
+    } else {
+        // "[the] fifth of March"
+        let start = usize::from(window[0].core == "the");
+        let day = parse_day(window.get(start..)?, lexicon)?;
+        let of = start + day.len;
+        if !day.ordinal || window.get(of)?.core != "of" {
+            return None;
+        }
+        let month = lexicon.month(window.get(of + 1)?)?;
+        (month, day, of + 2)
+    };
+
+    let text = format!("{} {}", month, day.value);
+    match parse_year(&window[len..], lexicon) {
+        Some((year, year_len)) => {
+            Some((len + year_len, Piece::Text(format!("{}, {}", text, year))))
+        }
+        // "April two people came" is not a date; "April second" is
+        None if day.ordinal => Some((len, Piece::Text(text))),
+        None => None,
+    }
+}
+
+fn match_number(
+    window: &[Token],
+    lexicon: &Lexicon,
+    options: &NormalizationOptions,
+) -> Option<(usize, Piece)> {
+    let number = parse_number(window, lexicon, true)?;
+    let rest = &window[number.len..];
+
+    if options.percentages && !number.ordinal {
+        if let Some(len) = lexicon
+            .percent_phrases
+            .iter()
+            .find_map(|phrase| match_phrase(rest, phrase))
+        {
+            let text = format!(
+                "{}{}",
+                format_number(&number, lexicon),
+                lexicon.percent_suffix
+            );
+            return Some((number.len + len, Piece::Text(text)));
+        }
+    }
+
+    if options.currency && !number.ordinal {
+        if let Some(result) = match_currency(&number, rest, lexicon) {
+            return Some(result);
+        }
+    }
+
+    // Single words below ten read better spelled out ("two cats", "first of all")
+    let spelled_out = number.literal.is_none()
+        && number.len == 1
+        && number.value < 10
+        && number.decimals.is_none();
+
+    if number.ordinal {
+        if !options.ordinals || spelled_out {
+            return None;
+        }
+        let text = format!("{}{}", number.value, english_ordinal_suffix(number.value));
+        return Some((number.len, Piece::Text(text)));
+    }
+
+    if !options.numbers || spelled_out || number.literal.is_some() {
+        return None;
+    }
+    Some((number.len, Piece::Text(format_number(&number, lexicon))))
+}
+
+fn match_currency(
+    number: &ParsedNumber,
+    rest: &[Token],
+    lexicon: &Lexicon,
+) -> Option<(usize, Piece)> {
+    let word = rest.first()?.core.as_str();
+
+    // "fifty cents"
+    if let Some(currency) = lexicon
+        .currencies
+        .iter()
+        .find(|c| c.minor_words.contains(&word) && c.minor_symbol.is_some())
+    {
+        if !lexicon.currencies.iter().any(|c| c.words.contains(&word)) && number.is_integer() {
+            let text = format!(
+                "{}{}",
+                number.value,
+                currency.minor_symbol.unwrap_or_default()
+            );
+            return Some((number.len + 1, Piece::Text(text)));
+        }
+    }
+
+    let currency = lexicon
+        .currencies
+        .iter()
+        .find(|c| c.words.contains(&word))?;
+    let mut len = number.len + 1;
+    let mut amount = format_number(number, lexicon);
+
+    // "and fifty cents"
+    let after = &rest[1..];
+    let minor_start = usize::from(
+        after
+            .first()
+            .is_some_and(|t| lexicon.connectors.contains(&t.core.as_str())),
+    );
+    if number.decimals.is_none() {
+        if let Some(minor) = after
+            .get(minor_start..)
+            .and_then(|t| parse_number(t, lexicon, false))
+        {
+            let minor_word = after.get(minor_start + minor.len).map(|t| t.core.as_str());
+            if minor.is_integer()
+                && minor.value < 100
+                && minor_word.is_some_and(|w| currency.minor_words.contains(&w))
+            {
+                amount = format!("{}{}{:02}", amount, lexicon.decimal_separator, minor.value);
+                len += minor_start + minor.len + 1;
+            }
+        }
+    }
+
+    let text = if currency.prefix {
+        if let Some(unsigned) = amount.strip_prefix('-') {
+            format!("-{}{}", currency.symbol, unsigned)
+        } else {
+            format!("{}{}", currency.symbol, amount)
+        }
+    } else {
+        format!("{}\u{a0}{}", amount, currency.symbol)
+    };
+    Some((len, Piece::Text(text)))
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+/* Driver                                                           */
+/* ──────────────────────────────────────────────────────────────── */
+
+#[derive(Default)]
+struct Output {
+    text: String,
+    glue_next: bool,
+    capitalize_next: bool,
+}
+
+impl Output {
+    fn push_text(&mut self, text: &str) {
+        if !self.text.is_empty() && !self.glue_next {
+            self.text.push(' ');
+        }
+        if self.capitalize_next {
+            self.text.push_str(&capitalize(text));
+        } else {
+            self.text.push_str(text);
+        }
+        self.glue_next = false;
+        self.capitalize_next = false;
+    }
+
+    fn push_mark(&mut self, mark: Mark) {
+        if !self.text.is_empty() && !mark.glue_left && !self.glue_next {
+            self.text.push(' ');
+        }
+        self.text.push_str(mark.symbol);
+        self.glue_next = mark.glue_right;
+        self.capitalize_next = mark.ends_sentence;
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    match text.char_indices().find(|(_, c)| c.is_alphabetic()) {
+        Some((idx, c)) => {
+            let mut out = text[..idx].to_string();
+            out.extend(c.to_uppercase());
+            out.push_str(&text[idx + c.len_utf8()..]);
+            out
+        }
+        None => text.to_string(),
+    }
+}
+
+/// Inverse text normalization: rewrites spoken-form numbers, dates, times,
+/// money, percentages, phone numbers and punctuation into written form,
+/// e.g. "twenty five percent" -> "25%".
+///
+/// `language` selects the word tables ("en", "de", "es", "fr"; "auto" is
+/// treated as English). Other languages are returned unchanged. Dates, clock
+/// times, ordinals and phone numbers are currently English-only.
+pub fn normalize_text(text: &str, language: &str, options: &NormalizationOptions) -> String {
+    let Some(lexicon) = Lexicon::for_language(language) else {
+        return text.to_string();
+    };
+    let tokens = tokenize(text, lexicon);
+    let mut out = Output::default();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let window = clean_window(&tokens[i..]);
+        let matched = None
+            .or_else(|| {
+                options
+                    .punctuation
+                    .then(|| match_punctuation(window, lexicon))
+                    .flatten()
+            })
+            .or_else(|| {
+                (options.phone_numbers && lexicon.clock)
+                    .then(|| match_phone_number(window, lexicon))
+                    .flatten()
+            })
+            .or_else(|| {
+                (options.times && lexicon.clock)
+                    .then(|| match_time(window, lexicon))
+                    .flatten()
+            })
+            .or_else(|| {
+                (options.dates && !lexicon.months.is_empty())
+                    .then(|| match_date(window, lexicon))
+                    .flatten()
+            })
+            .or_else(|| match_number(window, lexicon, options));
+
+        match matched {
+            Some((len, Piece::Mark(mark))) => {
+                // The transcriber's own punctuation around a spoken mark is dropped
+                out.push_mark(mark);
+                i += len;
+            }
+            Some((len, Piece::Text(text))) => {
+                let first = &tokens[i];
+                let last = &tokens[i + len - 1];
+                let mut suffix = last.suffix.as_str();
+                // The abbreviation dot of "p.m." is part of the marker we replaced
+                if matches!(last.core.as_str(), "a.m" | "p.m") {
+                    suffix = suffix.strip_prefix('.').unwrap_or(suffix);
+                }
+                out.push_text(&format!("{}{}{}", first.prefix, text, suffix));
+                i += len;
+            }
+            None => {
+                out.push_text(&tokens[i].text);
+                i += 1;
+            }
+        }
+    }
+    out.text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn en(text: &str) -> String {
+        normalize_text(text, "en", &NormalizationOptions::default())
+    }
+
+    #[test]
+    fn test_cardinals() {
+        assert_eq!(en("twenty five people"), "25 people");
+        assert_eq!(en("one hundred and five"), "105");
+        assert_eq!(en("three thousand four hundred"), "3400");
+        assert_eq!(en("twelve thousand five hundred"), "12,500");
+        assert_eq!(en("two million three hundred thousand"), "2,300,000");
+        assert_eq!(en("a hundred times"), "100 times");
+        assert_eq!(en("eleven"), "11");
+    }
+
+    #[test]
+    fn test_small_numbers_stay_spelled_out() {
+        assert_eq!(en("I have two cats"), "I have two cats");
+        assert_eq!(en("the one I want"), "the one I want");
+        assert_eq!(en("first of all"), "first of all");
+        // Bare scales are words in English
+        assert_eq!(en("thousand island dressing"), "thousand island dressing");
+    }
+
+    #[test]
+    fn test_separate_numbers_are_not_merged() {
+        assert_eq!(en("one two"), "one two");
+        assert_eq!(en("twenty, thirty"), "20, 30");
+        assert_eq!(en("one and two"), "one and two");
+    }
+
+    #[test]
+    fn test_hyphenated_numbers() {
+        assert_eq!(en("Twenty-five apples"), "25 apples");
+        assert_eq!(en("a well-known fact"), "a well-known fact");
+    }
+
+    #[test]
+    fn test_decimals_and_negatives() {
+        assert_eq!(en("three point one four"), "3.14");
+        assert_eq!(en("point five"), "0.5");
+        assert_eq!(en("minus five degrees"), "-5 degrees");
+        assert_eq!(en("the point is"), "the point is");
+        assert_eq!(en("minus"), "minus");
+    }
+
+    #[test]
+    fn test_ordinals() {
+        assert_eq!(en("the twenty first century"), "the 21st century");
+        assert_eq!(en("tenth place"), "10th place");
+        assert_eq!(en("one hundred and second"), "102nd");
+        assert_eq!(en("eleventh twelfth thirteenth"), "11th 12th 13th");
+        assert_eq!(en("the second time"), "the second time");
+    }
+
+    #[test]
+    fn test_percentages() {
+        assert_eq!(en("twenty five percent"), "25%");
+        assert_eq!(en("five per cent."), "5%.");
+        assert_eq!(en("3.5 percent"), "3.5%");
+        assert_eq!(en("ninety nine point nine percent"), "99.9%");
+    }
+
+    #[test]
+    fn test_currency() {
+        assert_eq!(en("five dollars and fifty cents"), "$5.50");
+        assert_eq!(en("it costs twenty euros"), "it costs €20");
+        assert_eq!(en("fifty cents"), "50¢");
+        assert_eq!(en("a million dollars"), "$1,000,000");
+        assert_eq!(en("ten pounds five pence"), "£10.05");
+        assert_eq!(en("5 dollars"), "$5");
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(en("on March fifth twenty twenty four"), "on March 5, 2024");
+        assert_eq!(en("the first of May"), "May 1");
+        assert_eq!(en("January 3rd, nineteen ninety nine"), "January 3rd, 1999");
+        assert_eq!(en("July twenty second"), "July 22");
+        assert_eq!(en("June first two thousand and five"), "June 1, 2005");
+        // Lower-case "may" is the verb
+        assert_eq!(en("you may first ask"), "you may first ask");
+        assert_eq!(en("in April two people came"), "in April two people came");
+        assert_eq!(en("May five twenty twenty"), "May 5, 2020");
+    }
+
+    #[test]
+    fn test_years() {
+        assert_eq!(en("in nineteen eighty four"), "in 1984");
+        assert_eq!(en("twenty twenty"), "2020");
+        assert_eq!(en("nineteen oh five"), "1905");
+        assert_eq!(en("twenty five"), "25");
+    }
+
+    #[test]
+    fn test_times() {
+        assert_eq!(en("three thirty pm"), "3:30 PM");
+        assert_eq!(en("at seven o'clock"), "at 7:00");
+        assert_eq!(en("ten oh five a.m. sharp"), "10:05 AM sharp");
+        assert_eq!(en("meet at 3 p.m."), "meet at 3 PM");
+        assert_eq!(en("twelve fifteen p m"), "12:15 PM");
+    }
+
+    #[test]
+    fn test_phone_numbers() {
+        assert_eq!(
+            en("call five five five one two three four five six seven"),
+            "call 555-123-4567"
+        );
+        assert_eq!(en("double five triple two oh one"), "552-2201");
+        assert_eq!(en("one two three"), "one two three");
+    }
+
+    #[test]
+    fn test_spoken_punctuation() {
+        assert_eq!(
+            en("hello comma how are you question mark"),
+            "hello, how are you?"
+        );
+        assert_eq!(en("done full stop next one"), "done. Next one");
+        assert_eq!(en("he said open quote hi close quote"), "he said \"hi\"");
+        assert_eq!(en("wait exclamation point"), "wait!");
+    }
+
+    #[test]
+    fn test_options_disable_rules() {
+        let options = NormalizationOptions {
+            numbers: false,
+            punctuation: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            normalize_text("twenty five people comma twenty percent", "en", &options),
+            "twenty five people comma 20%"
+        );
+    }
+
+    #[test]
+    fn test_auto_and_unsupported_languages() {
+        let options = NormalizationOptions::default();
+        assert_eq!(normalize_text("twenty five", "auto", &options), "25");
+        assert_eq!(normalize_text("twenty five", "ja", &options), "twenty five");
+    }
+
+    #[test]
+    fn test_german() {
+        let de = |t: &str| normalize_text(t, "de", &NormalizationOptions::default());
+        assert_eq!(de("fünfundzwanzig Prozent"), "25\u{a0}%");
+        assert_eq!(de("dreihundert Gäste"), "300 Gäste");
+        assert_eq!(de("zweitausendvierundzwanzig"), "2024");
+        assert_eq!(de("zwei komma fünf"), "2,5");
+        assert_eq!(de("zehn Euro"), "10\u{a0}€");
+        assert_eq!(de("fünfzigtausend"), "50.000");
+        assert_eq!(de("Hallo komma Welt"), "Hallo, Welt");
+        assert_eq!(de("ein Haus"), "ein Haus");
+        assert_eq!(de("Achtung"), "Achtung");
+    }
+
+    #[test]
+    fn test_spanish() {
+        let es = |t: &str| normalize_text(t, "es", &NormalizationOptions::default());
+        assert_eq!(es("treinta y cinco por ciento"), "35\u{a0}%");
+        assert_eq!(es("dos mil veinticuatro"), "2024");
+        assert_eq!(es("ciento cinco"), "105");
+        assert_eq!(es("doscientos mil"), "200.000");
+        assert_eq!(es("hola coma qué tal"), "hola, qué tal");
+        assert_eq!(es("sí punto y coma no"), "sí; no");
+    }
+
+    #[test]
+    fn test_french() {
+        let fr = |t: &str| normalize_text(t, "fr", &NormalizationOptions::default());
+        assert_eq!(fr("quatre-vingt-dix-neuf"), "99");
+        assert_eq!(fr("soixante et onze"), "71");
+        assert_eq!(fr("quatre-vingts"), "80");
+        assert_eq!(fr("dix-sept"), "17");
+        assert_eq!(fr("soixante-dix-sept"), "77");
+        assert_eq!(fr("vingt et un"), "21");
+        assert_eq!(fr("vingt pour cent"), "20\u{a0}%");
+        assert_eq!(fr("deux virgule cinq"), "2,5");
+        assert_eq!(fr("deux mille vingt-quatre"), "2024");
+        assert_eq!(fr("cent mille euros"), "100\u{202f}000\u{a0}€");
+        assert_eq!(fr("c'est neuf"), "c'est neuf");
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits(2024, ","), "2024");
+        assert_eq!(group_digits(12500, ","), "12,500");
+        assert_eq!(group_digits(1_234_567, "."), "1.234.567");
+    }
+}
@@ -0,0 +1,831 @@
+/// Encodes words into phonetic keys; two words sound alike when they share a key.
+///
+/// Implementations must be cheap to call repeatedly: vocabulary keys are
+/// computed once when the index is built, transcribed words on every match.
+pub trait PhoneticScorer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Phonetic keys for `word`. An empty list means the word can't be encoded
+    /// and never matches phonetically.
+    fn keys(&self, word: &str) -> Vec<String>;
+
+    fn sounds_alike(&self, a: &str, b: &str) -> bool {
+        let b_keys = self.keys(b);
+        self.keys(a).iter().any(|key| b_keys.contains(key))
+    }
+}
+
+/// Pick the scorer for a transcription language ("en", "de-CH", "auto", ...).
+///
+/// English and automatic detection keep Soundex, which the vocabulary
+/// matching was tuned with. Everything else gets language specific spelling
+/// rules on top of Double Metaphone.
+pub fn scorer_for_language(language: &str) -> Box<dyn PhoneticScorer> {
+    let code = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match code.as_str() {
+        "" | "auto" | "en" => Box::new(Soundex),
+        _ => Box::new(LanguagePhonetic::new(&code)),
+    }
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// American Soundex, e.g. "Robert" and "Rupert" are both R163.
+pub struct Soundex;
+
+impl PhoneticScorer for Soundex {
+    fn name(&self) -> &'static str {
+        "soundex"
+    }
+
+    fn keys(&self, word: &str) -> Vec<String> {
+        soundex(word).into_iter().collect()
+    }
+}
+
+fn soundex(word: &str) -> Option<String> {
+    let letters: Vec<char> = fold_diacritics(&word.to_lowercase())
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let (&first, rest) = letters.split_first()?;
+
+    let digit = |c: char| match c {
+        'B' | 'F' | 'P' | 'V' => '1',
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => '2',
+        'D' | 'T' => '3',
+        'L' => '4',
+        'M' | 'N' => '5',
+        'R' => '6',
+        _ => '0',
+    };
+
+    let mut code = String::from(first);
+    let mut last = digit(first);
+    for &c in rest {
+        let d = digit(c);
+        if d != '0' && d != last {
+            code.push(d);
+        }
+        // H and W don't separate letters with the same code
+        if c != 'H' && c != 'W' {
+            last = d;
+        }
+    }
+    while code.len() < 4 {
+        code.push('0');
+    }
+    code.truncate(4);
+    Some(code)
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Lawrence Philips' Double Metaphone. Produces a primary and an alternate
+/// key so both e.g. the Germanic and the anglicised reading of a name match.
+pub struct DoubleMetaphone;
+
+impl PhoneticScorer for DoubleMetaphone {
+    fn name(&self) -> &'static str {
+        "double_metaphone"
+    }
+
+    fn keys(&self, word: &str) -> Vec<String> {
+        let (primary, alternate) = double_metaphone(word);
+        let mut keys = Vec::new();
+        if !primary.is_empty() {
+            keys.push(primary);
+        }
+        if !alternate.is_empty() && !keys.contains(&alternate) {
+            keys.push(alternate);
+        }
+        keys
+    }
+}
+
+const METAPHONE_MAX_LEN: usize = 4;
+
+struct MetaphoneBuilder {
+    primary: String,
+    alternate: String,
+}
+
+impl MetaphoneBuilder {
+    fn add(&mut self, code: &str) {
+        self.primary.push_str(code);
+        self.alternate.push_str(code);
+    }
+
+    fn add_alt(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    fn done(&self) -> bool {
+        self.primary.len() >= METAPHONE_MAX_LEN && self.alternate.len() >= METAPHONE_MAX_LEN
+    }
+}
+
+/// Primary and alternate Double Metaphone codes for `word`
+pub fn double_metaphone(word: &str) -> (String, String) {
+    let w: Vec<char> = fold_diacritics(&word.to_lowercase())
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let len = w.len() as isize;
+    let last = len - 1;
+    let mut out = MetaphoneBuilder {
+        primary: String::new(),
+        alternate: String::new(),
+    };
+    if w.is_empty() {
+        return (out.primary, out.alternate);
+    }
+
+    let at = |i: isize| -> char {
+        if i < 0 || i >= len {
+            '\0'
+        } else {
+            w[i as usize]
+        }
+    };
+    let matches = |start: isize, options: &[&str]| -> bool {
+        options.iter().any(|option| {
+            option
+                .chars()
+                .enumerate()
+                .all(|(k, c)| at(start + k as isize) == c)
+        })
+    };
+    let is_vowel = |i: isize| matches!(at(i), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y');
+    let slavo_germanic =
+        w.contains(&'W') || w.contains(&'K') || (0..len).any(|i| matches(i, &["CZ", "WITZ"]));
+
+    let mut i: isize = 0;
+    // Initial letters that aren't pronounced
+    if matches(0, &["GN", "KN", "PN", "WR", "PS"]) {
+        i = 1;
+    }
+    // Initial X is pronounced Z, e.g. "Xavier"
+    if at(0) == 'X' {
+        out.add("S");
+        i = 1;
+    }
+
+    while i < len && !out.done() {
+        match at(i) {
+            'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                if i == 0 {
+                    out.add("A");
+                }
+                i += 1;
+            }
+            'B' => {
+                out.add("P");
+                i += if at(i + 1) == 'B' { 2 } else { 1 };
+            }
+            'C' => {
+                if i > 1
+                    && !is_vowel(i - 2)
+                    && matches(i - 1, &["ACH"])
+                    && at(i + 2) != 'I'
+                    && (at(i + 2) != 'E' || matches(i - 2, &["BACHER", "MACHER"]))
+                {
+                    out.add("K");
+                    i += 2;
+                } else if i == 0 && matches(i, &["CAESAR"]) {
+                    out.add("S");
+                    i += 2;
+                } else if matches(i, &["CHIA"]) {
+                    out.add("K");
+                    i += 2;
+                } else if matches(i, &["CH"]) {
+                    if i > 0 && matches(i, &["CHAE"]) {
+                        out.add_alt("K", "X");
+                    } else if (i == 0
+                        && matches(i + 1, &["HARAC", "HARIS", "HOR", "HYM", "HIA", "HEM"])
+                        && !matches(0, &["CHORE"]))
+                        || matches(0, &["SCH"])
+                        || matches(i - 2, &["ORCHES", "ARCHIT", "ORCHID"])
+                        || matches(i + 2, &["T", "S"])
+                        || ((matches(i - 1, &["A", "O", "U", "E"]) || i == 0)
+                            && (matches(i + 2, &["L", "R", "N", "M", "B", "H", "F", "V", "W"])
+                                || i + 2 >= len))
+                    {
+                        out.add("K");
+                    } else if i > 0 {
+                        if matches(0, &["MC"]) {
+                            out.add("K");
+                        } else {
+                            out.add_alt("X", "K");
+                        }
+                    } else {
+                        out.add("X");
+                    }
+                    i += 2;
+                } else if matches(i, &["CZ"]) && !matches(i - 2, &["WICZ"]) {
+                    out.add_alt("S", "X");
+                    i += 2;
+                } else if matches(i + 1, &["CIA"]) {
+                    out.add("X");
+                    i += 3;
+                } else if matches(i, &["CC"]) && !(i == 1 && at(0) == 'M') {
+                    if matches(i + 2, &["I", "E", "H"]) && !matches(i + 2, &["HU"]) {
+                        if (i == 1 && at(0) == 'A') || matches(i - 1, &["UCCEE", "UCCES"]) {
+                            out.add("KS");
+                        } else {
+                            out.add("X");
+                        }
+                        i += 3;
+                    } else {
+                        out.add("K");
+                        i += 2;
+                    }
+                } else if matches(i, &["CK", "CG", "CQ"]) {
+                    out.add("K");
+                    i += 2;
+                } else if matches(i, &["CI", "CE", "CY"]) {
+                    if matches(i, &["CIO", "CIE", "CIA"]) {
+                        out.add_alt("S", "X");
+                    } else {
+                        out.add("S");
+                    }
+                    i += 2;
+                } else {
+                    out.add("K");
+                    i += if matches(i + 1, &["C", "K", "Q"]) && !matches(i + 1, &["CE", "CI"]) {
+                        2
+                    } else {
+                        1
+                    };
+                }
+            }
+            'D' => {
+                if matches(i, &["DG"]) {
+                    if matches(i + 2, &["I", "E", "Y"]) {
+                        out.add("J");
+                        i += 3;
+                    } else {
+                        out.add("TK");
+                        i += 2;
+                    }
+                } else {
+                    out.add("T");
+                    i += if matches(i, &["DT", "DD"]) { 2 } else { 1 };
+                }
+            }
+            'F' => {
+                out.add("F");
+                i += if at(i + 1) == 'F' { 2 } else { 1 };
+            }
+            'G' => {
+                if at(i + 1) == 'H' {
+                    if i > 0 && !is_vowel(i - 1) {
+                        out.add("K");
+                    } else if i == 0 {
+                        out.add(if at(i + 2) == 'I' { "J" } else { "K" });
+                    } else if (i > 1 && matches(i - 2, &["B", "H", "D"]))
+                        || (i > 2 && matches(i - 3, &["B", "H", "D"]))
+                        || (i > 3 && matches(i - 4, &["B", "H"]))
+                    {
+                        // e.g. "Hugh", "bough": silent
+                    } else if i > 2
+                        && at(i - 1) == 'U'
+                        && matches(i - 3, &["C", "G", "L", "R", "T"])
+                    {
+                        // e.g. "laugh", "tough"
+                        out.add("F");
+                    } else if at(i - 1) != 'I' {
+                        out.add("K");
+                    }
+                    i += 2;
+                } else if at(i + 1) == 'N' {
+                    if i == 1 && is_vowel(0) && !slavo_germanic {
+                        out.add_alt("KN", "N");
+                    } else if !matches(i + 2, &["EY"]) && !slavo_germanic {
+                        out.add_alt("N", "KN");
+                    } else {
+                        out.add("KN");
+                    }
+                    i += 2;
+                } else if matches(i + 1, &["LI"]) && !slavo_germanic {
+                    out.add_alt("KL", "L");
+                    i += 2;
+                } else if (i == 0
+                    && (at(i + 1) == 'Y'
+                        || matches(
+                            i + 1,
+                            &[
+                                "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+                            ],
+                        )))
+                    || ((matches(i + 1, &["ER"]) || at(i + 1) == 'Y')
+                        && !matches(0, &["DANGER", "RANGER", "MANGER"])
+                        && !matches(i - 1, &["E", "I", "RGY", "OGY"]))
+                {
+                    out.add_alt("K", "J");
+                    i += 2;
+                } else if matches(i + 1, &["E", "I", "Y"]) || matches(i - 1, &["AGGI", "OGGI"]) {
+                    if matches(0, &["SCH"]) || matches(i + 1, &["ET"]) {
+                        out.add("K");
+                    } else if matches(i + 1, &["IER"]) && i + 3 == len {
+                        out.add("J");
+                    } else {
+                        out.add_alt("J", "K");
+                    }
+                    i += 2;
+                } else {
+                    out.add("K");
+                    i += if at(i + 1) == 'G' { 2 } else { 1 };
+                }
+            }
+            'H' => {
+                // Only pronounced before a vowel and not after a consonant
+                if (i == 0 || is_vowel(i - 1)) && is_vowel(i + 1) {
+                    out.add("H");
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            'J' => {
+                if matches(i, &["JOSE"]) {
+                    if i == 0 && i + 4 == len {
+                        out.add("H");
+                    } else {
+                        out.add_alt("J", "H");
+                    }
+                } else if i == 0 {
+                    out.add_alt("J", "A");
+                } else if is_vowel(i - 1) && !slavo_germanic && matches(i + 1, &["A", "O"]) {
+                    out.add_alt("J", "H");
+                } else if i == last {
+                    out.add_alt("J", "");
+                } else if !matches(i + 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+                    && !matches(i - 1, &["S", "K", "L"])
+                {
+                    out.add("J");
+                }
+                i += if at(i + 1) == 'J' { 2 } else { 1 };
+            }
+            'K' => {
+                out.add("K");
+                i += if at(i + 1) == 'K' { 2 } else { 1 };
+            }
+            'L' => {
+                if at(i + 1) == 'L' {
+                    // Spanish "-illo", "-illa", "-alle"
+                    if (i == len - 3 && matches(i - 1, &["ILLO", "ILLA", "ALLE"]))
+                        || ((matches(last - 1, &["AS", "OS"]) || matches(last, &["A", "O"]))
+                            && matches(i - 1, &["ALLE"]))
+                    {
+                        out.add_alt("L", "");
+                    } else {
+                        out.add("L");
+                    }
+                    i += 2;
+                } else {
+                    out.add("L");
+                    i += 1;
+                }
+            }
+            'M' => {
+                out.add("M");
+                i += if (matches(i - 1, &["UMB"]) && (i + 1 == last || matches(i + 2, &["ER"])))
+                    || at(i + 1) == 'M'
+                {
+                    2
+                } else {
+                    1
+                };
+            }
+            'N' => {
+                out.add("N");
+                i += if at(i + 1) == 'N' { 2 } else { 1 };
+            }
+            'P' => {
+                if at(i + 1) == 'H' {
+                    out.add("F");
+                    i += 2;
+                } else {
+                    out.add("P");
+                    i += if matches(i + 1, &["P", "B"]) { 2 } else { 1 };
+                }
+            }
+            'Q' => {
+                out.add("K");
+                i += if at(i + 1) == 'Q' { 2 } else { 1 };
+            }
+            'R' => {
+                // French "-ier" endings, e.g. "Rogier"
+                if i == last
+                    && !slavo_germanic
+                    && matches(i - 2, &["IE"])
+                    && !matches(i - 4, &["ME", "MA"])
+                {
+                    out.add_alt("", "R");
+                } else {
+                    out.add("R");
+                }
+                i += if at(i + 1) == 'R' { 2 } else { 1 };
+            }
+            'S' => {
+                if matches(i - 1, &["ISL", "YSL"]) {
+                    // "island", "carlysle": silent
+                    i += 1;
+                } else if i == 0 && matches(i, &["SUGAR"]) {
+                    out.add_alt("X", "S");
+                    i += 1;
+                } else if matches(i, &["SH"]) {
+                    if matches(i + 1, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+                        out.add("S");
+                    } else {
+                        out.add("X");
+                    }
+                    i += 2;
+                } else if matches(i, &["SIO", "SIA"]) {
+                    if slavo_germanic {
+                        out.add("S");
+                    } else {
+                        out.add_alt("S", "X");
+                    }
+                    i += 3;
+                } else if (i == 0 && matches(i + 1, &["M", "N", "L", "W"])) || at(i + 1) == 'Z' {
+                    out.add_alt("S", "X");
+                    i += if at(i + 1) == 'Z' { 2 } else { 1 };
+                } else if matches(i, &["SC"]) {
+                    if at(i + 2) == 'H' {
+                        if matches(i + 3, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                            if matches(i + 3, &["ER", "EN"]) {
+                                out.add_alt("X", "SK");
+                            } else {
+                                out.add("SK");
+                            }
+                        } else if i == 0 && !is_vowel(3) && at(3) != 'W' {
+                            out.add_alt("X", "S");
+                        } else {
+                            out.add("X");
+                        }
+                    } else if matches(i + 2, &["I", "E", "Y"]) {
+                        out.add("S");
+                    } else {
+                        out.add("SK");
+                    }
+                    i += 3;
+                } else {
+                    // French silent final "s", e.g. "Artois"
+                    if i == last && matches(i - 2, &["AI", "OI"]) {
+                        out.add_alt("", "S");
+                    } else {
+                        out.add("S");
+                    }
+                    i += if matches(i + 1, &["S", "Z"]) { 2 } else { 1 };
+                }
+            }
+            'T' => {
+                if matches(i, &["TION", "TIA", "TCH"]) {
+                    out.add("X");
+                    i += 3;
+                } else if matches(i, &["TH", "TTH"]) {
+                    if matches(i + 2, &["OM", "AM"]) || matches(0, &["SCH"]) {
+                        out.add("T");
+                    } else {
+                        out.add_alt("0", "T");
+                    }
+                    i += 2;
+                } else {
+                    out.add("T");
+                    i += if matches(i + 1, &["T", "D"]) { 2 } else { 1 };
+                }
+            }
+            'V' => {
+                out.add("F");
+                i += if at(i + 1) == 'V' { 2 } else { 1 };
+            }
+            'W' => {
+                if matches(i, &["WR"]) {
+                    out.add("R");
+                    i += 2;
+                } else {
+                    if i == 0 && (is_vowel(i + 1) || matches(i, &["WH"])) {
+                        if is_vowel(i + 1) {
+                            out.add_alt("A", "F");
+                        } else {
+                            out.add("A");
+                        }
+                    }
+                    if (i == last && is_vowel(i - 1))
+                        || matches(i - 1, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+                        || matches(0, &["SCH"])
+                    {
+                        out.add_alt("", "F");
+                        i += 1;
+                    } else if matches(i, &["WICZ", "WITZ"]) {
+                        out.add_alt("TS", "FX");
+                        i += 4;
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            'X' => {
+                // French silent final "x", e.g. "Breaux"
+                if !(i == last
+                    && (matches(i - 3, &["IAU", "EAU"]) || matches(i - 2, &["AU", "OU"])))
+                {
+                    out.add("KS");
+                }
+                i += if matches(i + 1, &["C", "X"]) { 2 } else { 1 };
+            }
+            'Z' => {
+                if at(i + 1) == 'H' {
+                    out.add("J");
+                    i += 2;
+                } else {
+                    if matches(i + 1, &["ZO", "ZI", "ZA"])
+                        || (slavo_germanic && i > 0 && at(i - 1) != 'T')
+                    {
+                        out.add_alt("S", "TS");
+                    } else {
+                        out.add("S");
+                    }
+                    i += if at(i + 1) == 'Z' { 2 } else { 1 };
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    out.primary.truncate(METAPHONE_MAX_LEN);
+    out.alternate.truncate(METAPHONE_MAX_LEN);
+    (out.primary, out.alternate)
+}
+
+/* ──────────────────────────────────────────────────────────────── */
+
+/// Double Metaphone preceded by spelling rules for the transcription language,
+/// so e.g. German "Müller" / "Mueller" or Spanish "Javier" / "Habier" share a
+/// key. Words without any Latin letters (CJK, Cyrillic, ...) fall back to
+/// their lowercase form as the only key, so they only match exactly.
+pub struct LanguagePhonetic {
+    rules: &'static [(&'static str, &'static str)],
+    drop_silent_endings: bool,
+}
+
+const GERMAN_RULES: &[(&str, &str)] = &[
+    ("ä", "e"),
+    ("ö", "o"),
+    ("ü", "u"),
+    ("ß", "ss"),
+    ("qu", "kv"),
+    ("ae", "e"),
+    ("oe", "o"),
+    ("ue", "u"),
+    ("sch", "sh"),
+    ("ph", "f"),
+    ("th", "t"),
+    ("dt", "t"),
+    ("ck", "k"),
+    ("tz", "ts"),
+    ("z", "ts"),
+    ("v", "f"),
+    ("w", "v"),
+    ("ei", "ai"),
+    ("ey", "ai"),
+    ("ie", "i"),
+];
+
+const SPANISH_RULES: &[(&str, &str)] = &[
+    ("ñ", "ny"),
+    ("ll", "y"),
+    ("qu", "k"),
+    ("gue", "ge"),
+    ("gui", "gi"),
+    ("ce", "se"),
+    ("ci", "si"),
+    ("z", "s"),
+    ("v", "b"),
+    ("j", "h"),
+];
+
+const FRENCH_RULES: &[(&str, &str)] = &[
+    ("ç", "s"),
+    ("eaux", "o"),
+    ("eau", "o"),
+    ("ault", "o"),
+    ("aux", "o"),
+    ("au", "o"),
+    ("ou", "u"),
+    ("ph", "f"),
+    ("qu", "k"),
+    ("gn", "ny"),
+];
+
+const ITALIAN_RULES: &[(&str, &str)] = &[
+    ("gli", "li"),
+    ("gn", "ny"),
+    ("ch", "k"),
+    ("gh", "g"),
+    ("zz", "ts"),
+    ("z", "ts"),
+];
+
+const PORTUGUESE_RULES: &[(&str, &str)] = &[
+    ("ç", "s"),
+    ("lh", "ly"),
+    ("nh", "ny"),
+    ("ão", "aun"),
+    ("qu", "k"),
+    ("ch", "sh"),
+];
+
+const DUTCH_RULES: &[(&str, &str)] = &[
+    ("ij", "ei"),
+    ("oe", "u"),
+    ("sch", "sg"),
+    ("ch", "g"),
+    ("dt", "t"),
+    ("v", "f"),
+    ("w", "v"),
+];
+
+impl LanguagePhonetic {
+    pub fn new(language: &str) -> Self {
+        let rules: &'static [(&str, &str)] = match language {
+            "de" => GERMAN_RULES,
+            "es" => SPANISH_RULES,
+            "fr" => FRENCH_RULES,
+            "it" => ITALIAN_RULES,
+            "pt" => PORTUGUESE_RULES,
+            "nl" => DUTCH_RULES,
+            _ => &[],
+        };
+        Self {
+            rules,
+            drop_silent_endings: language == "fr",
+        }
+    }
+
+    fn normalize(&self, word: &str) -> String {
+        let mut normalized = word.to_lowercase();
+        for (from, to) in self.rules {
+            if normalized.contains(from) {
+                normalized = normalized.replace(from, to);
+            }
+        }
+        if self.drop_silent_endings && normalized.chars().count() > 3 {
+            // French rarely pronounces a final e, s, t, d, x or z
+            while normalized.chars().count() > 3
+                && normalized.ends_with(['e', 's', 't', 'd', 'x', 'z'])
+            {
+                normalized.pop();
+            }
+        }
+        fold_diacritics(&normalized)
+    }
+}
+
+impl PhoneticScorer for LanguagePhonetic {
+    fn name(&self) -> &'static str {
+        "language"
+    }
+
+    fn keys(&self, word: &str) -> Vec<String> {
+        let normalized = self.normalize(word);
+        if !normalized.chars().any(|c| c.is_ascii_alphabetic()) {
+            let word = word.trim().to_lowercase();
+            return if word.is_empty() {
+                Vec::new()
+            } else {
+                vec![word]
+            };
+        }
+        DoubleMetaphone.keys(&normalized)
+    }
+}
+
+/// Replace accented Latin letters with their closest ASCII spelling.
+/// Expects lowercase input; other characters are kept as they are.
+pub fn fold_diacritics(word: &str) -> String {
+    let mut folded = String::with_capacity(word.len());
+    for c in word.chars() {
+        let replacement = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+            'æ' => "ae",
+            'ç' => "s",
+            'ć' | 'č' | 'ĉ' | 'ċ' => "c",
+            'ď' | 'đ' | 'ð' => "d",
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+            'ğ' | 'ĝ' | 'ġ' | 'ģ' => "g",
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+            'ķ' => "k",
+            'ł' | 'ľ' | 'ĺ' | 'ļ' => "l",
+            'ñ' | 'ń' | 'ň' | 'ņ' => "n",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+            'œ' => "oe",
+            'ŕ' | 'ř' | 'ŗ' => "r",
+            'ś' | 'š' | 'ş' | 'ŝ' | 'ș' => "s",
+            'ß' => "ss",
+            'ť' | 'ţ' | 'ț' => "t",
+            'þ' => "th",
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+            'ý' | 'ÿ' | 'ŷ' => "y",
+            'ź' | 'ż' | 'ž' => "z",
+            _ => {
+                folded.push(c);
+                continue;
+            }
+        };
+        folded.push_str(replacement);
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metaphone(word: &str) -> (String, String) {
+        double_metaphone(word)
+    }
+
+    fn pair(primary: &str, alternate: &str) -> (String, String) {
+        (primary.to_string(), alternate.to_string())
+    }
+
+    #[test]
+    fn test_soundex_codes() {
+        assert_eq!(soundex("Robert").as_deref(), Some("R163"));
+        assert_eq!(soundex("Rupert").as_deref(), Some("R163"));
+        assert_eq!(soundex("Tymczak").as_deref(), Some("T522"));
+        assert_eq!(soundex("Pfister").as_deref(), Some("P236"));
+        assert_eq!(soundex("Ashcraft").as_deref(), Some("A261"));
+        assert_eq!(soundex("123"), None);
+    }
+
+    #[test]
+    fn test_soundex_folds_accents() {
+        assert!(Soundex.sounds_alike("Müller", "Muller"));
+        assert!(!Soundex.sounds_alike("東京", "東京"));
+    }
+
+    #[test]
+    fn test_double_metaphone_codes() {
+        assert_eq!(metaphone("Smith"), pair("SM0", "XMT"));
+        assert_eq!(metaphone("Schmidt"), pair("XMT", "SMT"));
+        assert_eq!(metaphone("Thomas"), pair("TMS", "TMS"));
+        assert_eq!(metaphone("Knight"), pair("NT", "NT"));
+        assert_eq!(metaphone("Philips"), pair("FLPS", "FLPS"));
+        assert_eq!(metaphone("Xavier"), pair("SF", "SFR"));
+        assert_eq!(metaphone("Jose"), pair("HS", "HS"));
+    }
+
+    #[test]
+    fn test_double_metaphone_alternates_match() {
+        assert!(DoubleMetaphone.sounds_alike("Smith", "Schmidt"));
+        assert!(DoubleMetaphone.sounds_alike("Catherine", "Kathryn"));
+        assert!(!DoubleMetaphone.sounds_alike("Smith", "Jones"));
+    }
+
+    #[test]
+    fn test_language_phonetic_german() {
+        let german = LanguagePhonetic::new("de");
+        assert!(german.sounds_alike("Müller", "Mueller"));
+        assert!(german.sounds_alike("Schmitt", "Schmidt"));
+        assert!(german.sounds_alike("Weiß", "Weiss"));
+    }
+
+    #[test]
+    fn test_language_phonetic_spanish_and_french() {
+        let spanish = LanguagePhonetic::new("es");
+        assert!(spanish.sounds_alike("Javier", "Habier"));
+        assert!(spanish.sounds_alike("Núñez", "Nuniez"));
+
+        let french = LanguagePhonetic::new("fr");
+        assert!(french.sounds_alike("Renault", "Reno"));
+        assert!(french.sounds_alike("Thibault", "Tibo"));
+    }
+
+    #[test]
+    fn test_language_phonetic_non_latin_exact_only() {
+        let scorer = LanguagePhonetic::new("ja");
+        assert!(scorer.sounds_alike("東京", "東京"));
+        assert!(!scorer.sounds_alike("東京", "京都"));
+    }
+
+    #[test]
+    fn test_scorer_for_language() {
+        assert_eq!(scorer_for_language("en").name(), "soundex");
+        assert_eq!(scorer_for_language("en-GB").name(), "soundex");
+        assert_eq!(scorer_for_language("auto").name(), "soundex");
+        assert_eq!(scorer_for_language("").name(), "soundex");
+        assert_eq!(scorer_for_language("de").name(), "language");
+        assert_eq!(scorer_for_language("zh-Hans").name(), "language");
+    }
+}
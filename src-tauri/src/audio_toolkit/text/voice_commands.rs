@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Editing commands that can be spoken during dictation.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceCommand {
+    NewLine,
+    NewParagraph,
+    DeleteLastSentence,
+    ScratchThat,
+    CapsOn,
+    CapsOff,
+    /// Ends a hands-free session; anything said after it is dropped
+    StopListening,
+}
+
+/// A spoken phrase that triggers a command, e.g. "new line".
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct VoiceCommandPhrase {
+    pub phrase: String,
+    pub command: VoiceCommand,
+}
+
+/// Result of running the command layer over a transcription.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VoiceCommandOutput {
+    /// Text to insert after applying the in-utterance edits.
+    pub text: String,
+    /// Characters of previously inserted text to delete (backspace) before inserting `text`.
+    pub delete_before: usize,
+    /// The utterance asked to end hands-free listening.
+    pub stop_listening: bool,
+}
+
+/// Phrases recognised for a language. Phrases are matched case-insensitively
+/// on whole words, ignoring punctuation the transcriber put around them.
+#[derive(Debug, Clone, Default)]
+pub struct VoiceCommandSet {
+    phrases: Vec<(Vec<String>, VoiceCommand)>,
+}
+
+const BUILTIN_PHRASES: &[(&str, &[(&str, VoiceCommand)])] = &[
+    (
+        "en",
+        &[
+            ("new line", VoiceCommand::NewLine),
+            ("new paragraph", VoiceCommand::NewParagraph),
+            ("delete last sentence", VoiceCommand::DeleteLastSentence),
+            ("scratch that", VoiceCommand::ScratchThat),
+            ("all caps", VoiceCommand::CapsOn),
+            ("end caps", VoiceCommand::CapsOff),
+            ("stop listening", VoiceCommand::StopListening),
+        ],
+    ),
+    (
+        "de",
+        &[
+            ("neue zeile", VoiceCommand::NewLine),
+            ("neuer absatz", VoiceCommand::NewParagraph),
+            ("letzten satz löschen", VoiceCommand::DeleteLastSentence),
+            ("streich das", VoiceCommand::ScratchThat),
+            ("großbuchstaben an", VoiceCommand::CapsOn),
+            ("großbuchstaben aus", VoiceCommand::CapsOff),
+            ("zuhören beenden", VoiceCommand::StopListening),
+        ],
+    ),
+    (
+        "fr",
+        &[
+            ("à la ligne", VoiceCommand::NewLine),
+            ("nouvelle ligne", VoiceCommand::NewLine),
+            ("nouveau paragraphe", VoiceCommand::NewParagraph),
+            (
+                "supprimer la dernière phrase",
+                VoiceCommand::DeleteLastSentence,
+            ),
+            ("efface ça", VoiceCommand::ScratchThat),
+            ("tout en majuscules", VoiceCommand::CapsOn),
+            ("fin majuscules", VoiceCommand::CapsOff),
+            ("arrête d'écouter", VoiceCommand::StopListening),
+        ],
+    ),
+    (
+        "es",
+        &[
+            ("nueva línea", VoiceCommand::NewLine),
+            ("nuevo párrafo", VoiceCommand::NewParagraph),
+            ("borrar última frase", VoiceCommand::DeleteLastSentence),
+            ("borra eso", VoiceCommand::ScratchThat),
+            ("todo mayúsculas", VoiceCommand::CapsOn),
+            ("fin mayúsculas", VoiceCommand::CapsOff),
+            ("deja de escuchar", VoiceCommand::StopListening),
+        ],
+    ),
+];
+
+impl VoiceCommandSet {
+    /// Built-in phrases for `language` (e.g. "en", "fr-CA", "auto") plus the
+    /// user's own phrases. Unknown languages and "auto" fall back to English.
+    pub fn for_language(language: &str, custom: &[VoiceCommandPhrase]) -> Self {
+        let code = language
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("en")
+            .to_lowercase();
+        let builtin = BUILTIN_PHRASES
+            .iter()
+            .find(|(lang, _)| *lang == code)
+            .or_else(|| BUILTIN_PHRASES.iter().find(|(lang, _)| *lang == "en"))
+            .map(|(_, phrases)| *phrases)
+            .unwrap_or(&[]);
+
+        let mut set = Self::default();
+        for (phrase, command) in builtin {
+            set.add(phrase, *command);
+        }
+        for custom_phrase in custom {
+            set.add(&custom_phrase.phrase, custom_phrase.command);
+        }
+        set
+    }
+
+    pub fn add(&mut self, phrase: &str, command: VoiceCommand) {
+        let words: Vec<String> = phrase.split_whitespace().map(normalize_word).collect();
+        if words.iter().any(|w| w.is_empty()) || words.is_empty() {
+            return;
+        }
+        self.phrases.push((words, command));
+        // Longest phrases first so "new paragraph" never loses to a shorter prefix
+        self.phrases
+            .sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+    }
+
+    fn match_at(&self, words: &[&str]) -> Option<(usize, VoiceCommand)> {
+        self.phrases.iter().find_map(|(phrase, command)| {
+            let candidate = words.get(..phrase.len())?;
+            candidate
+                .iter()
+                .zip(phrase)
+                .all(|(word, expected)| normalize_word(word) == *expected)
+                .then_some((phrase.len(), *command))
+        })
+    }
+}
+
+/// Applies spoken editing commands to a transcription.
+///
+/// `previous` is the text inserted by the last dictation; commands that reach
+/// past the start of this utterance ("scratch that" at the beginning, or
+/// "delete last sentence" with nothing dictated yet) delete from it instead.
+pub fn apply_voice_commands(
+    text: &str,
+    commands: &VoiceCommandSet,
+    previous: Option<&str>,
+) -> VoiceCommandOutput {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut out = String::new();
+    let mut delete_before = 0usize;
+    // What is still left of the previous dictation after earlier deletions,
+    // including any trailing space that was typed after it
+    let mut previous_left = previous.unwrap_or("").to_string();
+    let mut caps = false;
+    let mut stop_listening = false;
+
+    let mut i = 0;
+    while i < words.len() {
+        if let Some((len, command)) = commands.match_at(&words[i..]) {
+            match command {
+                VoiceCommand::NewLine => push_break(&mut out, "\n"),
+                VoiceCommand::NewParagraph => push_break(&mut out, "\n\n"),
+                VoiceCommand::CapsOn => caps = true,
+                VoiceCommand::CapsOff => caps = false,
+                VoiceCommand::StopListening => {
+                    stop_listening = true;
+                    break;
+                }
+                VoiceCommand::ScratchThat => {
+                    if out.trim().is_empty() {
+                        delete_before += previous_left.chars().count();
+                        previous_left.clear();
+                    }
+                    out.clear();
+                }
+                VoiceCommand::DeleteLastSentence => {
+                    if out.trim().is_empty() {
+                        out.clear();
+                        let keep = last_sentence_start(&previous_left);
+                        delete_before += previous_left[keep..].chars().count();
+                        previous_left.truncate(keep);
+                    } else {
+                        let keep = last_sentence_start(&out);
+                        out.truncate(keep);
+                    }
+                }
+            }
+            i += len;
+            continue;
+        }
+
+        let word = if caps {
+            words[i].to_uppercase()
+        } else {
+            words[i].to_string()
+        };
+        if !(out.is_empty() || out.ends_with('\n') || out.ends_with(' ')) {
+            out.push(' ');
+        }
+        out.push_str(&word);
+        i += 1;
+    }
+
+    VoiceCommandOutput {
+        text: out.trim_end_matches(' ').to_string(),
+        delete_before,
+        stop_listening,
+    }
+}
+
+fn push_break(out: &mut String, separator: &str) {
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+    out.push_str(separator);
+}
+
+/// Byte index where the last sentence of `text` starts, i.e. just after the
+/// previous sentence terminator (and the whitespace following it).
+fn last_sentence_start(text: &str) -> usize {
+    let body = text.trim_end();
+    // Skip the terminator that ends the last sentence itself
+    let body = body.trim_end_matches(is_sentence_end);
+    match body.rfind(|c: char| is_sentence_end(c) || c == '\n') {
+        Some(idx) => {
+            let after = idx + body[idx..].chars().next().map_or(1, |c| c.len_utf8());
+            let rest = &text[after..];
+            after + (rest.len() - rest.trim_start_matches(' ').len())
+        }
+        None => 0,
+    }
+}
+
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '。' | '！' | '？')
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn english() -> VoiceCommandSet {
+        VoiceCommandSet::for_language("en", &[])
+    }
+
+    fn apply(text: &str) -> VoiceCommandOutput {
+        apply_voice_commands(text, &english(), None)
+    }
+
+    #[test]
+    fn test_no_commands_passthrough() {
+        let result = apply("Hello world, how are you?");
+        assert_eq!(result.text, "Hello world, how are you?");
+        assert_eq!(result.delete_before, 0);
+    }
+
+    #[test]
+    fn test_new_line_and_paragraph() {
+        assert_eq!(apply("Dear Sam, new line thanks").text, "Dear Sam,\nthanks");
+        assert_eq!(
+            apply("First point. New paragraph. Second point.").text,
+            "First point.\n\nSecond point."
+        );
+    }
+
+    #[test]
+    fn test_command_punctuation_is_ignored() {
+        assert_eq!(apply("Hello. New Line! World").text, "Hello.\nWorld");
+    }
+
+    #[test]
+    fn test_delete_last_sentence() {
+        assert_eq!(
+            apply("I like tea. I hate coffee. Delete last sentence. I like cake.").text,
+            "I like tea. I like cake."
+        );
+        assert_eq!(apply("Only one sentence delete last sentence").text, "");
+    }
+
+    #[test]
+    fn test_delete_last_sentence_reaches_previous_dictation() {
+        let result = apply_voice_commands(
+            "Delete last sentence.",
+            &english(),
+            Some("Keep this. Drop this. "),
+        );
+        assert_eq!(result.text, "");
+        assert_eq!(result.delete_before, "Drop this. ".chars().count());
+    }
+
+    #[test]
+    fn test_scratch_that() {
+        assert_eq!(
+            apply("Some words scratch that better words").text,
+            "better words"
+        );
+
+        // The trailing space typed after the previous dictation goes too
+        let result = apply_voice_commands("Scratch that.", &english(), Some("Oops "));
+        assert_eq!(result.text, "");
+        assert_eq!(result.delete_before, 5);
+    }
+
+    #[test]
+    fn test_all_caps() {
+        assert_eq!(
+            apply("This is all caps very important end caps okay").text,
+            "This is VERY IMPORTANT okay"
+        );
+        // Unterminated caps runs to the end
+        assert_eq!(apply("all caps shout").text, "SHOUT");
+    }
+
+    #[test]
+    fn test_stop_listening_drops_the_rest() {
+        let result = apply("Thanks for the update. Stop listening. Uh okay");
+        assert_eq!(result.text, "Thanks for the update.");
+        assert!(result.stop_listening);
+        assert!(!apply("Keep listening please").stop_listening);
+    }
+
+    #[test]
+    fn test_language_specific_phrases() {
+        let german = VoiceCommandSet::for_language("de-DE", &[]);
+        let result = apply_voice_commands("Hallo neue Zeile Welt", &german, None);
+        assert_eq!(result.text, "Hallo\nWelt");
+
+        // English phrases are not active for German
+        let result = apply_voice_commands("Hallo new line Welt", &german, None);
+        assert_eq!(result.text, "Hallo new line Welt");
+    }
+
+    #[test]
+    fn test_unknown_language_falls_back_to_english() {
+        let set = VoiceCommandSet::for_language("auto", &[]);
+        assert_eq!(
+            apply_voice_commands("a new line b", &set, None).text,
+            "a\nb"
+        );
+    }
+
+    #[test]
+    fn test_custom_phrases() {
+        let custom = vec![VoiceCommandPhrase {
+            phrase: "next line please".to_string(),
+            command: VoiceCommand::NewLine,
+        }];
+        let set = VoiceCommandSet::for_language("en", &custom);
+        assert_eq!(
+            apply_voice_commands("one next line please two", &set, None).text,
+            "one\ntwo"
+        );
+    }
+
+    #[test]
+    fn test_last_sentence_start() {
+        assert_eq!(last_sentence_start("One. Two."), 5);
+        assert_eq!(last_sentence_start("One"), 0);
+        assert_eq!(last_sentence_start("Line one\nLine two"), 9);
+    }
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+
+use super::{VadFrame, VoiceActivityDetector};
+
+const MIN_LEVEL_DB: f32 = -100.0;
+const INITIAL_NOISE_FLOOR_DB: f32 = -60.0;
+/// How far above the background a frame has to be to count as voice
+const NOISE_MARGIN_DB: f32 = 10.0;
+/// Per-frame smoothing when the level drops below the noise floor
+const FLOOR_FALL_RATE: f32 = 0.2;
+/// Per-frame smoothing when the level rises; slow, so speech barely moves it
+const FLOOR_RISE_RATE: f32 = 0.005;
+
+/// Level-based VAD: a frame is voice when it is louder than a fixed
+/// threshold and clearly above a running estimate of the background noise.
+/// Needs no model and next to no CPU, at the cost of being fooled by loud
+/// non-speech sounds.
+pub struct EnergyVad {
+    threshold_db: f32,
+    noise_floor_db: f32,
+}
+
+impl EnergyVad {
+    pub fn new(threshold_db: f32) -> Self {
+        Self {
+            threshold_db,
+            noise_floor_db: INITIAL_NOISE_FLOOR_DB,
+        }
+    }
+
+    fn level_db(frame: &[f32]) -> f32 {
+        if frame.is_empty() {
+            return MIN_LEVEL_DB;
+        }
+        let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt();
+        (20.0 * rms.log10()).max(MIN_LEVEL_DB)
+    }
+}
+
+impl VoiceActivityDetector for EnergyVad {
+    fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>> {
+        let level = Self::level_db(frame);
+        let is_voice = level > self.threshold_db && level > self.noise_floor_db + NOISE_MARGIN_DB;
+
+        let rate = if level < self.noise_floor_db {
+            FLOOR_FALL_RATE
+        } else {
+            FLOOR_RISE_RATE
+        };
+        self.noise_floor_db += (level - self.noise_floor_db) * rate;
+
+        if is_voice {
+            Ok(VadFrame::Speech(frame))
+        } else {
+            Ok(VadFrame::Noise)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: usize = 480;
+
+    fn tone(amplitude: f32) -> Vec<f32> {
+        (0..FRAME)
+            .map(|i| amplitude * (i as f32 * 0.1).sin())
+            .collect()
+    }
+
+    #[test]
+    fn speech_above_silence_is_voice() {
+        let mut vad = EnergyVad::new(-45.0);
+        assert!(!vad.is_voice(&[0.0; FRAME]).unwrap());
+        assert!(!vad.is_voice(&tone(0.001)).unwrap());
+        assert!(vad.is_voice(&tone(0.2)).unwrap());
+    }
+
+    #[test]
+    fn steady_background_noise_is_learned() {
+        let mut vad = EnergyVad::new(-45.0);
+        let hum = tone(0.03);
+        assert!(vad.is_voice(&hum).unwrap());
+
+        for _ in 0..2000 {
+            vad.is_voice(&hum).unwrap();
+        }
+        assert!(!vad.is_voice(&hum).unwrap());
+        assert!(vad.is_voice(&tone(0.5)).unwrap());
+    }
+}
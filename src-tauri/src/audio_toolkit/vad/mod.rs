@@ -25,8 +25,10 @@ pub trait VoiceActivityDetector: Send + Sync {
     fn reset(&mut self) {}
 }
 
+mod energy;
 mod silero;
 mod smoothed;
 
+pub use energy::EnergyVad;
 pub use silero::SileroVad;
 pub use smoothed::SmoothedVad;
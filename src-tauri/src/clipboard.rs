@@ -1,5 +1,5 @@
 use crate::input::{self, EnigoState};
-use crate::settings::{get_settings, ClipboardHandling, PasteMethod};
+use crate::settings::{AppSettings, ClipboardHandling, PasteMethod};
 use enigo::Enigo;
 use log::info;
 use tauri::{AppHandle, Manager};
@@ -361,8 +361,98 @@ fn paste_direct(enigo: &mut Enigo, text: &str) -> Result<(), String> {
     input::paste_text_direct(enigo, text)
 }
 
-pub fn paste(text: String, app_handle: AppHandle) -> Result<(), String> {
-    let settings = get_settings(&app_handle);
+/// Remove the last `count` characters typed into the focused field, used by
+/// voice commands that reach back into the previous dictation.
+pub fn delete_previous_text(count: usize, app_handle: &AppHandle) -> Result<(), String> {
+    if count == 0 {
+        return Ok(());
+    }
+
+    let enigo_state = app_handle
+        .try_state::<EnigoState>()
+        .ok_or("Enigo state not initialized")?;
+    let mut enigo = enigo_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+
+    info!("Deleting {} characters of previous dictation", count);
+    input::send_backspaces(&mut enigo, count)
+}
+
+/// Type text into the focused field as it is, e.g. post-processing output
+/// arriving piece by piece.
+pub fn type_text(text: &str, app_handle: &AppHandle) -> Result<(), String> {
+    let enigo_state = app_handle
+        .try_state::<EnigoState>()
+        .ok_or("Enigo state not initialized")?;
+    let mut enigo = enigo_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+
+    paste_direct(&mut enigo, text)
+}
+
+/// Copy the selection in the focused application and return it. The clipboard
+/// is cleared first so an empty selection is not mistaken for old clipboard
+/// content, and restored afterwards.
+pub fn read_selected_text(app_handle: &AppHandle) -> Result<String, String> {
+    let enigo_state = app_handle
+        .try_state::<EnigoState>()
+        .ok_or("Enigo state not initialized")?;
+    let mut enigo = enigo_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+
+    let clipboard = app_handle.clipboard();
+    let clipboard_content = clipboard.read_text().unwrap_or_default();
+    clipboard
+        .write_text("")
+        .map_err(|e| format!("Failed to clear clipboard: {}", e))?;
+
+    let copied = input::send_copy_ctrl_c(&mut enigo);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let selection = clipboard.read_text().unwrap_or_default();
+
+    // Restore original clipboard content
+    clipboard
+        .write_text(&clipboard_content)
+        .map_err(|e| format!("Failed to restore clipboard: {}", e))?;
+
+    copied.map(|_| selection)
+}
+
+/// Read the primary selection, i.e. whatever text is currently selected, without
+/// sending any keys. Needs `wl-paste` on Wayland and `xclip` on X11.
+#[cfg(target_os = "linux")]
+pub fn read_primary_selection() -> Result<String, String> {
+    let (program, args): (&str, &[&str]) = if is_wayland() {
+        ("wl-paste", &["--primary", "--no-newline"])
+    } else {
+        ("xclip", &["-o", "-selection", "primary"])
+    };
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute {}: {}", program, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", program, stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Paste using the given settings, e.g. with per-application rules already applied.
+pub fn paste_with_settings(
+    text: String,
+    app_handle: AppHandle,
+    settings: &AppSettings,
+) -> Result<(), String> {
     let paste_method = settings.paste_method;
 
     // Append trailing space if setting is enabled
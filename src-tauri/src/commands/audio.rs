@@ -1,11 +1,15 @@
 use crate::audio_feedback;
-use crate::audio_toolkit::audio::{list_input_devices, list_output_devices};
+use crate::audio_toolkit::audio::{
+    find_loopback_device, list_input_devices, list_loopback_devices, list_output_devices,
+};
+use crate::audio_toolkit::LevelStats;
 use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
-use crate::settings::{get_settings, write_settings};
+use crate::settings::{get_settings, write_settings, CaptureSource, VadBackend};
 use log::warn;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
 #[derive(Serialize, Type)]
@@ -106,6 +110,18 @@ pub fn set_selected_microphone(app: AppHandle, device_name: String) -> Result<()
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn set_microphone_priority(app: AppHandle, devices: Vec<String>) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.microphone_priority = devices;
+    write_settings(&app, settings);
+
+    // Takes effect right away if a fallback is in use
+    app.state::<Arc<AudioRecordingManager>>().check_microphone();
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_selected_microphone(app: AppHandle) -> Result<String, String> {
@@ -201,3 +217,254 @@ pub fn is_recording(app: AppHandle) -> bool {
     let audio_manager = app.state::<Arc<AudioRecordingManager>>();
     audio_manager.is_recording()
 }
+
+// Highest gain either capture source can be turned up to
+const MAX_CAPTURE_GAIN: f32 = 4.0;
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_system_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    let devices = list_loopback_devices()
+        .map_err(|e| format!("Failed to list system audio devices: {}", e))?;
+
+    let mut result = vec![AudioDevice {
+        index: "default".to_string(),
+        name: "Default".to_string(),
+        is_default: true,
+    }];
+
+    result.extend(devices.into_iter().map(|d| AudioDevice {
+        index: d.index,
+        name: d.name,
+        is_default: false, // The explicit default is handled separately
+    }));
+
+    Ok(result)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_system_audio_device(app: AppHandle, device_name: String) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.system_audio_device = if device_name == "default" {
+        None
+    } else {
+        Some(device_name)
+    };
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_selected_device()
+        .map_err(|e| format!("Failed to update selected device: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_capture_source(app: AppHandle, source: CaptureSource) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    // Without a system audio device there would be nothing to record
+    if source == CaptureSource::SystemAudio {
+        find_loopback_device(settings.system_audio_device.as_deref())?;
+    }
+    settings.capture_source = source;
+    write_settings(&app, settings);
+
+    // Reopen the stream on the new sources
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.update_selected_device()
+        .map_err(|e| format!("Failed to update capture source: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_capture_gains(
+    app: AppHandle,
+    microphone_gain: f32,
+    system_audio_gain: f32,
+) -> Result<(), String> {
+    for gain in [microphone_gain, system_audio_gain] {
+        if !(0.0..=MAX_CAPTURE_GAIN).contains(&gain) {
+            return Err(format!(
+                "Gain must be between 0 and {}, got {}",
+                MAX_CAPTURE_GAIN, gain
+            ));
+        }
+    }
+
+    let mut settings = get_settings(&app);
+    settings.microphone_gain = microphone_gain;
+    settings.system_audio_gain = system_audio_gain;
+    write_settings(&app, settings);
+
+    app.state::<Arc<AudioRecordingManager>>()
+        .update_audio_processing();
+    Ok(())
+}
+
+// Longest microphone test
+const MAX_CALIBRATION_SECS: u32 = 30;
+// Speech level the suggested gain aims for, in dBFS
+const TARGET_SPEECH_DB: f32 = -20.0;
+// Peak level the suggested gain never pushes the input past
+const TARGET_PEAK_DB: f32 = -6.0;
+// Speech has to be this far above the noise floor to be measured at all
+const MIN_SPEECH_MARGIN_DB: f32 = 10.0;
+
+/// Result of a microphone test. Levels are in dBFS, measured at the input
+/// before the gain and clean-up stages.
+#[derive(Serialize, Debug, Clone, Type)]
+pub struct CalibrationReport {
+    pub device: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub rms_db: f32,
+    pub peak_db: f32,
+    pub noise_floor_db: f32,
+    pub speech_level_db: f32,
+    pub clipped_samples: u32,
+    /// Whether the VAD heard speech; `None` when no VAD is in use
+    pub speech_detected: Option<bool>,
+    pub suggested_gain: Option<f32>,
+    /// Only suggested while the energy VAD is in use; Silero's threshold is
+    /// a speech probability the levels say nothing about
+    pub suggested_energy_threshold_db: Option<f32>,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn calibrate_microphone(
+    app: AppHandle,
+    seconds: u32,
+) -> Result<CalibrationReport, String> {
+    if !(1..=MAX_CALIBRATION_SECS).contains(&seconds) {
+        return Err(format!(
+            "Test length must be between 1 and {} seconds, got {}",
+            MAX_CALIBRATION_SECS, seconds
+        ));
+    }
+
+    let rm = Arc::clone(&app.state::<Arc<AudioRecordingManager>>());
+    let measurement = tauri::async_runtime::spawn_blocking(move || {
+        rm.measure_input(Duration::from_secs(seconds as u64))
+    })
+    .await
+    .map_err(|e| format!("Microphone test failed: {}", e))?
+    .map_err(|e| format!("Microphone test failed: {}", e))?;
+
+    let settings = get_settings(&app);
+    let gain = match settings.capture_source {
+        CaptureSource::SystemAudio => settings.system_audio_gain,
+        _ => settings.microphone_gain,
+    };
+    let stats = &measurement.input;
+    let suggested_gain = suggest_gain(stats, gain);
+    let suggested_energy_threshold_db = match settings.vad_backend {
+        VadBackend::Energy => suggest_energy_threshold(
+            &measurement.processed,
+            suggested_gain.map_or(1.0, |g| g / gain.max(0.1)),
+        ),
+        VadBackend::Silero | VadBackend::Off => None,
+    };
+    Ok(CalibrationReport {
+        device: measurement.device,
+        sample_rate: measurement.sample_rate,
+        rms_db: stats.rms_db,
+        peak_db: stats.peak_db,
+        noise_floor_db: stats.noise_floor_db,
+        speech_level_db: stats.speech_level_db,
+        clipped_samples: stats.clipped_samples as u32,
+        speech_detected: (settings.vad_backend != VadBackend::Off)
+            .then_some(stats.speech_frames > 0),
+        suggested_gain,
+        suggested_energy_threshold_db,
+    })
+}
+
+/// A gain that brings speech at the input to `TARGET_SPEECH_DB` without
+/// pushing the peaks past `TARGET_PEAK_DB`. `None` when the current gain is
+/// close enough, nobody spoke during the test, or the input itself clips;
+/// no gain here undoes that, the system's input volume has to come down.
+fn suggest_gain(input: &LevelStats, current: f32) -> Option<f32> {
+    if input.clipped_samples > 0
+        || input.speech_level_db - input.noise_floor_db < MIN_SPEECH_MARGIN_DB
+    {
+        return None;
+    }
+    let change_db = (TARGET_SPEECH_DB - input.speech_level_db).min(TARGET_PEAK_DB - input.peak_db);
+    let gain = 10f32.powf(change_db / 20.0).clamp(0.1, MAX_CAPTURE_GAIN);
+    let gain = (gain * 10.0).round() / 10.0;
+    let current = current.max(0.1);
+    ((gain - current).abs() / current > 0.2).then_some(gain)
+}
+
+/// An energy VAD threshold between the noise floor and the speech level the
+/// VAD sees, nearer the noise so quiet syllables still count. Both levels
+/// move with `gain_change`, the factor the gain is about to change by.
+fn suggest_energy_threshold(processed: &LevelStats, gain_change: f32) -> Option<f32> {
+    let margin = processed.speech_level_db - processed.noise_floor_db;
+    (margin >= MIN_SPEECH_MARGIN_DB).then(|| {
+        (processed.noise_floor_db + margin * 0.35 + 20.0 * gain_change.log10())
+            .round()
+            .clamp(-90.0, 0.0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(noise_floor_db: f32, speech_level_db: f32, peak_db: f32) -> LevelStats {
+        LevelStats {
+            frames: 100,
+            rms_db: speech_level_db - 3.0,
+            peak_db,
+            noise_floor_db,
+            speech_level_db,
+            clipped_samples: 0,
+            speech_frames: 50,
+        }
+    }
+
+    #[test]
+    fn quiet_speech_gets_more_gain() {
+        // Speech 12 dB under the target wants four times the amplitude
+        assert_eq!(suggest_gain(&levels(-70.0, -32.0, -20.0), 1.0), Some(4.0));
+        // Already there
+        assert_eq!(suggest_gain(&levels(-70.0, -32.0, -20.0), 4.0), None);
+    }
+
+    #[test]
+    fn suggested_gain_keeps_peaks_in_range() {
+        // Speech alone would want +12 dB, the peaks only leave room for +6
+        assert_eq!(suggest_gain(&levels(-70.0, -32.0, -12.0), 1.0), Some(2.0));
+    }
+
+    #[test]
+    fn no_gain_for_clipping_input_or_silence() {
+        let mut clipping = levels(-70.0, -32.0, 0.0);
+        clipping.clipped_samples = 10;
+        assert_eq!(suggest_gain(&clipping, 1.0), None);
+        // Nothing but room noise
+        assert_eq!(suggest_gain(&levels(-60.0, -55.0, -50.0), 1.0), None);
+    }
+
+    #[test]
+    fn energy_threshold_sits_nearer_the_noise() {
+        assert_eq!(
+            suggest_energy_threshold(&levels(-60.0, -20.0, -6.0), 1.0),
+            Some(-46.0)
+        );
+        assert_eq!(
+            suggest_energy_threshold(&levels(-60.0, -55.0, -50.0), 1.0),
+            None
+        );
+    }
+
+    #[test]
+    fn energy_threshold_follows_the_gain_change() {
+        // Doubling the gain lifts everything the VAD sees by 6 dB
+        assert_eq!(
+            suggest_energy_threshold(&levels(-60.0, -20.0, -6.0), 2.0),
+            Some(-40.0)
+        );
+    }
+}
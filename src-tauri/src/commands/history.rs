@@ -1,4 +1,5 @@
-use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::managers::history::{HistoryEntry, HistoryManager, UsageStatistics};
+use crate::managers::transcription::TranscriptionManager;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -34,7 +35,10 @@ pub async fn get_audio_file_path(
     history_manager: State<'_, Arc<HistoryManager>>,
     file_name: String,
 ) -> Result<String, String> {
-    let path = history_manager.get_audio_file_path(&file_name);
+    let path = history_manager
+        .get_playback_file_path(&file_name)
+        .await
+        .map_err(|e| e.to_string())?;
     path.to_str()
         .ok_or_else(|| "Invalid file path".to_string())
         .map(|s| s.to_string())
@@ -53,6 +57,59 @@ pub async fn delete_history_entry(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn retranscribe_history_entry(
+    history_manager: State<'_, Arc<HistoryManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    id: i64,
+) -> Result<String, String> {
+    let samples = history_manager
+        .load_entry_samples(id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let tm = Arc::clone(&transcription_manager);
+    tm.initiate_model_load();
+    let text = tauri::async_runtime::spawn_blocking(move || tm.transcribe(samples))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    history_manager
+        .update_transcription_text(id, &text)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(text)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn convert_recordings_format(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<usize, String> {
+    let codec = crate::settings::get_settings(&app).recording_format;
+    history_manager
+        .convert_recordings(codec)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_usage_statistics(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<UsageStatistics, String> {
+    let typing_wpm = crate::settings::get_settings(&app).typing_wpm;
+    history_manager
+        .get_usage_statistics(typing_wpm)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn update_history_limit(
@@ -3,6 +3,7 @@ pub mod gemini;
 pub mod google_auth;
 pub mod history;
 pub mod models;
+pub mod profiles;
 pub mod transcription;
 
 use crate::settings::{get_settings, write_settings, AppSettings, LogLevel};
@@ -2,6 +2,8 @@ use crate::managers::model::{ModelInfo, ModelManager};
 use crate::managers::transcription::TranscriptionManager;
 use crate::settings::{get_settings, write_settings};
 use std::sync::Arc;
+#[cfg(feature = "local-llm")]
+use tauri::Manager;
 use tauri::{AppHandle, State};
 
 #[tauri::command]
@@ -36,9 +38,20 @@ pub async fn download_model(
 #[tauri::command]
 #[specta::specta]
 pub async fn delete_model(
+    app_handle: AppHandle,
     model_manager: State<'_, Arc<ModelManager>>,
     model_id: String,
 ) -> Result<(), String> {
+    #[cfg(feature = "local-llm")]
+    {
+        let local_llm = app_handle.state::<Arc<crate::managers::local_llm::LocalLlmManager>>();
+        if local_llm.loaded_model().as_deref() == Some(model_id.as_str()) {
+            local_llm.unload();
+        }
+    }
+    #[cfg(not(feature = "local-llm"))]
+    let _ = app_handle;
+
     model_manager
         .delete_model(&model_id)
         .map_err(|e| e.to_string())
@@ -61,6 +74,10 @@ pub async fn set_active_model(
         return Err(format!("Model not downloaded: {}", model_id));
     }
 
+    if !model_info.engine_type.is_speech() {
+        return Err(format!("Not a speech recognition model: {}", model_id));
+    }
+
     // Load the model in the transcription manager
     transcription_manager
         .load_model(&model_id)
@@ -105,7 +122,9 @@ pub async fn has_any_models_available(
     model_manager: State<'_, Arc<ModelManager>>,
 ) -> Result<bool, String> {
     let models = model_manager.get_available_models();
-    Ok(models.iter().any(|m| m.is_downloaded))
+    Ok(models
+        .iter()
+        .any(|m| m.is_downloaded && m.engine_type.is_speech()))
 }
 
 #[tauri::command]
@@ -115,7 +134,9 @@ pub async fn has_any_models_or_downloads(
 ) -> Result<bool, String> {
     let models = model_manager.get_available_models();
     // Return true if any models are downloaded OR if any downloads are in progress
-    Ok(models.iter().any(|m| m.is_downloaded))
+    Ok(models
+        .iter()
+        .any(|m| m.is_downloaded && m.engine_type.is_speech()))
 }
 
 #[tauri::command]
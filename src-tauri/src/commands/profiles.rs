@@ -0,0 +1,159 @@
+use crate::managers::model::ModelManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{
+    get_dictation_settings, get_settings, write_settings, DictationProfile, PROFILE_ID_PREFIX,
+};
+use crate::shortcut;
+use crate::tray;
+use log::{info, warn};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Make `profile_id` the active profile, or go back to the plain global
+/// settings with `None`. The profile's overrides are applied per dictation
+/// (see `get_dictation_settings`), so nothing global is overwritten. A
+/// different model starts loading in the background; a dictation started in
+/// the meantime waits for it.
+pub fn activate_profile(app: &AppHandle, profile_id: Option<&str>) -> Result<(), String> {
+    let mut settings = get_settings(app);
+    let profile = match profile_id {
+        Some(id) => Some(
+            settings
+                .dictation_profile(id)
+                .cloned()
+                .ok_or_else(|| format!("Profile with id '{}' not found", id))?,
+        ),
+        None => None,
+    };
+
+    settings.active_profile_id = profile.as_ref().map(|p| p.id.clone());
+    write_settings(app, settings);
+
+    match &profile {
+        Some(profile) => info!("Activated dictation profile '{}'", profile.name),
+        None => info!("Deactivated dictation profile"),
+    }
+
+    preload_dictation_model(app);
+
+    tray::update_tray_menu(app, &tray::TrayIconState::Idle, None);
+    let _ = app.emit(
+        "settings-changed",
+        serde_json::json!({
+            "setting": "active_profile_id",
+            "value": profile.map(|p| p.id)
+        }),
+    );
+
+    Ok(())
+}
+
+/// Start loading the model the next dictation will use, if it is not loaded.
+fn preload_dictation_model(app: &AppHandle) {
+    let model = get_dictation_settings(app).selected_model;
+    if model.is_empty() {
+        return;
+    }
+    let model_manager = app.state::<Arc<ModelManager>>();
+    match model_manager.get_model_info(&model) {
+        Some(info) if info.is_downloaded => {
+            app.state::<Arc<TranscriptionManager>>()
+                .initiate_model_load();
+        }
+        _ => warn!("Dictation model '{}' is not downloaded", model),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_dictation_profiles(app: AppHandle) -> Result<Vec<DictationProfile>, String> {
+    Ok(get_settings(&app).dictation_profiles)
+}
+
+/// Create a profile from the current dictation settings.
+#[tauri::command]
+#[specta::specta]
+pub fn add_dictation_profile(app: AppHandle, name: String) -> Result<DictationProfile, String> {
+    let mut settings = get_settings(&app);
+
+    let id = format!(
+        "{}{}",
+        PROFILE_ID_PREFIX,
+        chrono::Utc::now().timestamp_millis()
+    );
+    let profile = DictationProfile::from_settings(id, name, &settings);
+
+    settings.dictation_profiles.push(profile.clone());
+    write_settings(&app, settings);
+    tray::update_tray_menu(&app, &tray::TrayIconState::Idle, None);
+
+    Ok(profile)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_dictation_profile(app: AppHandle, profile: DictationProfile) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+
+    let existing = settings
+        .dictation_profiles
+        .iter_mut()
+        .find(|p| p.id == profile.id)
+        .ok_or_else(|| format!("Profile with id '{}' not found", profile.id))?;
+    *existing = profile.clone();
+
+    // Keep the shortcut label in sync with the profile name
+    if let Some(binding) = settings.bindings.get_mut(&profile.id) {
+        binding.name = profile.name.clone();
+    }
+
+    let is_active = settings.active_profile_id.as_deref() == Some(profile.id.as_str());
+    write_settings(&app, settings);
+
+    if is_active {
+        // The overrides apply from the next dictation; only the model needs
+        // loading ahead of time
+        preload_dictation_model(&app);
+        tray::update_tray_menu(&app, &tray::TrayIconState::Idle, None);
+    } else {
+        tray::update_tray_menu(&app, &tray::TrayIconState::Idle, None);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_dictation_profile(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+
+    let original_len = settings.dictation_profiles.len();
+    settings.dictation_profiles.retain(|p| p.id != id);
+    if settings.dictation_profiles.len() == original_len {
+        return Err(format!("Profile with id '{}' not found", id));
+    }
+
+    if let Some(binding) = settings.bindings.remove(&id) {
+        if !binding.current_binding.is_empty() {
+            if let Err(e) = shortcut::unregister_shortcut(&app, binding) {
+                warn!("Failed to unregister shortcut for profile {}: {}", id, e);
+            }
+        }
+    }
+
+    if settings.active_profile_id.as_deref() == Some(id.as_str()) {
+        settings.active_profile_id = None;
+    }
+
+    write_settings(&app, settings);
+    tray::update_tray_menu(&app, &tray::TrayIconState::Idle, None);
+
+    Ok(())
+}
+
+/// Activate a profile, or pass `None` to dictate with the global settings.
+#[tauri::command]
+#[specta::specta]
+pub fn set_active_dictation_profile(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    activate_profile(&app, id.as_deref())
+}
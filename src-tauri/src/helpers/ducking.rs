@@ -0,0 +1,663 @@
+use log::{debug, warn};
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::Command;
+
+/// Logged when per-application ducking was asked for where it can't be done
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+const APPS_UNSUPPORTED: &str =
+    "Ducking single applications needs PipeWire or PulseAudio; ducking the whole output";
+
+/// Output volume changes made for a recording, remembered so they can be
+/// undone exactly.
+#[derive(Debug, Default)]
+pub struct DuckedOutput {
+    saved: Vec<SavedVolume>,
+}
+
+/// Volume and mute state of one output or stream before it was ducked.
+#[derive(Debug)]
+enum SavedVolume {
+    /// PulseAudio sink (also PipeWire through pipewire-pulse), raw volume per channel
+    #[cfg(target_os = "linux")]
+    PulseSink {
+        name: String,
+        volumes: Vec<u32>,
+        muted: bool,
+    },
+    /// PulseAudio stream of a single application
+    #[cfg(target_os = "linux")]
+    PulseStream {
+        index: u32,
+        volumes: Vec<u32>,
+        muted: bool,
+    },
+    /// Default PipeWire sink through wpctl, volume as wpctl printed it
+    #[cfg(target_os = "linux")]
+    Wpctl { volume: String, muted: bool },
+    /// ALSA Master control, percent per channel
+    #[cfg(target_os = "linux")]
+    Amixer { percents: Vec<u32>, muted: bool },
+    #[cfg(target_os = "windows")]
+    Endpoint {
+        device: Option<String>,
+        level: f32,
+        muted: bool,
+    },
+    #[cfg(target_os = "macos")]
+    AppleScript { volume: u32, muted: bool },
+}
+
+/// Lower the output volume by `amount`, from 0.0 (untouched) to 1.0 (muted).
+///
+/// `device` is the cpal name of the selected output; `None`, or a name that
+/// can't be matched, ducks the default output. When `apps` is not empty only
+/// the streams of those applications are lowered, which needs PipeWire or
+/// PulseAudio; elsewhere the whole output is ducked instead.
+pub fn duck_output(
+    device: Option<&str>,
+    amount: f32,
+    apps: &[String],
+) -> Result<DuckedOutput, String> {
+    let amount = amount.clamp(0.0, 1.0);
+    if amount == 0.0 {
+        return Ok(DuckedOutput::default());
+    }
+    let device = device.filter(|name| !name.eq_ignore_ascii_case("default"));
+    let saved = duck_platform(device, amount, apps)?;
+    debug!("Ducked {} output(s) by {:.0}%", saved.len(), amount * 100.0);
+    Ok(DuckedOutput { saved })
+}
+
+impl DuckedOutput {
+    /// Put every volume and mute state back the way it was.
+    pub fn restore(self) -> Result<(), String> {
+        let errors: Vec<String> = self
+            .saved
+            .into_iter()
+            .filter_map(|saved| restore_volume(saved).err())
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
+
+fn restore_volume(saved: SavedVolume) -> Result<(), String> {
+    match saved {
+        #[cfg(target_os = "linux")]
+        SavedVolume::PulseSink {
+            name,
+            volumes,
+            muted,
+        } => set_pulse_volume("sink", &name, &volumes, muted),
+        #[cfg(target_os = "linux")]
+        SavedVolume::PulseStream {
+            index,
+            volumes,
+            muted,
+        } => {
+            // The application may have closed its stream in the meantime
+            if let Err(e) = set_pulse_volume("sink-input", &index.to_string(), &volumes, muted) {
+                debug!("Stream {} not restored: {}", index, e);
+            }
+            Ok(())
+        }
+        #[cfg(target_os = "linux")]
+        SavedVolume::Wpctl { volume, muted } => {
+            run("wpctl", &["set-volume", WPCTL_SINK, &volume])?;
+            run(
+                "wpctl",
+                &["set-mute", WPCTL_SINK, if muted { "1" } else { "0" }],
+            )
+            .map(drop)
+        }
+        #[cfg(target_os = "linux")]
+        SavedVolume::Amixer { percents, muted } => {
+            let level = percents
+                .iter()
+                .map(|p| format!("{}%", p))
+                .collect::<Vec<_>>()
+                .join(",");
+            let state = if muted { "mute" } else { "unmute" };
+            run("amixer", &["set", "Master", &level, state]).map(drop)
+        }
+        #[cfg(target_os = "windows")]
+        SavedVolume::Endpoint {
+            device,
+            level,
+            muted,
+        } => {
+            let volume = endpoint_volume(device.as_deref())?;
+            unsafe {
+                volume
+                    .SetMasterVolumeLevelScalar(level, std::ptr::null())
+                    .and_then(|_| volume.SetMute(muted, std::ptr::null()))
+                    .map_err(|e| e.to_string())
+            }
+        }
+        #[cfg(target_os = "macos")]
+        SavedVolume::AppleScript { volume, muted } => run(
+            "osascript",
+            &[
+                "-e",
+                &format!("set volume output volume {}", volume),
+                "-e",
+                &format!("set volume output muted {}", muted),
+            ],
+        )
+        .map(drop),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    // Keep the output parseable whatever the user's locale
+    let output = Command::new(program)
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "linux")]
+const WPCTL_SINK: &str = "@DEFAULT_AUDIO_SINK@";
+
+#[cfg(target_os = "linux")]
+fn duck_platform(
+    device: Option<&str>,
+    amount: f32,
+    apps: &[String],
+) -> Result<Vec<SavedVolume>, String> {
+    // pactl talks to PulseAudio and to PipeWire's pulse server alike
+    match run("pactl", &["list", "sinks"]) {
+        Ok(sinks) => {
+            let sinks = parse_pactl_list(&sinks, "Sink #");
+            return if apps.is_empty() {
+                duck_pulse_sink(&sinks, device, amount)
+            } else {
+                duck_pulse_streams(apps, amount)
+            };
+        }
+        Err(e) => debug!("pactl unavailable ({}), trying wpctl", e),
+    }
+
+    if !apps.is_empty() {
+        warn!("{}", APPS_UNSUPPORTED);
+    }
+    if let Some(device) = device {
+        debug!(
+            "Without pactl only the default output is ducked, not '{}'",
+            device
+        );
+    }
+    duck_wpctl(amount).or_else(|e| {
+        debug!("wpctl unavailable ({}), trying amixer", e);
+        duck_amixer(amount)
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn duck_pulse_sink(
+    sinks: &[PulseEntry],
+    device: Option<&str>,
+    amount: f32,
+) -> Result<Vec<SavedVolume>, String> {
+    let sink = match device.and_then(|name| find_sink(sinks, name, &read_asound_cards())) {
+        Some(sink) => sink,
+        None => {
+            if let Some(name) = device {
+                debug!("No sink matches '{}', ducking the default sink", name);
+            }
+            let default = run("pactl", &["get-default-sink"])?;
+            let default = default.trim();
+            sinks
+                .iter()
+                .find(|sink| sink.name == default)
+                .ok_or_else(|| format!("Default sink '{}' not found", default))?
+        }
+    };
+
+    duck_pulse_entry("sink", &sink.name, sink, amount)?;
+    Ok(vec![SavedVolume::PulseSink {
+        name: sink.name.clone(),
+        volumes: sink.volumes.clone(),
+        muted: sink.muted,
+    }])
+}
+
+#[cfg(target_os = "linux")]
+fn duck_pulse_streams(apps: &[String], amount: f32) -> Result<Vec<SavedVolume>, String> {
+    let streams = parse_pactl_list(&run("pactl", &["list", "sink-inputs"])?, "Sink Input #");
+    let mut saved = Vec::new();
+    for stream in streams.iter().filter(|stream| is_listed_app(stream, apps)) {
+        match duck_pulse_entry("sink-input", &stream.index.to_string(), stream, amount) {
+            Ok(()) => saved.push(SavedVolume::PulseStream {
+                index: stream.index,
+                volumes: stream.volumes.clone(),
+                muted: stream.muted,
+            }),
+            Err(e) => warn!("Failed to duck stream {}: {}", stream.index, e),
+        }
+    }
+    Ok(saved)
+}
+
+#[cfg(target_os = "linux")]
+fn duck_pulse_entry(kind: &str, id: &str, entry: &PulseEntry, amount: f32) -> Result<(), String> {
+    if amount >= 1.0 {
+        return run("pactl", &[&format!("set-{}-mute", kind), id, "1"]).map(drop);
+    }
+    let volumes: Vec<u32> = entry
+        .volumes
+        .iter()
+        .map(|v| (*v as f32 * (1.0 - amount)).round() as u32)
+        .collect();
+    set_pulse_volume(kind, id, &volumes, entry.muted)
+}
+
+#[cfg(target_os = "linux")]
+fn set_pulse_volume(kind: &str, id: &str, volumes: &[u32], muted: bool) -> Result<(), String> {
+    if !volumes.is_empty() {
+        let volumes: Vec<String> = volumes.iter().map(|v| v.to_string()).collect();
+        let mut args = vec![format!("set-{}-volume", kind), id.to_string()];
+        args.extend(volumes);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run("pactl", &args)?;
+    }
+    run(
+        "pactl",
+        &[
+            &format!("set-{}-mute", kind),
+            id,
+            if muted { "1" } else { "0" },
+        ],
+    )
+    .map(drop)
+}
+
+#[cfg(target_os = "linux")]
+fn duck_wpctl(amount: f32) -> Result<Vec<SavedVolume>, String> {
+    // e.g. "Volume: 0.40 [MUTED]"
+    let output = run("wpctl", &["get-volume", WPCTL_SINK])?;
+    let volume = output
+        .split_whitespace()
+        .nth(1)
+        .ok_or_else(|| format!("Unexpected wpctl output: {}", output.trim()))?
+        .to_string();
+    let muted = output.contains("[MUTED]");
+
+    if amount >= 1.0 {
+        run("wpctl", &["set-mute", WPCTL_SINK, "1"])?;
+    } else {
+        let level: f32 = volume
+            .parse()
+            .map_err(|_| format!("Unexpected wpctl volume: {}", volume))?;
+        let ducked = format!("{:.3}", level * (1.0 - amount));
+        run("wpctl", &["set-volume", WPCTL_SINK, &ducked])?;
+    }
+    Ok(vec![SavedVolume::Wpctl { volume, muted }])
+}
+
+#[cfg(target_os = "linux")]
+fn duck_amixer(amount: f32) -> Result<Vec<SavedVolume>, String> {
+    let (percents, muted) = parse_amixer(&run("amixer", &["get", "Master"])?)
+        .ok_or("Could not read the ALSA Master volume")?;
+
+    if amount >= 1.0 {
+        run("amixer", &["set", "Master", "mute"])?;
+    } else {
+        let level = percents
+            .iter()
+            .map(|p| format!("{}%", (*p as f32 * (1.0 - amount)).round()))
+            .collect::<Vec<_>>()
+            .join(",");
+        run("amixer", &["set", "Master", &level])?;
+    }
+    Ok(vec![SavedVolume::Amixer { percents, muted }])
+}
+
+#[cfg(target_os = "linux")]
+fn read_asound_cards() -> String {
+    std::fs::read_to_string("/proc/asound/cards").unwrap_or_default()
+}
+
+/// One sink or stream from `pactl list`.
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct PulseEntry {
+    index: u32,
+    name: String,
+    description: String,
+    muted: bool,
+    /// Raw volume per channel, 65536 being 100%
+    volumes: Vec<u32>,
+    properties: std::collections::HashMap<String, String>,
+}
+
+/// Split `pactl list sinks` / `pactl list sink-inputs` output (C locale)
+/// into entries, each starting at a line beginning with `header`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_pactl_list(output: &str, header: &str) -> Vec<PulseEntry> {
+    let mut entries: Vec<PulseEntry> = Vec::new();
+    for line in output.lines() {
+        if let Some(index) = line.strip_prefix(header) {
+            entries.push(PulseEntry {
+                index: index.trim().parse().unwrap_or_default(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(entry) = entries.last_mut() else {
+            continue;
+        };
+
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("Name:") {
+            entry.name = name.trim().to_string();
+        } else if let Some(description) = line.strip_prefix("Description:") {
+            entry.description = description.trim().to_string();
+        } else if let Some(mute) = line.strip_prefix("Mute:") {
+            entry.muted = mute.trim() == "yes";
+        } else if let Some(volume) = line.strip_prefix("Volume:") {
+            // e.g. "front-left: 32768 /  50% / -18.06 dB,   front-right: ..."
+            entry.volumes = volume
+                .split(',')
+                .filter_map(|channel| {
+                    let (_, value) = channel.split_once(':')?;
+                    value.split('/').next()?.trim().parse().ok()
+                })
+                .collect();
+        } else if let Some((key, value)) = line.split_once(" = ") {
+            entry
+                .properties
+                .insert(key.to_string(), value.trim_matches('"').to_string());
+        }
+    }
+    entries
+}
+
+/// The sink behind a cpal (ALSA) output name. PulseAudio and PipeWire plugin
+/// devices mean the default sink; hardware names such as
+/// `front:CARD=PCH,DEV=0` are matched through their card number, looked up
+/// in `asound_cards` (the contents of `/proc/asound/cards`).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn find_sink<'a>(
+    sinks: &'a [PulseEntry],
+    device: &str,
+    asound_cards: &str,
+) -> Option<&'a PulseEntry> {
+    if ["pulse", "pipewire", "sysdefault"].contains(&device) {
+        return None;
+    }
+    if let Some(sink) = sinks
+        .iter()
+        .find(|sink| sink.name == device || sink.description == device)
+    {
+        return Some(sink);
+    }
+
+    let card = device.split_once("CARD=")?.1.split(',').next()?;
+    // e.g. " 0 [PCH            ]: HDA-Intel - HDA Intel PCH"
+    let number = asound_cards.lines().find_map(|line| {
+        let (number, rest) = line.trim().split_once(' ')?;
+        let id = rest.trim().strip_prefix('[')?.split(']').next()?.trim();
+        (id == card).then(|| number.to_string())
+    })?;
+    sinks.iter().find(|sink| {
+        ["alsa.card", "api.alsa.card"]
+            .iter()
+            .any(|key| sink.properties.get(*key) == Some(&number))
+    })
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn is_listed_app(stream: &PulseEntry, apps: &[String]) -> bool {
+    ["application.name", "application.process.binary"]
+        .iter()
+        .filter_map(|key| stream.properties.get(*key))
+        .any(|value| apps.iter().any(|app| app.eq_ignore_ascii_case(value)))
+}
+
+/// Percent per channel and whether all channels are off, from `amixer get`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_amixer(output: &str) -> Option<(Vec<u32>, bool)> {
+    // e.g. "  Front Left: Playback 55705 [85%] [-7.50dB] [on]"
+    let channels: Vec<(u32, bool)> = output
+        .lines()
+        .filter(|line| line.contains("Playback") && line.contains('['))
+        .filter_map(|line| {
+            let percent = line.split('[').nth(1)?.split("%]").next()?.parse().ok()?;
+            Some((percent, line.contains("[off]")))
+        })
+        .collect();
+    if channels.is_empty() {
+        return None;
+    }
+    let muted = channels.iter().all(|(_, off)| *off);
+    Some((channels.into_iter().map(|(p, _)| p).collect(), muted))
+}
+
+#[cfg(target_os = "windows")]
+fn duck_platform(
+    device: Option<&str>,
+    amount: f32,
+    apps: &[String],
+) -> Result<Vec<SavedVolume>, String> {
+    if !apps.is_empty() {
+        warn!("{}", APPS_UNSUPPORTED);
+    }
+    let volume = endpoint_volume(device)?;
+    unsafe {
+        let level = volume
+            .GetMasterVolumeLevelScalar()
+            .map_err(|e| e.to_string())?;
+        let muted = volume.GetMute().map_err(|e| e.to_string())?.as_bool();
+        let result = if amount >= 1.0 {
+            volume.SetMute(true, std::ptr::null())
+        } else {
+            volume.SetMasterVolumeLevelScalar(level * (1.0 - amount), std::ptr::null())
+        };
+        result.map_err(|e| e.to_string())?;
+
+        Ok(vec![SavedVolume::Endpoint {
+            device: device.map(str::to_string),
+            level,
+            muted,
+        }])
+    }
+}
+
+/// Volume control of the output whose friendly name is `device` (the name
+/// cpal reports), falling back to the default output.
+#[cfg(target_os = "windows")]
+fn endpoint_volume(
+    device: Option<&str>,
+) -> Result<windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume, String> {
+    use windows::Win32::{
+        Devices::FunctionDiscovery::PKEY_Device_FriendlyName,
+        Media::Audio::{
+            eMultimedia, eRender, Endpoints::IAudioEndpointVolume, IMMDeviceEnumerator,
+            MMDeviceEnumerator, DEVICE_STATE_ACTIVE,
+        },
+        System::Com::{
+            CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED, STGM_READ,
+        },
+    };
+
+    unsafe {
+        // Initialize the COM library for this thread.
+        // If already initialized (e.g., by another library like Tauri), this does nothing.
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(|e| e.to_string())?;
+
+        let mut endpoint = None;
+        if let Some(name) = device {
+            let devices = enumerator
+                .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
+                .map_err(|e| e.to_string())?;
+            for i in 0..devices.GetCount().map_err(|e| e.to_string())? {
+                let Ok(candidate) = devices.Item(i) else {
+                    continue;
+                };
+                let friendly_name = candidate
+                    .OpenPropertyStore(STGM_READ)
+                    .and_then(|store| store.GetValue(&PKEY_Device_FriendlyName))
+                    .map(|value| value.to_string())
+                    .unwrap_or_default();
+                if friendly_name == name {
+                    endpoint = Some(candidate);
+                    break;
+                }
+            }
+            if endpoint.is_none() {
+                debug!("No output named '{}', ducking the default output", name);
+            }
+        }
+
+        let endpoint = match endpoint {
+            Some(endpoint) => endpoint,
+            None => enumerator
+                .GetDefaultAudioEndpoint(eRender, eMultimedia)
+                .map_err(|e| e.to_string())?,
+        };
+        endpoint
+            .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn duck_platform(
+    device: Option<&str>,
+    amount: f32,
+    apps: &[String],
+) -> Result<Vec<SavedVolume>, String> {
+    if !apps.is_empty() {
+        warn!("{}", APPS_UNSUPPORTED);
+    }
+    if let Some(device) = device {
+        debug!(
+            "AppleScript can only duck the default output, not '{}'",
+            device
+        );
+    }
+
+    // e.g. "output volume:44, input volume:50, alert volume:100, output muted:false"
+    let settings = run("osascript", &["-e", "get volume settings"])?;
+    let field = |key: &str| {
+        settings
+            .split(',')
+            .find_map(|part| part.trim().strip_prefix(key))
+            .map(str::trim)
+    };
+    let volume: u32 = field("output volume:")
+        .and_then(|v| v.parse().ok())
+        .ok_or("The default output has no adjustable volume")?;
+    let muted = field("output muted:") == Some("true");
+
+    let script = if amount >= 1.0 {
+        "set volume output muted true".to_string()
+    } else {
+        format!(
+            "set volume output volume {}",
+            (volume as f32 * (1.0 - amount)).round()
+        )
+    };
+    run("osascript", &["-e", &script])?;
+    Ok(vec![SavedVolume::AppleScript { volume, muted }])
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn duck_platform(
+    _device: Option<&str>,
+    _amount: f32,
+    _apps: &[String],
+) -> Result<Vec<SavedVolume>, String> {
+    Err("Ducking is not supported on this platform".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SINKS: &str = "Sink #57
+\tState: RUNNING
+\tName: alsa_output.pci-0000_00_1f.3.analog-stereo
+\tDescription: Built-in Audio Analog Stereo
+\tMute: no
+\tVolume: front-left: 32768 /  50% / -18.06 dB,   front-right: 36045 /  55% / -15.58 dB
+\t        balance 0.10
+\tBase Volume: 65536 / 100% / 0.00 dB
+\tProperties:
+\t\talsa.card = \"0\"
+\t\talsa.card_name = \"HDA Intel PCH\"
+
+Sink #58
+\tName: alsa_output.usb-headset.analog-stereo
+\tDescription: USB Headset
+\tMute: yes
+\tVolume: mono: 65536 / 100% / 0.00 dB
+\tProperties:
+\t\tapi.alsa.card = \"2\"
+";
+
+    #[test]
+    fn test_parse_pactl_list() {
+        let sinks = parse_pactl_list(SINKS, "Sink #");
+        assert_eq!(sinks.len(), 2);
+        assert_eq!(sinks[0].index, 57);
+        assert_eq!(sinks[0].description, "Built-in Audio Analog Stereo");
+        assert_eq!(sinks[0].volumes, vec![32768, 36045]);
+        assert!(!sinks[0].muted);
+        assert_eq!(sinks[1].volumes, vec![65536]);
+        assert!(sinks[1].muted);
+        assert_eq!(
+            sinks[1].properties.get("api.alsa.card"),
+            Some(&"2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_find_sink() {
+        let sinks = parse_pactl_list(SINKS, "Sink #");
+        let cards = " 0 [PCH            ]: HDA-Intel - HDA Intel PCH
+                      HDA Intel PCH at 0xf7f10000 irq 32
+ 2 [Headset        ]: USB-Audio - USB Headset
+";
+        let index = |device| find_sink(&sinks, device, cards).map(|sink| sink.index);
+        assert_eq!(index("front:CARD=Headset,DEV=0"), Some(58));
+        assert_eq!(index("sysdefault:CARD=PCH"), Some(57));
+        assert_eq!(index("Built-in Audio Analog Stereo"), Some(57));
+        assert_eq!(index("pipewire"), None);
+        assert_eq!(index("hw:CARD=Missing,DEV=0"), None);
+    }
+
+    #[test]
+    fn test_parse_amixer() {
+        let output = "Simple mixer control 'Master',0
+  Playback channels: Front Left - Front Right
+  Limits: Playback 0 - 65536
+  Front Left: Playback 55705 [85%] [on]
+  Front Right: Playback 52429 [80%] [on]
+";
+        assert_eq!(parse_amixer(output), Some((vec![85, 80], false)));
+        assert_eq!(
+            parse_amixer("  Mono: Playback 0 [0%] [-65.25dB] [off]"),
+            Some((vec![0], true))
+        );
+        assert_eq!(parse_amixer("Simple mixer control 'Master',0"), None);
+    }
+}
@@ -0,0 +1,180 @@
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use log::debug;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Application that had focus when the current recording started.
+/// Stored at `TranscribeAction::start` so the rule still applies after the
+/// user has clicked elsewhere while transcription runs.
+#[derive(Default)]
+pub struct FocusedAppState(pub Mutex<Option<String>>);
+
+/// Identify the application owning the focused window.
+///
+/// Returns the X11 WM_CLASS class on Linux, the process name on macOS and the
+/// executable name (without extension) on Windows.
+#[cfg(target_os = "linux")]
+pub fn get_focused_app() -> Option<String> {
+    // Under Wayland this only works for XWayland clients; native Wayland
+    // compositors don't expose the focused window to other clients.
+    let output = Command::new("xprop")
+        .args(["-root", "_NET_ACTIVE_WINDOW"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        debug!("xprop failed to read _NET_ACTIVE_WINDOW");
+        return None;
+    }
+
+    // e.g. "_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let window_id = stdout.split_whitespace().last()?;
+    if !window_id.starts_with("0x") || window_id == "0x0" {
+        return None;
+    }
+
+    let output = Command::new("xprop")
+        .args(["-id", window_id, "WM_CLASS"])
+        .output()
+        .ok()?;
+    parse_wm_class(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "macos")]
+pub fn get_focused_app() -> Option<String> {
+    let output = Command::new("osascript")
+        .args([
+            "-e",
+            "tell application \"System Events\" to get name of first application process whose frontmost is true",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        debug!("osascript failed to read the frontmost application");
+        return None;
+    }
+
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_focused_app() -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return None;
+        }
+
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        std::path::Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn get_focused_app() -> Option<String> {
+    None
+}
+
+/// Whether `app_name` (as returned by [`get_focused_app`]) is a terminal
+/// emulator, where Ctrl+C interrupts the running program instead of copying.
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub fn is_terminal(app_name: &str) -> bool {
+    const TERMINALS: &[&str] = &[
+        "alacritty",
+        "cmd",
+        "conhost",
+        "foot",
+        "ghostty",
+        "hyper",
+        "iterm2",
+        "kitty",
+        "konsole",
+        "powershell",
+        "pwsh",
+        "st",
+        "tabby",
+        "terminator",
+        "tilix",
+        "urxvt",
+        "warp",
+        "wezterm",
+        "wezterm-gui",
+        "windowsterminal",
+        "xterm",
+    ];
+    let name = app_name.to_lowercase();
+    name.contains("terminal") || TERMINALS.contains(&name.as_str())
+}
+
+/// Extract the class name from `xprop WM_CLASS` output.
+///
+/// WM_CLASS holds the instance and class names, e.g.
+/// `WM_CLASS(STRING) = "gnome-terminal-server", "Gnome-terminal"`.
+/// The class (second value) is the more stable identifier.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_wm_class(output: &str) -> Option<String> {
+    let (_, values) = output.split_once('=')?;
+    values
+        .split(',')
+        .map(|value| value.trim().trim_matches('"'))
+        .rfind(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wm_class() {
+        assert_eq!(
+            parse_wm_class("WM_CLASS(STRING) = \"gnome-terminal-server\", \"Gnome-terminal\"\n"),
+            Some("Gnome-terminal".to_string())
+        );
+        assert_eq!(
+            parse_wm_class("WM_CLASS(STRING) = \"code\""),
+            Some("code".to_string())
+        );
+        assert_eq!(parse_wm_class("WM_CLASS:  not found."), None);
+    }
+
+    #[test]
+    fn test_is_terminal() {
+        assert!(is_terminal("Gnome-terminal"));
+        assert!(is_terminal("iTerm2"));
+        assert!(is_terminal("WindowsTerminal"));
+        assert!(is_terminal("kitty"));
+        assert!(!is_terminal("code"));
+        assert!(!is_terminal("Slack"));
+    }
+}
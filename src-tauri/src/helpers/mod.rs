@@ -1 +1,3 @@
 pub mod clamshell;
+pub mod ducking;
+pub mod focused_app;
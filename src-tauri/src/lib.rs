@@ -10,8 +10,11 @@ mod google_auth;
 mod helpers;
 mod input;
 mod llm_client;
+#[cfg(feature = "local-llm")]
+mod local_llm;
 mod managers;
 mod overlay;
+mod post_process;
 mod gemini_popup;
 mod settings;
 mod shortcut;
@@ -131,6 +134,15 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     let history_manager =
         Arc::new(HistoryManager::new(app_handle).expect("Failed to initialize history manager"));
     let gemini_conversation_manager = Arc::new(GeminiConversationManager::new());
+    let meeting_manager = Arc::new(
+        managers::meeting::MeetingManager::new(app_handle)
+            .expect("Failed to initialize meeting manager"),
+    );
+    #[cfg(feature = "local-llm")]
+    let local_llm_manager = Arc::new(managers::local_llm::LocalLlmManager::new(
+        app_handle,
+        model_manager.clone(),
+    ));
 
     // Add managers to Tauri's managed state
     app_handle.manage(recording_manager.clone());
@@ -138,6 +150,10 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     app_handle.manage(transcription_manager.clone());
     app_handle.manage(history_manager.clone());
     app_handle.manage(gemini_conversation_manager.clone());
+    app_handle.manage(meeting_manager);
+    #[cfg(feature = "local-llm")]
+    app_handle.manage(local_llm_manager);
+    app_handle.manage(helpers::focused_app::FocusedAppState::default());
     
     // Initialize IP address cache
     app_handle.manage(std::sync::Arc::new(std::sync::Mutex::new(None::<String>)));
@@ -197,6 +213,16 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             "quit" => {
                 app.exit(0);
             }
+            tray::NO_PROFILE_MENU_ID => {
+                if let Err(e) = commands::profiles::activate_profile(app, None) {
+                    log::warn!("Failed to deactivate profile from tray: {}", e);
+                }
+            }
+            id if id.starts_with(settings::PROFILE_ID_PREFIX) => {
+                if let Err(e) = commands::profiles::activate_profile(app, Some(id)) {
+                    log::warn!("Failed to activate profile from tray: {}", e);
+                }
+            }
             _ => {}
         })
         .build(app_handle)
@@ -283,22 +309,55 @@ pub fn run() {
         shortcut::change_overlay_position_setting,
         shortcut::change_debug_mode_setting,
         shortcut::change_word_correction_threshold_setting,
+        shortcut::change_typing_wpm_setting,
         shortcut::change_paste_method_setting,
+        shortcut::change_recording_format_setting,
         shortcut::change_clipboard_handling_setting,
         shortcut::change_post_process_enabled_setting,
         shortcut::change_post_process_base_url_setting,
+        shortcut::change_post_process_dialect_setting,
         shortcut::change_post_process_api_key_setting,
         shortcut::change_post_process_model_setting,
         shortcut::set_post_process_provider,
         shortcut::fetch_post_process_models,
         shortcut::add_post_process_prompt,
         shortcut::update_post_process_prompt,
+        shortcut::update_post_process_prompt_options,
+        shortcut::change_post_process_streaming_setting,
+        shortcut::change_post_process_timeout_setting,
+        shortcut::change_post_process_provider_timeout_setting,
+        shortcut::update_post_process_fallbacks,
+        shortcut::change_post_process_breaker_setting,
+        shortcut::change_hands_free_silence_timeout_setting,
+        shortcut::change_meeting_speaker_detection_setting,
+        shortcut::change_meeting_max_speakers_setting,
+        shortcut::change_vad_backend_setting,
+        shortcut::change_vad_threshold_setting,
+        shortcut::change_vad_energy_threshold_setting,
+        shortcut::change_vad_smoothing_setting,
+        shortcut::change_noise_suppression_setting,
+        shortcut::change_high_pass_filter_setting,
+        shortcut::change_auto_gain_control_setting,
+        shortcut::change_pre_roll_setting,
         shortcut::delete_post_process_prompt,
         shortcut::set_post_process_selected_prompt,
+        shortcut::preview_post_process_prompt,
+        shortcut::update_post_process_pipeline,
         shortcut::update_custom_words,
+        shortcut::update_vocabulary,
+        shortcut::update_vocabulary_regex_rules,
+        shortcut::update_app_rules,
+        shortcut::change_whisper_initial_prompt_setting,
+        shortcut::change_whisper_prompt_include_previous_setting,
+        shortcut::change_text_normalization_enabled_setting,
+        shortcut::update_text_normalization_options,
+        shortcut::change_voice_commands_enabled_setting,
+        shortcut::update_custom_voice_commands,
         shortcut::suspend_binding,
         shortcut::resume_binding,
         shortcut::change_mute_while_recording_setting,
+        shortcut::change_duck_amount_setting,
+        shortcut::change_duck_apps_setting,
         shortcut::change_append_trailing_space_setting,
         shortcut::change_app_language_setting,
         shortcut::change_update_checks_setting,
@@ -340,6 +399,8 @@ pub fn run() {
         commands::audio::get_microphone_mode,
         commands::audio::get_available_microphones,
         commands::audio::set_selected_microphone,
+        commands::audio::set_microphone_priority,
+        commands::audio::calibrate_microphone,
         commands::audio::get_selected_microphone,
         commands::audio::get_available_output_devices,
         commands::audio::set_selected_output_device,
@@ -349,6 +410,10 @@ pub fn run() {
         commands::audio::set_clamshell_microphone,
         commands::audio::get_clamshell_microphone,
         commands::audio::is_recording,
+        commands::audio::get_system_audio_devices,
+        commands::audio::set_system_audio_device,
+        commands::audio::set_capture_source,
+        commands::audio::set_capture_gains,
         commands::transcription::set_model_unload_timeout,
         commands::transcription::get_model_load_status,
         commands::transcription::unload_model_manually,
@@ -356,6 +421,15 @@ pub fn run() {
         commands::history::toggle_history_entry_saved,
         commands::history::get_audio_file_path,
         commands::history::delete_history_entry,
+        commands::history::get_usage_statistics,
+        commands::profiles::get_dictation_profiles,
+        commands::profiles::add_dictation_profile,
+        commands::profiles::update_dictation_profile,
+        commands::profiles::delete_dictation_profile,
+        commands::profiles::set_active_dictation_profile,
+        shortcut::set_dictation_profile_shortcut,
+        commands::history::retranscribe_history_entry,
+        commands::history::convert_recordings_format,
         commands::history::update_history_limit,
         commands::history::update_recording_retention_period,
         helpers::clamshell::is_laptop,
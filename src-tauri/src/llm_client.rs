@@ -1,7 +1,45 @@
-use crate::settings::PostProcessProvider;
-use log::debug;
+use crate::settings::{PostProcessProvider, ProviderDialect};
+use log::{debug, warn};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, REFERER, USER_AGENT};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// The Messages API requires `max_tokens`; used when the prompt leaves it unset
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// A chat completion to send to a provider.
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+    /// Sent as a separate system message when not empty
+    pub system: String,
+    pub user: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Upper bound for the whole request, including a streamed response
+    pub timeout: Duration,
+}
+
+impl ChatRequest {
+    /// System and user text as a single prompt, for providers without roles.
+    #[cfg_attr(
+        not(all(target_os = "macos", target_arch = "aarch64")),
+        allow(dead_code)
+    )]
+    pub fn combined_prompt(&self) -> String {
+        if self.system.trim().is_empty() {
+            self.user.clone()
+        } else {
+            format!("{}\n\n{}", self.system, self.user)
+        }
+    }
+
+    fn system(&self) -> Option<String> {
+        (!self.system.trim().is_empty()).then(|| self.system.clone())
+    }
+}
+
+/* ──────────────────────────── OpenAI-compatible ──────────────────────────── */
 
 #[derive(Debug, Serialize)]
 struct ChatMessage {
@@ -13,6 +51,35 @@ struct ChatMessage {
 struct ChatCompletionRequest {
     model: String,
     messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+impl ChatCompletionRequest {
+    fn new(model: &str, request: &ChatRequest, stream: bool) -> Self {
+        let mut messages = Vec::new();
+        if let Some(system) = request.system() {
+            messages.push(ChatMessage {
+                role: "system".to_string(),
+                content: system,
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: request.user.clone(),
+        });
+        Self {
+            model: model.to_string(),
+            messages,
+            temperature: request.temperature,
+            max_tokens: request.max_tokens,
+            stream,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -30,7 +97,320 @@ struct ChatMessageResponse {
     content: Option<String>,
 }
 
-/// Build headers for API requests based on provider type
+#[derive(Debug, Deserialize)]
+struct ChatCompletionChunk {
+    choices: Vec<ChatChunkChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunkChoice {
+    delta: ChatMessageResponse,
+}
+
+/* ──────────────────────────── Anthropic Messages ─────────────────────────── */
+
+#[derive(Debug, Serialize)]
+struct AnthropicRequest {
+    model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<ChatMessage>,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+impl AnthropicRequest {
+    fn new(model: &str, request: &ChatRequest, stream: bool) -> Self {
+        Self {
+            model: model.to_string(),
+            system: request.system(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: request.user.clone(),
+            }],
+            max_tokens: request.max_tokens.unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS),
+            temperature: request.temperature,
+            stream,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContentBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicStreamEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    delta: Option<AnthropicDelta>,
+    #[serde(default)]
+    error: Option<AnthropicError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicDelta {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicError {
+    #[serde(default)]
+    message: String,
+}
+
+/* ─────────────────────────── Gemini generateContent ──────────────────────── */
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GeminiGenerationConfig>,
+}
+
+impl GeminiRequest {
+    fn new(request: &ChatRequest) -> Self {
+        let generation_config = (request.temperature.is_some() || request.max_tokens.is_some())
+            .then_some(GeminiGenerationConfig {
+                temperature: request.temperature,
+                max_output_tokens: request.max_tokens,
+            });
+        Self {
+            contents: vec![GeminiContent::text(Some("user"), request.user.clone())],
+            system_instruction: request
+                .system()
+                .map(|system| GeminiContent::text(None, system)),
+            generation_config,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GeminiContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+impl GeminiContent {
+    fn text(role: Option<&str>, text: String) -> Self {
+        Self {
+            role: role.map(str::to_string),
+            parts: vec![GeminiPart { text: Some(text) }],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GeminiPart {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiContent>,
+}
+
+impl GeminiResponse {
+    fn text(self) -> String {
+        self.candidates
+            .into_iter()
+            .next()
+            .and_then(|candidate| candidate.content)
+            .map(|content| {
+                content
+                    .parts
+                    .into_iter()
+                    .filter_map(|part| part.text)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/* ──────────────────────────────── Dialects ───────────────────────────────── */
+
+/// What one server-sent event means for the response being assembled.
+#[derive(Debug, PartialEq)]
+enum StreamEvent {
+    Delta(String),
+    Done,
+    Ignore,
+}
+
+/// URL for a completion request. Gemini puts the model and the streaming
+/// mode in the path; the others take them in the body.
+fn completion_url(provider: &PostProcessProvider, model: &str, stream: bool) -> String {
+    let base_url = provider.base_url.trim_end_matches('/');
+    match provider.dialect {
+        ProviderDialect::OpenAi => format!("{}/chat/completions", base_url),
+        ProviderDialect::Anthropic => format!("{}/messages", base_url),
+        ProviderDialect::Gemini => {
+            let model = model.trim_start_matches("models/");
+            if stream {
+                format!(
+                    "{}/models/{}:streamGenerateContent?alt=sse",
+                    base_url, model
+                )
+            } else {
+                format!("{}/models/{}:generateContent", base_url, model)
+            }
+        }
+    }
+}
+
+fn completion_body(
+    dialect: ProviderDialect,
+    model: &str,
+    request: &ChatRequest,
+    stream: bool,
+) -> Result<serde_json::Value, String> {
+    let body = match dialect {
+        ProviderDialect::OpenAi => {
+            serde_json::to_value(ChatCompletionRequest::new(model, request, stream))
+        }
+        ProviderDialect::Anthropic => {
+            serde_json::to_value(AnthropicRequest::new(model, request, stream))
+        }
+        ProviderDialect::Gemini => serde_json::to_value(GeminiRequest::new(request)),
+    };
+    body.map_err(|e| format!("Failed to encode request: {}", e))
+}
+
+fn parse_completion(dialect: ProviderDialect, body: &str) -> Result<Option<String>, String> {
+    let parse_error = |e: serde_json::Error| format!("Failed to parse API response: {}", e);
+    let content = match dialect {
+        ProviderDialect::OpenAi => {
+            let completion: ChatCompletionResponse =
+                serde_json::from_str(body).map_err(parse_error)?;
+            completion
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.message.content)
+        }
+        ProviderDialect::Anthropic => {
+            let message: AnthropicResponse = serde_json::from_str(body).map_err(parse_error)?;
+            let text: String = message
+                .content
+                .into_iter()
+                .filter(|block| block.kind == "text")
+                .filter_map(|block| block.text)
+                .collect();
+            (!text.is_empty()).then_some(text)
+        }
+        ProviderDialect::Gemini => {
+            let response: GeminiResponse = serde_json::from_str(body).map_err(parse_error)?;
+            let text = response.text();
+            (!text.is_empty()).then_some(text)
+        }
+    };
+    Ok(content)
+}
+
+fn parse_stream_event(dialect: ProviderDialect, data: &str) -> Result<StreamEvent, String> {
+    if dialect == ProviderDialect::OpenAi && data == "[DONE]" {
+        return Ok(StreamEvent::Done);
+    }
+    let delta = match dialect {
+        ProviderDialect::OpenAi => serde_json::from_str::<ChatCompletionChunk>(data).map(|chunk| {
+            chunk
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.delta.content)
+        }),
+        ProviderDialect::Anthropic => {
+            let event: AnthropicStreamEvent = match serde_json::from_str(data) {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Ignoring unexpected stream event '{}': {}", data, e);
+                    return Ok(StreamEvent::Ignore);
+                }
+            };
+            match event.kind.as_str() {
+                "content_block_delta" => Ok(event.delta.and_then(|delta| delta.text)),
+                "message_stop" => return Ok(StreamEvent::Done),
+                "error" => {
+                    let message = event.error.map(|e| e.message).unwrap_or_default();
+                    return Err(format!("Provider reported an error: {}", message));
+                }
+                _ => Ok(None),
+            }
+        }
+        ProviderDialect::Gemini => {
+            serde_json::from_str::<GeminiResponse>(data).map(|response| Some(response.text()))
+        }
+    };
+    match delta {
+        Ok(Some(text)) if !text.is_empty() => Ok(StreamEvent::Delta(text)),
+        Ok(_) => Ok(StreamEvent::Ignore),
+        Err(e) => {
+            warn!("Ignoring unexpected stream event '{}': {}", data, e);
+            Ok(StreamEvent::Ignore)
+        }
+    }
+}
+
+/// Splits a `text/event-stream` body into the payloads of its `data:` lines.
+/// Chunks may end anywhere, including inside a UTF-8 sequence.
+#[derive(Debug, Default)]
+struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if let Some(data) = line.strip_prefix("data:") {
+                events.push(data.trim_start().to_string());
+            }
+        }
+        events
+    }
+}
+
+/// Build headers for API requests based on the provider's dialect
 fn build_headers(provider: &PostProcessProvider, api_key: &str) -> Result<HeaderMap, String> {
     let mut headers = HeaderMap::new();
 
@@ -46,21 +426,28 @@ fn build_headers(provider: &PostProcessProvider, api_key: &str) -> Result<Header
     );
     headers.insert("X-Title", HeaderValue::from_static("Handy"));
 
-    // Provider-specific auth headers
+    if provider.dialect == ProviderDialect::Anthropic {
+        headers.insert(
+            "anthropic-version",
+            HeaderValue::from_static(ANTHROPIC_VERSION),
+        );
+    }
+
+    // Dialect-specific auth headers
     if !api_key.is_empty() {
-        if provider.id == "anthropic" {
-            headers.insert(
-                "x-api-key",
-                HeaderValue::from_str(api_key)
-                    .map_err(|e| format!("Invalid API key header value: {}", e))?,
-            );
-            headers.insert("anthropic-version", HeaderValue::from_static("2023-06-01"));
-        } else {
-            headers.insert(
-                AUTHORIZATION,
-                HeaderValue::from_str(&format!("Bearer {}", api_key))
-                    .map_err(|e| format!("Invalid authorization header value: {}", e))?,
-            );
+        let key_header = |value: &str| {
+            HeaderValue::from_str(value).map_err(|e| format!("Invalid API key header value: {}", e))
+        };
+        match provider.dialect {
+            ProviderDialect::Anthropic => {
+                headers.insert("x-api-key", key_header(api_key)?);
+            }
+            ProviderDialect::Gemini => {
+                headers.insert("x-goog-api-key", key_header(api_key)?);
+            }
+            ProviderDialect::OpenAi => {
+                headers.insert(AUTHORIZATION, key_header(&format!("Bearer {}", api_key))?);
+            }
         }
     }
 
@@ -76,36 +463,36 @@ fn create_client(provider: &PostProcessProvider, api_key: &str) -> Result<reqwes
         .map_err(|e| format!("Failed to build HTTP client: {}", e))
 }
 
-/// Send a chat completion request to an OpenAI-compatible API
-/// Returns Ok(Some(content)) on success, Ok(None) if response has no content,
-/// or Err on actual errors (HTTP, parsing, etc.)
-pub async fn send_chat_completion(
+async fn post_completion(
     provider: &PostProcessProvider,
-    api_key: String,
+    api_key: &str,
     model: &str,
-    prompt: String,
-) -> Result<Option<String>, String> {
-    let base_url = provider.base_url.trim_end_matches('/');
-    let url = format!("{}/chat/completions", base_url);
+    request: &ChatRequest,
+    stream: bool,
+) -> Result<reqwest::Response, String> {
+    let url = completion_url(provider, model, stream);
+    let body = completion_body(provider.dialect, model, request, stream)?;
 
-    debug!("Sending chat completion request to: {}", url);
-
-    let client = create_client(provider, &api_key)?;
+    debug!(
+        "Sending {:?} completion request to: {} (stream: {})",
+        provider.dialect, url, stream
+    );
 
-    let request_body = ChatCompletionRequest {
-        model: model.to_string(),
-        messages: vec![ChatMessage {
-            role: "user".to_string(),
-            content: prompt,
-        }],
-    };
+    let client = create_client(provider, api_key)?;
 
     let response = client
         .post(&url)
-        .json(&request_body)
+        .json(&body)
+        .timeout(request.timeout)
         .send()
         .await
-        .map_err(|e| format!("HTTP request failed: {}", e))?;
+        .map_err(|e| {
+            if e.is_timeout() {
+                format!("Request timed out after {:?}", request.timeout)
+            } else {
+                format!("HTTP request failed: {}", e)
+            }
+        })?;
 
     let status = response.status();
     if !status.is_success() {
@@ -119,18 +506,72 @@ pub async fn send_chat_completion(
         ));
     }
 
-    let completion: ChatCompletionResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+    Ok(response)
+}
+
+/// Send a completion request in the provider's dialect
+/// Returns Ok(Some(content)) on success, Ok(None) if response has no content,
+/// or Err on actual errors (HTTP, parsing, timeout, etc.)
+pub async fn send_chat_completion(
+    provider: &PostProcessProvider,
+    api_key: String,
+    model: &str,
+    request: &ChatRequest,
+) -> Result<Option<String>, String> {
+    let response = post_completion(provider, &api_key, model, request, false).await?;
+
+    let body = response.text().await.map_err(|e| {
+        if e.is_timeout() {
+            format!("Request timed out after {:?}", request.timeout)
+        } else {
+            format!("Failed to read API response: {}", e)
+        }
+    })?;
 
-    Ok(completion
-        .choices
-        .first()
-        .and_then(|choice| choice.message.content.clone()))
+    parse_completion(provider.dialect, &body)
 }
 
-/// Fetch available models from an OpenAI-compatible API
+/// Like `send_chat_completion`, but asks for a server-sent event stream and
+/// passes each piece of content to `on_delta` as it arrives. Returns the
+/// complete content once the stream ends.
+pub async fn stream_chat_completion(
+    provider: &PostProcessProvider,
+    api_key: String,
+    model: &str,
+    request: &ChatRequest,
+    on_delta: &mut (dyn FnMut(&str) + Send),
+) -> Result<Option<String>, String> {
+    let mut response = post_completion(provider, &api_key, model, request, true).await?;
+
+    let mut parser = SseParser::default();
+    let mut content = String::new();
+    'stream: loop {
+        let chunk = response.chunk().await.map_err(|e| {
+            if e.is_timeout() {
+                format!("Stream timed out after {:?}", request.timeout)
+            } else {
+                format!("Failed to read response stream: {}", e)
+            }
+        })?;
+        let Some(chunk) = chunk else {
+            break;
+        };
+        for data in parser.push(&chunk) {
+            match parse_stream_event(provider.dialect, &data)? {
+                StreamEvent::Delta(delta) => {
+                    on_delta(&delta);
+                    content.push_str(&delta);
+                }
+                StreamEvent::Done => break 'stream,
+                StreamEvent::Ignore => {}
+            }
+        }
+    }
+
+    Ok((!content.is_empty()).then_some(content))
+}
+
+/// Fetch available models from the provider
 /// Returns a list of model IDs
 pub async fn fetch_models(
     provider: &PostProcessProvider,
@@ -178,6 +619,24 @@ pub async fn fetch_models(
             }
         }
     }
+    // Handle Gemini format: { models: [ { name: "models/..." }, ... ] }
+    else if let Some(list) = parsed.get("models").and_then(|m| m.as_array()) {
+        for entry in list {
+            let supports_generate = entry
+                .get("supportedGenerationMethods")
+                .and_then(|m| m.as_array())
+                .is_none_or(|methods| {
+                    methods
+                        .iter()
+                        .any(|method| method.as_str() == Some("generateContent"))
+                });
+            if let Some(name) = entry.get("name").and_then(|n| n.as_str()) {
+                if supports_generate {
+                    models.push(name.trim_start_matches("models/").to_string());
+                }
+            }
+        }
+    }
     // Handle array format: [ "model1", "model2", ... ]
     else if let Some(array) = parsed.as_array() {
         for entry in array {
@@ -189,3 +648,297 @@ pub async fn fetch_models(
 
     Ok(models)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    struct MockRequest {
+        path: String,
+        headers: Vec<(String, String)>,
+        body: serde_json::Value,
+    }
+
+    impl MockRequest {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Serves one request on a local port with a canned response and hands
+    /// back what the client sent.
+    fn mock_server(
+        status: &'static str,
+        content_type: &'static str,
+        body: &'static str,
+    ) -> (String, mpsc::Receiver<MockRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                let (key, value) = line.split_once(':').unwrap();
+                headers.push((key.trim().to_lowercase(), value.trim().to_string()));
+            }
+            let length = headers
+                .iter()
+                .find(|(key, _)| key == "content-length")
+                .map_or(0, |(_, value)| value.parse().unwrap());
+            let mut request_body = vec![0; length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            (&stream).write_all(response.as_bytes()).unwrap();
+            let _ = tx.send(MockRequest {
+                path,
+                headers,
+                body: serde_json::from_slice(&request_body).unwrap_or_default(),
+            });
+        });
+        (format!("http://{}/v1", address), rx)
+    }
+
+    fn provider(dialect: ProviderDialect, base_url: String) -> PostProcessProvider {
+        PostProcessProvider {
+            id: "test".to_string(),
+            label: "Test".to_string(),
+            base_url,
+            allow_base_url_edit: true,
+            models_endpoint: None,
+            dialect,
+        }
+    }
+
+    fn request(system: &str) -> ChatRequest {
+        ChatRequest {
+            system: system.to_string(),
+            user: "fix this".to_string(),
+            temperature: Some(0.2),
+            max_tokens: None,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    fn complete(
+        provider: &PostProcessProvider,
+        request: &ChatRequest,
+    ) -> Result<Option<String>, String> {
+        tauri::async_runtime::block_on(send_chat_completion(
+            provider,
+            "secret".to_string(),
+            "model-1",
+            request,
+        ))
+    }
+
+    fn stream(provider: &PostProcessProvider) -> (Result<Option<String>, String>, Vec<String>) {
+        let mut deltas = Vec::new();
+        let result = tauri::async_runtime::block_on(stream_chat_completion(
+            provider,
+            "secret".to_string(),
+            "model-1",
+            &request(""),
+            &mut |delta: &str| deltas.push(delta.to_string()),
+        ));
+        (result, deltas)
+    }
+
+    #[test]
+    fn test_sse_parser_split_chunks() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"data: {\"a\":").is_empty());
+        assert_eq!(
+            parser.push(b"1}\r\n\ndata: [DONE]\n"),
+            vec!["{\"a\":1}", "[DONE]"]
+        );
+    }
+
+    #[test]
+    fn test_sse_parser_split_utf8() {
+        let mut parser = SseParser::default();
+        let line = "data: caf\u{e9}\n".as_bytes();
+        let split = line.len() - 2;
+        assert!(parser.push(&line[..split]).is_empty());
+        assert_eq!(parser.push(&line[split..]), vec!["caf\u{e9}"]);
+    }
+
+    #[test]
+    fn test_sse_parser_ignores_other_fields() {
+        let mut parser = SseParser::default();
+        let events = parser.push(b": keep-alive\nevent: message\ndata:x\n\n");
+        assert_eq!(events, vec!["x"]);
+    }
+
+    #[test]
+    fn test_openai_dialect() {
+        let (base_url, rx) = mock_server(
+            "200 OK",
+            "application/json",
+            r#"{"choices":[{"message":{"content":"Fixed."}}]}"#,
+        );
+        let provider = provider(ProviderDialect::OpenAi, base_url);
+        assert_eq!(
+            complete(&provider, &request("")),
+            Ok(Some("Fixed.".to_string()))
+        );
+
+        let sent = rx.recv().unwrap();
+        assert_eq!(sent.path, "/v1/chat/completions");
+        assert_eq!(sent.header("authorization"), Some("Bearer secret"));
+        assert_eq!(sent.body["model"], "model-1");
+        assert_eq!(sent.body["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(sent.body["messages"][0]["content"], "fix this");
+        assert!(sent.body.get("stream").is_none());
+        assert!(sent.body.get("max_tokens").is_none());
+    }
+
+    #[test]
+    fn test_anthropic_dialect() {
+        let (base_url, rx) = mock_server(
+            "200 OK",
+            "application/json",
+            r#"{"id":"msg_1","type":"message","role":"assistant","content":[{"type":"text","text":"Fixed."}],"stop_reason":"end_turn"}"#,
+        );
+        let provider = provider(ProviderDialect::Anthropic, base_url);
+        assert_eq!(
+            complete(&provider, &request("Be terse")),
+            Ok(Some("Fixed.".to_string()))
+        );
+
+        let sent = rx.recv().unwrap();
+        assert_eq!(sent.path, "/v1/messages");
+        assert_eq!(sent.header("x-api-key"), Some("secret"));
+        assert_eq!(sent.header("anthropic-version"), Some(ANTHROPIC_VERSION));
+        assert_eq!(sent.header("authorization"), None);
+        assert_eq!(sent.body["system"], "Be terse");
+        assert_eq!(sent.body["max_tokens"], ANTHROPIC_DEFAULT_MAX_TOKENS);
+        assert_eq!(sent.body["messages"][0]["role"], "user");
+    }
+
+    #[test]
+    fn test_gemini_dialect() {
+        let (base_url, rx) = mock_server(
+            "200 OK",
+            "application/json",
+            r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Fix"},{"text":"ed."}]}}]}"#,
+        );
+        let provider = provider(ProviderDialect::Gemini, base_url);
+        assert_eq!(
+            complete(&provider, &request("Be terse")),
+            Ok(Some("Fixed.".to_string()))
+        );
+
+        let sent = rx.recv().unwrap();
+        assert_eq!(sent.path, "/v1/models/model-1:generateContent");
+        assert_eq!(sent.header("x-goog-api-key"), Some("secret"));
+        assert_eq!(sent.body["contents"][0]["parts"][0]["text"], "fix this");
+        assert_eq!(
+            sent.body["systemInstruction"]["parts"][0]["text"],
+            "Be terse"
+        );
+        let temperature = sent.body["generationConfig"]["temperature"]
+            .as_f64()
+            .unwrap();
+        assert!((temperature - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_openai_streaming() {
+        let (base_url, rx) = mock_server(
+            "200 OK",
+            "text/event-stream",
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n\
+             data: {\"choices\":[{\"delta\":{\"content\":\"Fix\"}}]}\n\n\
+             data: {\"choices\":[{\"delta\":{\"content\":\"ed.\"}}]}\n\n\
+             data: [DONE]\n\n",
+        );
+        let (result, deltas) = stream(&provider(ProviderDialect::OpenAi, base_url));
+        assert_eq!(result, Ok(Some("Fixed.".to_string())));
+        assert_eq!(deltas, vec!["Fix", "ed."]);
+        assert_eq!(rx.recv().unwrap().body["stream"], true);
+    }
+
+    #[test]
+    fn test_anthropic_streaming() {
+        let (base_url, rx) = mock_server(
+            "200 OK",
+            "text/event-stream",
+            "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{}}\n\n\
+             event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Fix\"}}\n\n\
+             event: ping\ndata: {\"type\":\"ping\"}\n\n\
+             event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"ed.\"}}\n\n\
+             event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+        );
+        let (result, deltas) = stream(&provider(ProviderDialect::Anthropic, base_url));
+        assert_eq!(result, Ok(Some("Fixed.".to_string())));
+        assert_eq!(deltas, vec!["Fix", "ed."]);
+        assert_eq!(rx.recv().unwrap().body["stream"], true);
+    }
+
+    #[test]
+    fn test_anthropic_stream_error() {
+        let (base_url, _rx) = mock_server(
+            "200 OK",
+            "text/event-stream",
+            "event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n",
+        );
+        let (result, _) = stream(&provider(ProviderDialect::Anthropic, base_url));
+        assert!(result.unwrap_err().contains("Overloaded"));
+    }
+
+    #[test]
+    fn test_gemini_streaming() {
+        let (base_url, rx) = mock_server(
+            "200 OK",
+            "text/event-stream",
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Fix\"}]}}]}\r\n\r\n\
+             data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"ed.\"}]}}]}\r\n\r\n",
+        );
+        let (result, deltas) = stream(&provider(ProviderDialect::Gemini, base_url));
+        assert_eq!(result, Ok(Some("Fixed.".to_string())));
+        assert_eq!(deltas, vec!["Fix", "ed."]);
+        assert_eq!(
+            rx.recv().unwrap().path,
+            "/v1/models/model-1:streamGenerateContent?alt=sse"
+        );
+    }
+
+    #[test]
+    fn test_error_status() {
+        let (base_url, _rx) = mock_server(
+            "401 Unauthorized",
+            "application/json",
+            r#"{"error":{"message":"bad key"}}"#,
+        );
+        let provider = provider(ProviderDialect::Anthropic, base_url);
+        let err = complete(&provider, &request("")).unwrap_err();
+        assert!(err.contains("401"));
+        assert!(err.contains("bad key"));
+    }
+}
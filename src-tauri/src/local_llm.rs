@@ -0,0 +1,146 @@
+use crate::llm_client::ChatRequest;
+use anyhow::{anyhow, Result};
+use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::llama_backend::LlamaBackend;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::params::LlamaModelParams;
+use llama_cpp_2::model::{AddBos, LlamaChatMessage, LlamaModel, Special};
+use llama_cpp_2::sampling::LlamaSampler;
+use log::debug;
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// Context window for a cleanup request: prompt, transcript and answer
+const CONTEXT_TOKENS: u32 = 4096;
+/// Answer length when the prompt does not set `max_tokens`
+const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+/// llama.cpp may only be initialised once per process.
+fn backend() -> Result<&'static LlamaBackend> {
+    static BACKEND: OnceLock<LlamaBackend> = OnceLock::new();
+    if let Some(backend) = BACKEND.get() {
+        return Ok(backend);
+    }
+    let backend = LlamaBackend::init()?;
+    Ok(BACKEND.get_or_init(|| backend))
+}
+
+/// A GGUF chat model running on the CPU.
+pub struct LocalLlm {
+    model: LlamaModel,
+}
+
+impl LocalLlm {
+    pub fn load(path: &Path) -> Result<Self> {
+        let model = LlamaModel::load_from_file(backend()?, path, &LlamaModelParams::default())
+            .map_err(|e| anyhow!("Failed to load {}: {}", path.display(), e))?;
+        Ok(Self { model })
+    }
+
+    /// Runs `request` through the model's chat template and generates an
+    /// answer, passing each decoded piece to `on_delta`.
+    pub fn generate(
+        &self,
+        request: &ChatRequest,
+        on_delta: &mut dyn FnMut(&str),
+    ) -> Result<String> {
+        let started = Instant::now();
+        let prompt = self.chat_prompt(request)?;
+        let tokens = self.model.str_to_token(&prompt, AddBos::Never)?;
+        let max_tokens = request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS) as usize;
+        if tokens.len() + max_tokens > CONTEXT_TOKENS as usize {
+            return Err(anyhow!(
+                "Prompt is too long for the local model ({} tokens)",
+                tokens.len()
+            ));
+        }
+
+        let threads = std::thread::available_parallelism().map_or(4, |n| n.get()) as i32;
+        let params = LlamaContextParams::default()
+            .with_n_ctx(NonZeroU32::new(CONTEXT_TOKENS))
+            // The whole prompt is decoded as one batch
+            .with_n_batch(CONTEXT_TOKENS)
+            .with_n_threads(threads)
+            .with_n_threads_batch(threads);
+        let mut ctx = self.model.new_context(backend()?, params)?;
+
+        let mut batch = LlamaBatch::new(CONTEXT_TOKENS as usize, 1);
+        let last = tokens.len() - 1;
+        for (i, token) in tokens.iter().enumerate() {
+            batch.add(*token, i as i32, &[0], i == last)?;
+        }
+        ctx.decode(&mut batch)?;
+
+        // Cleanup should be deterministic unless the prompt asks otherwise
+        let mut sampler = match request.temperature {
+            Some(temperature) if temperature > 0.0 => LlamaSampler::chain_simple([
+                LlamaSampler::temp(temperature),
+                LlamaSampler::dist(rand::random()),
+            ]),
+            _ => LlamaSampler::greedy(),
+        };
+
+        let mut output = String::new();
+        let mut pending = Vec::new();
+        let mut position = batch.n_tokens();
+        for _ in 0..max_tokens {
+            if started.elapsed() > request.timeout {
+                return Err(anyhow!(
+                    "Local generation timed out after {:?}",
+                    request.timeout
+                ));
+            }
+
+            let token = sampler.sample(&ctx, batch.n_tokens() - 1);
+            sampler.accept(token);
+            if self.model.is_eog_token(token) {
+                break;
+            }
+
+            // A token can end in the middle of a UTF-8 sequence
+            pending.extend(self.model.token_to_bytes(token, Special::Tokenize)?);
+            let valid = match std::str::from_utf8(&pending) {
+                Ok(text) => text.len(),
+                Err(e) => e.valid_up_to(),
+            };
+            if valid > 0 {
+                let piece = String::from_utf8_lossy(&pending[..valid]).into_owned();
+                pending.drain(..valid);
+                on_delta(&piece);
+                output.push_str(&piece);
+            }
+
+            batch.clear();
+            batch.add(token, position, &[0], true)?;
+            position += 1;
+            ctx.decode(&mut batch)?;
+        }
+
+        debug!(
+            "Local LLM generated {} chars from {} prompt tokens in {:?}",
+            output.len(),
+            tokens.len(),
+            started.elapsed()
+        );
+        Ok(output)
+    }
+
+    fn chat_prompt(&self, request: &ChatRequest) -> Result<String> {
+        let mut messages = Vec::new();
+        if !request.system.trim().is_empty() {
+            messages.push(LlamaChatMessage::new(
+                "system".to_string(),
+                request.system.clone(),
+            )?);
+        }
+        messages.push(LlamaChatMessage::new(
+            "user".to_string(),
+            request.user.clone(),
+        )?);
+
+        let template = self.model.chat_template(None)?;
+        Ok(self.model.apply_chat_template(&template, &messages, true)?)
+    }
+}
@@ -1,102 +1,31 @@
-use crate::audio_toolkit::{list_input_devices, vad::SmoothedVad, AudioRecorder, SileroVad};
+use crate::audio_toolkit::{
+    find_loopback_device, list_input_devices, vad::SmoothedVad, AudioRecorder, CaptureDevice,
+    CaptureFrame, CpalDeviceInfo, DspConfig, EnergyVad, LevelMeter, LevelStats, ListenEvent,
+    SileroVad, VoiceActivityDetector,
+};
 use crate::helpers::clamshell;
-use crate::settings::{get_settings, AppSettings};
+use crate::helpers::ducking::{duck_output, DuckedOutput};
+use crate::settings::{get_settings, AppSettings, CaptureSource, VadBackend};
 use crate::utils;
-use log::{debug, error, info};
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
-use tauri::Manager;
-
-fn set_mute(mute: bool) {
-    // Expected behavior:
-    // - Windows: works on most systems using standard audio drivers.
-    // - Linux: works on many systems (PipeWire, PulseAudio, ALSA),
-    //   but some distros may lack the tools used.
-    // - macOS: works on most standard setups via AppleScript.
-    // If unsupported, fails silently.
-
-    #[cfg(target_os = "windows")]
-    {
-        unsafe {
-            use windows::Win32::{
-                Media::Audio::{
-                    eMultimedia, eRender, Endpoints::IAudioEndpointVolume, IMMDeviceEnumerator,
-                    MMDeviceEnumerator,
-                },
-                System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED},
-            };
-
-            macro_rules! unwrap_or_return {
-                ($expr:expr) => {
-                    match $expr {
-                        Ok(val) => val,
-                        Err(_) => return,
-                    }
-                };
-            }
-
-            // Initialize the COM library for this thread.
-            // If already initialized (e.g., by another library like Tauri), this does nothing.
-            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
-
-            let all_devices: IMMDeviceEnumerator =
-                unwrap_or_return!(CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL));
-            let default_device =
-                unwrap_or_return!(all_devices.GetDefaultAudioEndpoint(eRender, eMultimedia));
-            let volume_interface = unwrap_or_return!(
-                default_device.Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
-            );
-
-            let _ = volume_interface.SetMute(mute, std::ptr::null());
-        }
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        use std::process::Command;
-
-        let mute_val = if mute { "1" } else { "0" };
-        let amixer_state = if mute { "mute" } else { "unmute" };
-
-        // Try multiple backends to increase compatibility
-        // 1. PipeWire (wpctl)
-        if Command::new("wpctl")
-            .args(["set-mute", "@DEFAULT_AUDIO_SINK@", mute_val])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-        {
-            return;
-        }
-
-        // 2. PulseAudio (pactl)
-        if Command::new("pactl")
-            .args(["set-sink-mute", "@DEFAULT_SINK@", mute_val])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-        {
-            return;
-        }
-
-        // 3. ALSA (amixer)
-        let _ = Command::new("amixer")
-            .args(["set", "Master", amixer_state])
-            .output();
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        use std::process::Command;
-        let script = format!(
-            "set volume output muted {}",
-            if mute { "true" } else { "false" }
-        );
-        let _ = Command::new("osascript").args(["-e", &script]).output();
-    }
-}
+use log::{debug, error, info, warn};
+use serde::Serialize;
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
 
 const WHISPER_SAMPLE_RATE: usize = 16000;
+const SILERO_MODEL_PATH: &str = "resources/models/silero_vad_v4.onnx";
+/// Binding id that marks the capture of a microphone test
+const CALIBRATION_BINDING: &str = "calibration";
+/// How often the input device list is checked for microphones coming and
+/// going, while a stream is open
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Wait after a stream loses its device before picking another, so a burst
+/// of stream errors is handled once and the device list has settled
+const DEVICE_LOST_DEBOUNCE: Duration = Duration::from_millis(500);
 
 /* ──────────────────────────────────────────────────────────────── */
 
@@ -104,6 +33,28 @@ const WHISPER_SAMPLE_RATE: usize = 16000;
 pub enum RecordingState {
     Idle,
     Recording { binding_id: String },
+    Listening { binding_id: String },
+    Capturing { binding_id: String },
+}
+
+/// Sent to the frontend when recording moves to another microphone.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct MicrophoneChangedEvent {
+    pub previous: Option<String>,
+    pub current: String,
+    /// The selected microphone is missing and `current` stands in for it
+    pub fallback: bool,
+}
+
+/// What a microphone test measured.
+#[derive(Clone, Debug)]
+pub struct InputMeasurement {
+    pub device: Option<String>,
+    pub sample_rate: Option<u32>,
+    /// Levels as they come from the device, before gain and clean-up
+    pub input: LevelStats,
+    /// Levels after gain and clean-up, as the VAD sees them
+    pub processed: LevelStats,
 }
 
 #[derive(Clone, Debug)]
@@ -112,33 +63,155 @@ pub enum MicrophoneMode {
     OnDemand,
 }
 
+/// The microphone to use out of the `available` ones: `preferred` if it is
+/// connected, else the first connected entry of `priority`. `None` means
+/// the default input.
+fn choose_microphone(
+    preferred: Option<&str>,
+    priority: &[String],
+    available: &[String],
+) -> Option<String> {
+    let preferred = preferred?;
+    std::iter::once(preferred)
+        .chain(priority.iter().map(String::as_str))
+        .find(|name| available.iter().any(|a| a == name))
+        .map(str::to_string)
+}
+
+fn input_device_names() -> Vec<String> {
+    list_input_devices()
+        .map(|devices| devices.into_iter().map(|d| d.name).collect())
+        .unwrap_or_default()
+}
+
+/// Pads recordings shorter than a second with silence; the transcription
+/// engines do poorly on very short input.
+pub fn pad_short_recording(samples: Vec<f32>) -> Vec<f32> {
+    let s_len = samples.len();
+    if s_len < WHISPER_SAMPLE_RATE && s_len > 0 {
+        let mut padded = samples;
+        padded.resize(WHISPER_SAMPLE_RATE * 5 / 4, 0.0);
+        padded
+    } else {
+        samples
+    }
+}
+
 /* ──────────────────────────────────────────────────────────────── */
 
+/// Builds the VAD chosen in settings, wrapped in the smoothing stage.
+/// Returns `None` when voice detection is turned off.
+fn create_vad(
+    app_handle: &tauri::AppHandle,
+    settings: &AppSettings,
+) -> Result<Option<Box<dyn VoiceActivityDetector>>, anyhow::Error> {
+    let inner: Box<dyn VoiceActivityDetector> = match settings.vad_backend {
+        VadBackend::Off => return Ok(None),
+        VadBackend::Silero => {
+            let vad_path = app_handle
+                .path()
+                .resolve(SILERO_MODEL_PATH, tauri::path::BaseDirectory::Resource)
+                .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))?;
+            let silero = SileroVad::new(vad_path, settings.vad_threshold)
+                .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
+            Box::new(silero)
+        }
+        VadBackend::Energy => Box::new(EnergyVad::new(settings.vad_energy_threshold_db)),
+    };
+
+    Ok(Some(Box::new(SmoothedVad::new(
+        inner,
+        settings.vad_prefill_frames as usize,
+        settings.vad_hangover_frames as usize,
+        settings.vad_onset_frames as usize,
+    ))))
+}
+
 fn create_audio_recorder(
-    vad_path: &str,
+    vad: Option<Box<dyn VoiceActivityDetector>>,
     app_handle: &tauri::AppHandle,
 ) -> Result<AudioRecorder, anyhow::Error> {
-    let silero = SileroVad::new(vad_path, 0.3)
-        .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
-    let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
-
     // Recorder with VAD plus a spectrum-level callback that forwards updates to
     // the frontend.
-    let recorder = AudioRecorder::new()
+    let mut recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
-        .with_vad(Box::new(smoothed_vad))
         .with_level_callback({
             let app_handle = app_handle.clone();
             move |levels| {
                 utils::emit_levels(&app_handle, &levels);
             }
+        })
+        .with_device_lost_callback({
+            let app_handle = app_handle.clone();
+            move || {
+                if let Some(rm) = app_handle.try_state::<Arc<AudioRecordingManager>>() {
+                    rm.handle_device_lost();
+                }
+            }
         });
+    recorder.set_vad(vad);
 
     Ok(recorder)
 }
 
 /* ──────────────────────────────────────────────────────────────── */
 
+/// Background thread following microphones being plugged in or removed.
+/// Stops once the last manager holding it is gone.
+struct DeviceWatcher {
+    shutdown_signal: Arc<AtomicBool>,
+    handle: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl DeviceWatcher {
+    /// Enumerating devices opens every PCM on ALSA, so the list is only
+    /// polled while a stream is open; opening one picks a device anyway.
+    fn start(app_handle: tauri::AppHandle, is_open: Arc<Mutex<bool>>) -> Self {
+        let shutdown_signal = Arc::new(AtomicBool::new(false));
+        let shutdown = shutdown_signal.clone();
+        let handle = thread::spawn(move || {
+            let mut known: Option<Vec<String>> = None;
+            while !shutdown.load(Ordering::Relaxed) {
+                thread::sleep(DEVICE_POLL_INTERVAL);
+                if shutdown.load(Ordering::Relaxed) {
+                    break;
+                }
+                if !*is_open.lock().unwrap() {
+                    known = None;
+                    continue;
+                }
+
+                let names = input_device_names();
+                if known.as_ref().is_some_and(|known| *known != names) {
+                    debug!("Input devices changed: {:?}", names);
+                    let _ = app_handle.emit("audio-devices-changed", ());
+                    if let Some(rm) = app_handle.try_state::<Arc<AudioRecordingManager>>() {
+                        rm.check_microphone();
+                    }
+                }
+                known = Some(names);
+            }
+            debug!("Input device watcher shutting down");
+        });
+
+        Self {
+            shutdown_signal,
+            handle: Mutex::new(Some(handle)),
+        }
+    }
+}
+
+impl Drop for DeviceWatcher {
+    fn drop(&mut self) {
+        self.shutdown_signal.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            if let Err(e) = handle.join() {
+                warn!("Failed to join input device watcher: {:?}", e);
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AudioRecordingManager {
     state: Arc<Mutex<RecordingState>>,
@@ -148,7 +221,13 @@ pub struct AudioRecordingManager {
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
     is_open: Arc<Mutex<bool>>,
     is_recording: Arc<Mutex<bool>>,
-    did_mute: Arc<Mutex<bool>>,
+    ducked: Arc<Mutex<Option<DuckedOutput>>>,
+    /// Name of the microphone the open stream records from
+    active_microphone: Arc<Mutex<Option<String>>>,
+    /// Set while a microphone check after a lost device is scheduled
+    device_check_pending: Arc<AtomicBool>,
+    /// Only held so the watcher stops with the last clone
+    _device_watcher: Arc<DeviceWatcher>,
 }
 
 impl AudioRecordingManager {
@@ -162,15 +241,19 @@ impl AudioRecordingManager {
             MicrophoneMode::OnDemand
         };
 
+        let is_open = Arc::new(Mutex::new(false));
         let manager = Self {
             state: Arc::new(Mutex::new(RecordingState::Idle)),
             mode: Arc::new(Mutex::new(mode.clone())),
             app_handle: app.clone(),
 
             recorder: Arc::new(Mutex::new(None)),
-            is_open: Arc::new(Mutex::new(false)),
+            is_open: is_open.clone(),
             is_recording: Arc::new(Mutex::new(false)),
-            did_mute: Arc::new(Mutex::new(false)),
+            ducked: Arc::new(Mutex::new(None)),
+            active_microphone: Arc::new(Mutex::new(None)),
+            device_check_pending: Arc::new(AtomicBool::new(false)),
+            _device_watcher: Arc::new(DeviceWatcher::start(app.clone(), is_open)),
         };
 
         // Always-on?  Open immediately.
@@ -183,7 +266,8 @@ impl AudioRecordingManager {
 
     /* ---------- helper methods --------------------------------------------- */
 
-    fn get_effective_microphone_device(&self, settings: &AppSettings) -> Option<cpal::Device> {
+    /// The selected microphone, or the clamshell one while the lid is closed.
+    fn preferred_microphone(&self, settings: &AppSettings) -> Option<String> {
         // Check if we're in clamshell mode and have a clamshell microphone configured
         let use_clamshell_mic = if let Ok(is_clamshell) = clamshell::is_clamshell() {
             is_clamshell && settings.clamshell_microphone.is_some()
@@ -191,46 +275,175 @@ impl AudioRecordingManager {
             false
         };
 
-        let device_name = if use_clamshell_mic {
-            settings.clamshell_microphone.as_ref().unwrap()
+        if use_clamshell_mic {
+            settings.clamshell_microphone.clone()
         } else {
-            settings.selected_microphone.as_ref()?
-        };
+            settings.selected_microphone.clone()
+        }
+    }
 
-        // Find the device by name
-        match list_input_devices() {
-            Ok(devices) => devices
-                .into_iter()
-                .find(|d| d.name == *device_name)
-                .map(|d| d.device),
+    /// The microphone to record from: the preferred one if it is connected,
+    /// else the first connected device of the priority list, else the
+    /// default input.
+    fn resolve_microphone(&self, settings: &AppSettings) -> Option<CpalDeviceInfo> {
+        let devices = match list_input_devices() {
+            Ok(devices) => devices,
             Err(e) => {
                 debug!("Failed to list devices, using default: {}", e);
-                None
+                return None;
+            }
+        };
+        let names: Vec<String> = devices.iter().map(|d| d.name.clone()).collect();
+        let preferred = self.preferred_microphone(settings);
+        let chosen = choose_microphone(preferred.as_deref(), &settings.microphone_priority, &names);
+        if let (Some(preferred), None) = (&preferred, &chosen) {
+            warn!(
+                "Microphone '{}' is not connected, using the default",
+                preferred
+            );
+        }
+
+        devices.into_iter().find(|d| match &chosen {
+            Some(name) => d.name == *name,
+            None => d.is_default,
+        })
+    }
+
+    /// Moves the open stream to the microphone `resolve_microphone` picks now,
+    /// if that is another one, and tells the frontend. What is being recorded
+    /// carries on with the new device.
+    pub fn check_microphone(&self) {
+        self.reselect_microphone(false);
+    }
+
+    /// The open stream's device went away. Once the errors have stopped, move
+    /// to the microphone picked now, reopening it even if it has the same
+    /// name, as the old stream is dead either way.
+    fn handle_device_lost(&self) {
+        if self.device_check_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let manager = self.clone();
+        thread::spawn(move || {
+            thread::sleep(DEVICE_LOST_DEBOUNCE);
+            manager.device_check_pending.store(false, Ordering::SeqCst);
+            warn!("Capture device lost, picking a microphone again");
+            manager.reselect_microphone(true);
+        });
+    }
+
+    fn reselect_microphone(&self, reopen: bool) {
+        // Listing devices is slow, so the stream is free to open or close
+        // meanwhile; it is checked again before switching
+        if !*self.is_open.lock().unwrap() {
+            return;
+        }
+        let settings = get_settings(&self.app_handle);
+        if matches!(settings.capture_source, CaptureSource::SystemAudio) {
+            return;
+        }
+        let Some(microphone) = self.resolve_microphone(&settings) else {
+            warn!("No microphone available to record from");
+            return;
+        };
+        let previous = self.active_microphone.lock().unwrap().clone();
+        let unchanged = previous.as_deref() == Some(microphone.name.as_str());
+        if unchanged && !reopen {
+            return;
+        }
+
+        let open_flag = self.is_open.lock().unwrap();
+        if !*open_flag {
+            return;
+        }
+        if let Some(rec) = self.recorder.lock().unwrap().as_mut() {
+            if let Err(e) = rec.switch_source(CaptureDevice::input(microphone.device)) {
+                error!(
+                    "Failed to switch to microphone '{}': {}",
+                    microphone.name, e
+                );
+                return;
             }
         }
+        drop(open_flag);
+        if unchanged {
+            info!("Reopened microphone '{}'", microphone.name);
+            return;
+        }
+        info!(
+            "Switched microphone from {:?} to '{}'",
+            previous, microphone.name
+        );
+
+        let fallback = self
+            .preferred_microphone(&settings)
+            .is_some_and(|preferred| preferred != microphone.name);
+        *self.active_microphone.lock().unwrap() = Some(microphone.name.clone());
+        let _ = self.app_handle.emit(
+            "microphone-changed",
+            MicrophoneChangedEvent {
+                previous,
+                current: microphone.name,
+                fallback,
+            },
+        );
+    }
+
+    /// The device to open and the one to mix into it, per the capture source
+    /// setting. `None` as the first means the default microphone.
+    fn get_capture_devices(
+        &self,
+        settings: &AppSettings,
+        microphone: Option<CaptureDevice>,
+    ) -> Result<(Option<CaptureDevice>, Option<CaptureDevice>), anyhow::Error> {
+        let system_audio = || {
+            find_loopback_device(settings.system_audio_device.as_deref())
+                .map_err(anyhow::Error::msg)
+        };
+
+        match settings.capture_source {
+            CaptureSource::Microphone => Ok((microphone, None)),
+            CaptureSource::SystemAudio => Ok((Some(system_audio()?), None)),
+            CaptureSource::Both => match system_audio() {
+                Ok(system) => Ok((microphone, Some(system))),
+                Err(e) => {
+                    warn!("{}; recording the microphone only", e);
+                    Ok((microphone, None))
+                }
+            },
+        }
     }
 
     /* ---------- microphone life-cycle -------------------------------------- */
 
-    /// Applies mute if mute_while_recording is enabled and stream is open
+    /// Ducks the selected output if mute_while_recording is enabled and stream is open
     pub fn apply_mute(&self) {
         let settings = get_settings(&self.app_handle);
-        let mut did_mute_guard = self.did_mute.lock().unwrap();
+        let mut ducked_guard = self.ducked.lock().unwrap();
 
-        if settings.mute_while_recording && *self.is_open.lock().unwrap() {
-            set_mute(true);
-            *did_mute_guard = true;
-            debug!("Mute applied");
+        if settings.mute_while_recording && ducked_guard.is_none() && *self.is_open.lock().unwrap()
+        {
+            match duck_output(
+                settings.selected_output_device.as_deref(),
+                settings.duck_amount,
+                &settings.duck_apps,
+            ) {
+                Ok(ducked) => {
+                    *ducked_guard = Some(ducked);
+                    debug!("Output ducked");
+                }
+                Err(e) => warn!("Failed to duck output: {}", e),
+            }
         }
     }
 
-    /// Removes mute if it was applied
+    /// Restores the output volume if it was ducked
     pub fn remove_mute(&self) {
-        let mut did_mute_guard = self.did_mute.lock().unwrap();
-        if *did_mute_guard {
-            set_mute(false);
-            *did_mute_guard = false;
-            debug!("Mute removed");
+        if let Some(ducked) = self.ducked.lock().unwrap().take() {
+            match ducked.restore() {
+                Ok(()) => debug!("Output volume restored"),
+                Err(e) => warn!("Failed to restore output volume: {}", e),
+            }
         }
     }
 
@@ -243,37 +456,33 @@ impl AudioRecordingManager {
 
         let start_time = Instant::now();
 
-        // Don't mute immediately - caller will handle muting after audio feedback
-        let mut did_mute_guard = self.did_mute.lock().unwrap();
-        *did_mute_guard = false;
-
-        let vad_path = self
-            .app_handle
-            .path()
-            .resolve(
-                "resources/models/silero_vad_v4.onnx",
-                tauri::path::BaseDirectory::Resource,
-            )
-            .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))?;
+        let settings = get_settings(&self.app_handle);
         let mut recorder_opt = self.recorder.lock().unwrap();
 
         if recorder_opt.is_none() {
-            *recorder_opt = Some(create_audio_recorder(
-                vad_path.to_str().unwrap(),
-                &self.app_handle,
-            )?);
+            let vad = create_vad(&self.app_handle, &settings)?;
+            *recorder_opt = Some(create_audio_recorder(vad, &self.app_handle)?);
         }
 
-        // Get the selected device from settings, considering clamshell mode
-        let settings = get_settings(&self.app_handle);
-        let selected_device = self.get_effective_microphone_device(&settings);
+        // Get the selected devices from settings, considering clamshell mode
+        // and the fallback microphones
+        let microphone = self.resolve_microphone(&settings);
+        *self.active_microphone.lock().unwrap() = match settings.capture_source {
+            CaptureSource::SystemAudio => None,
+            _ => microphone.as_ref().map(|m| m.name.clone()),
+        };
+        let microphone = microphone.map(|m| CaptureDevice::input(m.device));
+        let (source, extra) = self.get_capture_devices(&settings, microphone)?;
 
         if let Some(rec) = recorder_opt.as_mut() {
-            rec.open(selected_device)
+            rec.open_sources(source, extra)
                 .map_err(|e| anyhow::anyhow!("Failed to open recorder: {}", e))?;
         }
 
         *open_flag = true;
+        drop(recorder_opt);
+        self.update_audio_processing();
+        self.update_pre_roll();
         info!(
             "Microphone stream initialized in {:?}",
             start_time.elapsed()
@@ -281,17 +490,47 @@ impl AudioRecordingManager {
         Ok(())
     }
 
+    /// Applies the source gains and the noise suppression, high-pass and gain
+    /// control settings to the running stream.
+    pub fn update_audio_processing(&self) {
+        let settings = get_settings(&self.app_handle);
+        let config = DspConfig {
+            noise_suppression: settings.noise_suppression,
+            high_pass: settings.high_pass_filter,
+            auto_gain: settings.auto_gain_control,
+        };
+        // System audio alone is opened as the main source
+        let (gain, extra_gain) = match settings.capture_source {
+            CaptureSource::SystemAudio => (settings.system_audio_gain, 1.0),
+            _ => (settings.microphone_gain, settings.system_audio_gain),
+        };
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.set_dsp(config);
+            rec.set_gains(gain, extra_gain);
+        }
+    }
+
+    /// Keeps a little audio from before each recording. Only an always-on
+    /// stream is running before the key press, so on-demand gets none.
+    pub fn update_pre_roll(&self) {
+        let settings = get_settings(&self.app_handle);
+        let pre_roll = if matches!(*self.mode.lock().unwrap(), MicrophoneMode::AlwaysOn) {
+            Duration::from_millis(settings.pre_roll_ms as u64)
+        } else {
+            Duration::ZERO
+        };
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.set_pre_roll(pre_roll);
+        }
+    }
+
     pub fn stop_microphone_stream(&self) {
         let mut open_flag = self.is_open.lock().unwrap();
         if !*open_flag {
             return;
         }
 
-        let mut did_mute_guard = self.did_mute.lock().unwrap();
-        if *did_mute_guard {
-            set_mute(false);
-        }
-        *did_mute_guard = false;
+        self.remove_mute();
 
         if let Some(rec) = self.recorder.lock().unwrap().as_mut() {
             // If still recording, stop first.
@@ -327,6 +566,7 @@ impl AudioRecordingManager {
         }
 
         *self.mode.lock().unwrap() = new_mode;
+        self.update_pre_roll();
         Ok(())
     }
 
@@ -361,6 +601,208 @@ impl AudioRecordingManager {
         }
     }
 
+    /// Starts a hands-free session. Every utterance the VAD finds is sent to
+    /// `events` until the session is stopped or cancelled.
+    pub fn try_start_listening(&self, binding_id: &str, events: mpsc::Sender<ListenEvent>) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if !matches!(*state, RecordingState::Idle) {
+            return false;
+        }
+
+        if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
+            if let Err(e) = self.start_microphone_stream() {
+                error!("Failed to open microphone stream: {e}");
+                return false;
+            }
+        }
+
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            match rec.listen(events) {
+                Ok(()) => {
+                    *self.is_recording.lock().unwrap() = true;
+                    *state = RecordingState::Listening {
+                        binding_id: binding_id.to_string(),
+                    };
+                    debug!("Hands-free listening started for binding {binding_id}");
+                    return true;
+                }
+                Err(e) => error!("Failed to start listening: {e}"),
+            }
+        } else {
+            error!("Recorder not available");
+        }
+        false
+    }
+
+    /// Ends the hands-free session of `binding_id`. An utterance in progress
+    /// is still delivered, after which the event channel closes.
+    pub fn stop_listening(&self, binding_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            RecordingState::Listening {
+                binding_id: ref active,
+            } if active == binding_id => {
+                *state = RecordingState::Idle;
+                drop(state);
+                self.end_listening(true);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn end_listening(&self, flush: bool) {
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            if let Err(e) = rec.stop_listening(flush) {
+                error!("stop_listening() failed: {e}");
+            }
+        }
+        *self.is_recording.lock().unwrap() = false;
+
+        if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
+            self.stop_microphone_stream();
+        }
+        debug!("Hands-free listening stopped");
+    }
+
+    pub fn is_listening(&self) -> bool {
+        matches!(
+            *self.state.lock().unwrap(),
+            RecordingState::Listening { .. }
+        )
+    }
+
+    /// Starts a long-form capture. Every frame, with the VAD's verdict, is
+    /// sent to `frames` until the capture is stopped or cancelled.
+    pub fn try_start_capture(&self, binding_id: &str, frames: mpsc::Sender<CaptureFrame>) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if !matches!(*state, RecordingState::Idle) {
+            return false;
+        }
+
+        if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
+            if let Err(e) = self.start_microphone_stream() {
+                error!("Failed to open microphone stream: {e}");
+                return false;
+            }
+        }
+
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            match rec.capture(frames) {
+                Ok(()) => {
+                    *self.is_recording.lock().unwrap() = true;
+                    *state = RecordingState::Capturing {
+                        binding_id: binding_id.to_string(),
+                    };
+                    debug!("Capture started for binding {binding_id}");
+                    return true;
+                }
+                Err(e) => error!("Failed to start capture: {e}"),
+            }
+        } else {
+            error!("Recorder not available");
+        }
+        false
+    }
+
+    /// Ends the capture of `binding_id`; the frame channel closes after the
+    /// last buffered audio.
+    pub fn stop_capture(&self, binding_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match *state {
+            RecordingState::Capturing {
+                binding_id: ref active,
+            } if active == binding_id => {
+                *state = RecordingState::Idle;
+                drop(state);
+                self.end_capture();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn end_capture(&self) {
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            if let Err(e) = rec.stop_capture() {
+                error!("stop_capture() failed: {e}");
+            }
+        }
+        *self.is_recording.lock().unwrap() = false;
+
+        if matches!(*self.mode.lock().unwrap(), MicrophoneMode::OnDemand) {
+            self.stop_microphone_stream();
+        }
+        debug!("Capture stopped");
+    }
+
+    /// Records `duration` from the current microphone through the same gain,
+    /// clean-up and VAD as a dictation and measures its levels, both at the
+    /// input and after processing. The live levels reach the frontend as they
+    /// do while recording.
+    pub fn measure_input(&self, duration: Duration) -> Result<InputMeasurement, anyhow::Error> {
+        let (frames_tx, frames_rx) = mpsc::channel::<CaptureFrame>();
+        if !self.try_start_capture(CALIBRATION_BINDING, frames_tx) {
+            return Err(anyhow::anyhow!(
+                "The microphone is busy or could not be opened"
+            ));
+        }
+
+        let deadline = Instant::now() + duration;
+        let mut input = LevelMeter::default();
+        let mut processed = LevelMeter::default();
+        while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+            match frames_rx.recv_timeout(left) {
+                Ok(frame) => {
+                    // The pre-roll only exists processed; leave it out of both
+                    if let Some(raw) = &frame.raw {
+                        input.push(raw, frame.speech);
+                        processed.push(&frame.samples, frame.speech);
+                    }
+                }
+                // Timed out, or the test was cancelled
+                Err(_) => break,
+            }
+        }
+
+        let sample_rate = self
+            .recorder
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|rec| rec.sample_rate());
+        let device = self.active_microphone.lock().unwrap().clone();
+        self.stop_capture(CALIBRATION_BINDING);
+
+        let input = input.stats();
+        if input.frames == 0 {
+            return Err(anyhow::anyhow!("No audio came from the microphone"));
+        }
+        Ok(InputMeasurement {
+            device,
+            sample_rate,
+            input,
+            processed: processed.stats(),
+        })
+    }
+
+    /// Rebuilds the VAD from the settings. An open stream is restarted so the
+    /// change applies right away, unless it is in use; then it applies the
+    /// next time the stream opens.
+    pub fn update_vad(&self) -> Result<(), anyhow::Error> {
+        let vad = create_vad(&self.app_handle, &get_settings(&self.app_handle))?;
+        if let Some(rec) = self.recorder.lock().unwrap().as_mut() {
+            rec.set_vad(vad);
+        }
+
+        let idle = matches!(*self.state.lock().unwrap(), RecordingState::Idle);
+        if idle && *self.is_open.lock().unwrap() {
+            self.stop_microphone_stream();
+            self.start_microphone_stream()?;
+        }
+        Ok(())
+    }
+
     pub fn update_selected_device(&self) -> Result<(), anyhow::Error> {
         // If currently open, restart the microphone stream to use the new device
         if *self.is_open.lock().unwrap() {
@@ -400,16 +842,7 @@ impl AudioRecordingManager {
                     self.stop_microphone_stream();
                 }
 
-                // Pad if very short
-                let s_len = samples.len();
-                // debug!("Got {} samples", s_len);
-                if s_len < WHISPER_SAMPLE_RATE && s_len > 0 {
-                    let mut padded = samples;
-                    padded.resize(WHISPER_SAMPLE_RATE * 5 / 4, 0.0);
-                    Some(padded)
-                } else {
-                    Some(samples)
-                }
+                Some(pad_short_recording(samples))
             }
             _ => None,
         }
@@ -425,6 +858,21 @@ impl AudioRecordingManager {
     pub fn cancel_recording(&self) {
         let mut state = self.state.lock().unwrap();
 
+        if let RecordingState::Listening { .. } = *state {
+            *state = RecordingState::Idle;
+            drop(state);
+            self.end_listening(false);
+            return;
+        }
+
+        // What was captured so far is kept; only the capture ends
+        if let RecordingState::Capturing { .. } = *state {
+            *state = RecordingState::Idle;
+            drop(state);
+            self.end_capture();
+            return;
+        }
+
         if let RecordingState::Recording { .. } = *state {
             *state = RecordingState::Idle;
             drop(state);
@@ -442,3 +890,28 @@ impl AudioRecordingManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn missing_microphone_falls_back_in_priority_order() {
+        let priority = names(&["Headset", "Webcam"]);
+        let available = names(&["Built-in", "Webcam"]);
+        assert_eq!(
+            choose_microphone(Some("USB Mic"), &priority, &available),
+            Some("Webcam".to_string())
+        );
+        assert_eq!(
+            choose_microphone(Some("Built-in"), &priority, &available),
+            Some("Built-in".to_string())
+        );
+        assert_eq!(choose_microphone(Some("USB Mic"), &[], &available), None);
+        assert_eq!(choose_microphone(None, &priority, &available), None);
+    }
+}
@@ -1,15 +1,19 @@
 use anyhow::Result;
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
 use log::{debug, error, info};
 use rusqlite::{params, Connection, OptionalExtension};
 use rusqlite_migration::{Migrations, M};
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::audio_toolkit::save_wav_file;
+use crate::audio_toolkit::{
+    constants::WHISPER_SAMPLE_RATE, load_audio_file, save_audio_file, AudioCodec,
+};
+use crate::settings::PostProcessStepKind;
 
 /// Database migrations for transcription history.
 /// Each migration is applied in order. The library tracks which migrations
@@ -31,6 +35,11 @@ static MIGRATIONS: &[M] = &[
     ),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_processed_text TEXT;"),
     M::up("ALTER TABLE transcription_history ADD COLUMN post_process_prompt TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN duration_ms INTEGER;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN model_id TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN transcription_ms INTEGER;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN post_process_provider TEXT;"),
+    M::up("ALTER TABLE transcription_history ADD COLUMN post_process_steps TEXT;"),
 ];
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -43,6 +52,87 @@ pub struct HistoryEntry {
     pub transcription_text: String,
     pub post_processed_text: Option<String>,
     pub post_process_prompt: Option<String>,
+    pub post_process_steps: Vec<PostProcessStepRecord>,
+}
+
+/// Output of one post-processing pipeline step, kept for debugging.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct PostProcessStepRecord {
+    pub step_id: String,
+    pub kind: PostProcessStepKind,
+    /// `None` when the step failed or returned nothing
+    pub output: Option<String>,
+    pub duration_ms: i64,
+}
+
+/// A transcription about to be written to the database.
+struct NewHistoryEntry {
+    file_name: String,
+    timestamp: i64,
+    title: String,
+    transcription_text: String,
+    post_processed_text: Option<String>,
+    post_process_prompt: Option<String>,
+    duration_ms: i64,
+}
+
+/// Subdirectory of `recordings/` holding decoded copies for playback.
+const PLAYBACK_DIR: &str = ".playback";
+
+/// Extra information recorded with each transcription for usage statistics
+/// and debugging.
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptionMetrics {
+    pub model_id: Option<String>,
+    pub transcription_ms: Option<i64>,
+    pub post_process_provider: Option<String>,
+    pub post_process_steps: Vec<PostProcessStepRecord>,
+}
+
+/// Words dictated within a single day or week, keyed by the first day of the period.
+#[derive(Clone, Debug, Serialize, Type, PartialEq)]
+pub struct WordsPerPeriod {
+    pub period_start: String,
+    pub entries: u32,
+    pub words: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Type, PartialEq)]
+pub struct ModelLatency {
+    pub model_id: String,
+    pub transcriptions: u32,
+    pub average_latency_ms: f64,
+    pub average_audio_seconds: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Type, PartialEq)]
+pub struct ProviderUsage {
+    pub provider_id: String,
+    pub count: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Type, PartialEq)]
+pub struct UsageStatistics {
+    pub total_entries: u32,
+    pub total_words: u32,
+    pub total_audio_minutes: f64,
+    pub words_per_day: Vec<WordsPerPeriod>,
+    pub words_per_week: Vec<WordsPerPeriod>,
+    pub latency_by_model: Vec<ModelLatency>,
+    pub post_process_by_provider: Vec<ProviderUsage>,
+    pub typing_wpm: u32,
+    pub estimated_typing_minutes: f64,
+    pub estimated_minutes_saved: f64,
+}
+
+/// A single history row reduced to the fields the statistics care about.
+struct UsageRow {
+    day: NaiveDate,
+    words: u32,
+    duration_ms: Option<i64>,
+    model_id: Option<String>,
+    transcription_ms: Option<i64>,
+    post_process_provider: Option<String>,
 }
 
 pub struct HistoryManager {
@@ -183,23 +273,30 @@ impl HistoryManager {
         transcription_text: String,
         post_processed_text: Option<String>,
         post_process_prompt: Option<String>,
+        metrics: TranscriptionMetrics,
     ) -> Result<()> {
         let timestamp = Utc::now().timestamp();
-        let file_name = format!("handy-{}.wav", timestamp);
+        let duration_ms = audio_samples.len() as i64 * 1000 / WHISPER_SAMPLE_RATE as i64;
+        let codec = crate::settings::get_settings(&self.app_handle).recording_format;
+        let file_name = format!("handy-{}.{}", timestamp, codec.extension());
         let title = self.format_timestamp_title(timestamp);
 
-        // Save WAV file
+        // Save audio file in the configured format
         let file_path = self.recordings_dir.join(&file_name);
-        save_wav_file(file_path, &audio_samples).await?;
+        run_blocking(move || save_audio_file(file_path, &audio_samples, codec)).await?;
 
         // Save to database
         self.save_to_database(
-            file_name,
-            timestamp,
-            title,
-            transcription_text,
-            post_processed_text,
-            post_process_prompt,
+            NewHistoryEntry {
+                file_name,
+                timestamp,
+                title,
+                transcription_text,
+                post_processed_text,
+                post_process_prompt,
+                duration_ms,
+            },
+            metrics,
         )?;
 
         // Clean up old entries
@@ -213,19 +310,79 @@ impl HistoryManager {
         Ok(())
     }
 
-    fn save_to_database(
+    /// Save a recording that is already on disk, such as a meeting too long
+    /// to hold in memory. The file is moved into the recordings directory.
+    pub async fn save_recording_file(
         &self,
-        file_name: String,
-        timestamp: i64,
-        title: String,
+        source: &Path,
+        duration_ms: i64,
         transcription_text: String,
-        post_processed_text: Option<String>,
-        post_process_prompt: Option<String>,
+        metrics: TranscriptionMetrics,
+    ) -> Result<()> {
+        let timestamp = Utc::now().timestamp();
+        let extension = source
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("wav");
+        let file_name = format!("handy-{}.{}", timestamp, extension);
+        let title = self.format_timestamp_title(timestamp);
+
+        let file_path = self.recordings_dir.join(&file_name);
+        if fs::rename(source, &file_path).is_err() {
+            // Different file systems; fall back to copying
+            fs::copy(source, &file_path)?;
+            fs::remove_file(source)?;
+        }
+
+        self.save_to_database(
+            NewHistoryEntry {
+                file_name,
+                timestamp,
+                title,
+                transcription_text,
+                post_processed_text: None,
+                post_process_prompt: None,
+                duration_ms,
+            },
+            metrics,
+        )?;
+
+        self.cleanup_old_entries()?;
+
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    fn save_to_database(
+        &self,
+        entry: NewHistoryEntry,
+        metrics: TranscriptionMetrics,
     ) -> Result<()> {
         let conn = self.get_connection()?;
+        let post_process_steps = if metrics.post_process_steps.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&metrics.post_process_steps)?)
+        };
         conn.execute(
-            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![file_name, timestamp, false, title, transcription_text, post_processed_text, post_process_prompt],
+            "INSERT INTO transcription_history (file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, duration_ms, model_id, transcription_ms, post_process_provider, post_process_steps) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                entry.file_name,
+                entry.timestamp,
+                false,
+                entry.title,
+                entry.transcription_text,
+                entry.post_processed_text,
+                entry.post_process_prompt,
+                entry.duration_ms,
+                metrics.model_id,
+                metrics.transcription_ms,
+                metrics.post_process_provider,
+                post_process_steps
+            ],
         )?;
 
         debug!("Saved transcription to database");
@@ -267,16 +424,17 @@ impl HistoryManager {
                 params![id],
             )?;
 
-            // Delete WAV file
+            // Delete audio file
             let file_path = self.recordings_dir.join(file_name);
             if file_path.exists() {
                 if let Err(e) = fs::remove_file(&file_path) {
-                    error!("Failed to delete WAV file {}: {}", file_name, e);
+                    error!("Failed to delete audio file {}: {}", file_name, e);
                 } else {
-                    debug!("Deleted old WAV file: {}", file_name);
+                    debug!("Deleted old audio file: {}", file_name);
                     deleted_count += 1;
                 }
             }
+            self.remove_playback_copy(file_name);
         }
 
         Ok(deleted_count)
@@ -355,7 +513,7 @@ impl HistoryManager {
     pub async fn get_history_entries(&self) -> Result<Vec<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt FROM transcription_history ORDER BY timestamp DESC"
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, post_process_steps FROM transcription_history ORDER BY timestamp DESC"
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -368,6 +526,7 @@ impl HistoryManager {
                 transcription_text: row.get("transcription_text")?,
                 post_processed_text: row.get("post_processed_text")?,
                 post_process_prompt: row.get("post_process_prompt")?,
+                post_process_steps: parse_step_records(row.get("post_process_steps")?),
             })
         })?;
 
@@ -406,14 +565,157 @@ impl HistoryManager {
         Ok(())
     }
 
+    /// Resolve the on-disk path of a recording. If the file was converted to
+    /// another format since the entry was read, the sibling with the same stem is used.
     pub fn get_audio_file_path(&self, file_name: &str) -> PathBuf {
-        self.recordings_dir.join(file_name)
+        let path = self.recordings_dir.join(file_name);
+        if path.exists() {
+            return path;
+        }
+
+        AudioCodec::ALL
+            .iter()
+            .map(|codec| path.with_extension(codec.extension()))
+            .find(|candidate| candidate.exists())
+            .unwrap_or(path)
+    }
+
+    /// Path the frontend can hand to an `<audio>` element. WebKit on macOS cannot
+    /// play Ogg Opus, so those recordings are decoded into a cached WAV copy.
+    pub async fn get_playback_file_path(&self, file_name: &str) -> Result<PathBuf> {
+        let path = self.get_audio_file_path(file_name);
+        if !cfg!(target_os = "macos") || AudioCodec::from_path(&path) != Some(AudioCodec::Opus) {
+            return Ok(path);
+        }
+
+        let playback_path = self.playback_copy_path(file_name);
+        if !playback_path.exists() {
+            fs::create_dir_all(self.recordings_dir.join(PLAYBACK_DIR))?;
+            let target = playback_path.clone();
+            run_blocking(move || {
+                let samples = load_audio_file(&path)?;
+                save_audio_file(&target, &samples, AudioCodec::Wav)
+            })
+            .await?;
+        }
+        Ok(playback_path)
+    }
+
+    /// Decode a history entry's recording back into 16 kHz mono samples.
+    pub async fn load_entry_samples(&self, id: i64) -> Result<Vec<f32>> {
+        let entry = self
+            .get_entry_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("History entry {} not found", id))?;
+        let path = self.get_audio_file_path(&entry.file_name);
+        run_blocking(move || load_audio_file(path)).await
+    }
+
+    pub async fn update_transcription_text(&self, id: i64, transcription_text: &str) -> Result<()> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE transcription_history SET transcription_text = ?1 WHERE id = ?2",
+            params![transcription_text, id],
+        )?;
+
+        debug!("Updated transcription text for entry {}", id);
+
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Re-encode every stored recording that is not already in `codec`.
+    /// Returns the number of converted files.
+    pub async fn convert_recordings(&self, codec: AudioCodec) -> Result<usize> {
+        let conn = self.get_connection()?;
+        let entries: Vec<(i64, String, Option<i64>)> = {
+            let mut stmt =
+                conn.prepare("SELECT id, file_name, duration_ms FROM transcription_history")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>("id")?,
+                    row.get::<_, String>("file_name")?,
+                    row.get::<_, Option<i64>>("duration_ms")?,
+                ))
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut converted = 0;
+        for (id, file_name, duration_ms) in entries {
+            let old_path = self.get_audio_file_path(&file_name);
+            if !old_path.exists() || AudioCodec::from_path(&old_path) == Some(codec) {
+                continue;
+            }
+
+            let new_path = old_path.with_extension(codec.extension());
+            let (source, target) = (old_path.clone(), new_path.clone());
+            let converted_len = run_blocking(move || {
+                let samples = load_audio_file(&source)
+                    .map_err(|e| anyhow::anyhow!("Failed to decode {:?}: {}", source, e))?;
+                save_audio_file(&target, &samples, codec)
+                    .map_err(|e| anyhow::anyhow!("Failed to write {:?}: {}", target, e))?;
+                Ok(samples.len())
+            })
+            .await;
+            let sample_count = match converted_len {
+                Ok(len) => len,
+                Err(e) => {
+                    error!("Failed to convert recording {}: {}", file_name, e);
+                    continue;
+                }
+            };
+
+            let new_file_name = new_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(&file_name)
+                .to_string();
+            let duration_ms =
+                duration_ms.unwrap_or(sample_count as i64 * 1000 / WHISPER_SAMPLE_RATE as i64);
+            conn.execute(
+                "UPDATE transcription_history SET file_name = ?1, duration_ms = ?2 WHERE id = ?3",
+                params![new_file_name, duration_ms, id],
+            )?;
+
+            if let Err(e) = fs::remove_file(&old_path) {
+                error!("Failed to remove converted file {:?}: {}", old_path, e);
+            }
+            self.remove_playback_copy(&file_name);
+            converted += 1;
+        }
+
+        if converted > 0 {
+            info!("Converted {} recordings to {}", converted, codec.extension());
+            if let Err(e) = self.app_handle.emit("history-updated", ()) {
+                error!("Failed to emit history-updated event: {}", e);
+            }
+        }
+
+        Ok(converted)
+    }
+
+    fn playback_copy_path(&self, file_name: &str) -> PathBuf {
+        self.recordings_dir
+            .join(PLAYBACK_DIR)
+            .join(file_name)
+            .with_extension(AudioCodec::Wav.extension())
+    }
+
+    fn remove_playback_copy(&self, file_name: &str) {
+        let playback_path = self.playback_copy_path(file_name);
+        if playback_path.exists() {
+            let _ = fs::remove_file(playback_path);
+        }
     }
 
     pub async fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, post_process_prompt, post_process_steps
              FROM transcription_history WHERE id = ?1",
         )?;
 
@@ -428,6 +730,7 @@ impl HistoryManager {
                     transcription_text: row.get("transcription_text")?,
                     post_processed_text: row.get("post_processed_text")?,
                     post_process_prompt: row.get("post_process_prompt")?,
+                    post_process_steps: parse_step_records(row.get("post_process_steps")?),
                 })
             })
             .optional()?;
@@ -448,6 +751,7 @@ impl HistoryManager {
                     // Continue with database deletion even if file deletion fails
                 }
             }
+            self.remove_playback_copy(&entry.file_name);
         }
 
         // Delete from database
@@ -466,6 +770,74 @@ impl HistoryManager {
         Ok(())
     }
 
+    /// Aggregate usage statistics over every entry still in the history database.
+    /// Time saved is estimated against typing the final text at `typing_wpm`.
+    pub async fn get_usage_statistics(&self, typing_wpm: u32) -> Result<UsageStatistics> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, file_name, timestamp, transcription_text, post_processed_text, duration_ms, model_id, transcription_ms, post_process_provider FROM transcription_history",
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            let timestamp: i64 = row.get("timestamp")?;
+            let transcription_text: String = row.get("transcription_text")?;
+            let post_processed_text: Option<String> = row.get("post_processed_text")?;
+            let file_name: String = row.get("file_name")?;
+            let duration_ms: Option<i64> = row.get("duration_ms")?;
+
+            let day = DateTime::from_timestamp(timestamp, 0)
+                .map(|dt| dt.with_timezone(&Local).date_naive())
+                .unwrap_or_default();
+            let final_text = post_processed_text.as_deref().unwrap_or(&transcription_text);
+
+            Ok((
+                row.get::<_, i64>("id")?,
+                file_name,
+                UsageRow {
+                    day,
+                    words: final_text.split_whitespace().count() as u32,
+                    duration_ms,
+                    model_id: row.get("model_id")?,
+                    transcription_ms: row.get("transcription_ms")?,
+                    post_process_provider: row.get("post_process_provider")?,
+                },
+            ))
+        })?;
+
+        let mut usage_rows = Vec::new();
+        let mut backfilled = Vec::new();
+        for row in rows {
+            let (id, file_name, mut usage_row) = row?;
+            // Entries recorded before durations were stored: read it from the
+            // file header once and keep it
+            if usage_row.duration_ms.is_none() {
+                usage_row.duration_ms = self.read_audio_duration_ms(&file_name);
+                if let Some(duration_ms) = usage_row.duration_ms {
+                    backfilled.push((id, duration_ms));
+                }
+            }
+            usage_rows.push(usage_row);
+        }
+
+        for (id, duration_ms) in backfilled {
+            conn.execute(
+                "UPDATE transcription_history SET duration_ms = ?1 WHERE id = ?2",
+                params![duration_ms, id],
+            )?;
+        }
+
+        Ok(aggregate_usage_statistics(&usage_rows, typing_wpm))
+    }
+
+    fn read_audio_duration_ms(&self, file_name: &str) -> Option<i64> {
+        let reader = hound::WavReader::open(self.get_audio_file_path(file_name)).ok()?;
+        let sample_rate = reader.spec().sample_rate as i64;
+        if sample_rate == 0 {
+            return None;
+        }
+        Some(reader.duration() as i64 * 1000 / sample_rate)
+    }
+
     fn format_timestamp_title(&self, timestamp: i64) -> String {
         if let Some(utc_datetime) = DateTime::from_timestamp(timestamp, 0) {
             // Convert UTC to local timezone
@@ -476,3 +848,183 @@ impl HistoryManager {
         }
     }
 }
+
+/* ────────────────────────────────────────────────────────────── */
+
+/// Runs audio encoding or decoding on the blocking pool; a long recording
+/// would otherwise stall the async runtime for seconds.
+async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f).await?
+}
+
+/// Step records are stored as JSON; entries from before the pipeline have none.
+fn parse_step_records(json: Option<String>) -> Vec<PostProcessStepRecord> {
+    json.and_then(|json| match serde_json::from_str(&json) {
+        Ok(records) => Some(records),
+        Err(e) => {
+            error!("Failed to parse post-processing steps: {}", e);
+            None
+        }
+    })
+    .unwrap_or_default()
+}
+
+fn aggregate_usage_statistics(rows: &[UsageRow], typing_wpm: u32) -> UsageStatistics {
+    let mut per_day: BTreeMap<NaiveDate, (u32, u32)> = BTreeMap::new();
+    let mut per_week: BTreeMap<NaiveDate, (u32, u32)> = BTreeMap::new();
+    // model -> (count, total latency ms, total audio ms)
+    let mut per_model: BTreeMap<&str, (u32, i64, i64)> = BTreeMap::new();
+    let mut per_provider: BTreeMap<&str, u32> = BTreeMap::new();
+
+    let mut total_words = 0u32;
+    let mut total_audio_ms = 0i64;
+
+    for row in rows {
+        total_words += row.words;
+        total_audio_ms += row.duration_ms.unwrap_or(0);
+
+        let day = per_day.entry(row.day).or_default();
+        day.0 += 1;
+        day.1 += row.words;
+
+        let week_start =
+            row.day - chrono::Duration::days(row.day.weekday().num_days_from_monday() as i64);
+        let week = per_week.entry(week_start).or_default();
+        week.0 += 1;
+        week.1 += row.words;
+
+        if let (Some(model_id), Some(latency)) = (&row.model_id, row.transcription_ms) {
+            let model = per_model.entry(model_id.as_str()).or_default();
+            model.0 += 1;
+            model.1 += latency;
+            model.2 += row.duration_ms.unwrap_or(0);
+        }
+
+        if let Some(provider) = &row.post_process_provider {
+            *per_provider.entry(provider.as_str()).or_default() += 1;
+        }
+    }
+
+    let to_periods = |map: BTreeMap<NaiveDate, (u32, u32)>| {
+        map.into_iter()
+            .map(|(start, (entries, words))| WordsPerPeriod {
+                period_start: start.format("%Y-%m-%d").to_string(),
+                entries,
+                words,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let latency_by_model = per_model
+        .into_iter()
+        .map(|(model_id, (count, latency_ms, audio_ms))| ModelLatency {
+            model_id: model_id.to_string(),
+            transcriptions: count,
+            average_latency_ms: latency_ms as f64 / count as f64,
+            average_audio_seconds: audio_ms as f64 / count as f64 / 1000.0,
+        })
+        .collect();
+
+    let mut post_process_by_provider: Vec<ProviderUsage> = per_provider
+        .into_iter()
+        .map(|(provider_id, count)| ProviderUsage {
+            provider_id: provider_id.to_string(),
+            count,
+        })
+        .collect();
+    post_process_by_provider.sort_by_key(|usage| std::cmp::Reverse(usage.count));
+
+    let total_audio_minutes = total_audio_ms as f64 / 60_000.0;
+    let estimated_typing_minutes = if typing_wpm > 0 {
+        total_words as f64 / typing_wpm as f64
+    } else {
+        0.0
+    };
+
+    UsageStatistics {
+        total_entries: rows.len() as u32,
+        total_words,
+        total_audio_minutes,
+        words_per_day: to_periods(per_day),
+        words_per_week: to_periods(per_week),
+        latency_by_model,
+        post_process_by_provider,
+        typing_wpm,
+        estimated_typing_minutes,
+        estimated_minutes_saved: (estimated_typing_minutes - total_audio_minutes).max(0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(day: &str, words: u32, duration_ms: i64) -> UsageRow {
+        UsageRow {
+            day: NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap(),
+            words,
+            duration_ms: Some(duration_ms),
+            model_id: None,
+            transcription_ms: None,
+            post_process_provider: None,
+        }
+    }
+
+    #[test]
+    fn test_words_grouped_by_day_and_week() {
+        // 2024-01-07 is a Sunday, 2024-01-08 a Monday
+        let rows = vec![
+            row("2024-01-07", 10, 5_000),
+            row("2024-01-08", 20, 5_000),
+            row("2024-01-08", 5, 5_000),
+        ];
+        let stats = aggregate_usage_statistics(&rows, 40);
+
+        assert_eq!(stats.total_entries, 3);
+        assert_eq!(stats.total_words, 35);
+        assert_eq!(stats.words_per_day.len(), 2);
+        assert_eq!(stats.words_per_day[1].words, 25);
+        assert_eq!(stats.words_per_day[1].entries, 2);
+        assert_eq!(stats.words_per_week[0].period_start, "2024-01-01");
+        assert_eq!(stats.words_per_week[1].period_start, "2024-01-08");
+    }
+
+    #[test]
+    fn test_latency_and_provider_usage() {
+        let mut a = row("2024-01-08", 10, 4_000);
+        a.model_id = Some("small".to_string());
+        a.transcription_ms = Some(300);
+        a.post_process_provider = Some("openai".to_string());
+        let mut b = row("2024-01-08", 10, 2_000);
+        b.model_id = Some("small".to_string());
+        b.transcription_ms = Some(100);
+        let stats = aggregate_usage_statistics(&[a, b], 40);
+
+        assert_eq!(stats.latency_by_model.len(), 1);
+        assert_eq!(stats.latency_by_model[0].average_latency_ms, 200.0);
+        assert_eq!(stats.latency_by_model[0].average_audio_seconds, 3.0);
+        assert_eq!(
+            stats.post_process_by_provider,
+            vec![ProviderUsage {
+                provider_id: "openai".to_string(),
+                count: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_time_saved() {
+        let rows = vec![row("2024-01-08", 80, 60_000)];
+        let stats = aggregate_usage_statistics(&rows, 40);
+        assert_eq!(stats.total_audio_minutes, 1.0);
+        assert_eq!(stats.estimated_typing_minutes, 2.0);
+        assert_eq!(stats.estimated_minutes_saved, 1.0);
+
+        let stats = aggregate_usage_statistics(&rows, 0);
+        assert_eq!(stats.estimated_minutes_saved, 0.0);
+    }
+}
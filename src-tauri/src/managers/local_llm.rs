@@ -0,0 +1,149 @@
+use crate::llm_client::ChatRequest;
+use crate::local_llm::LocalLlm;
+use crate::managers::model::{EngineType, ModelManager};
+use crate::settings::{get_settings, ModelUnloadTimeout};
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::AppHandle;
+
+struct LoadedLlm {
+    model_id: String,
+    llm: LocalLlm,
+}
+
+/// Keeps the GGUF model used by the local post-processing provider in memory
+/// and unloads it after `model_unload_timeout`, like the speech model.
+pub struct LocalLlmManager {
+    model_manager: Arc<ModelManager>,
+    app_handle: AppHandle,
+    loaded: Arc<Mutex<Option<LoadedLlm>>>,
+    last_activity: Arc<AtomicU64>,
+    shutdown_signal: Arc<AtomicBool>,
+    watcher_handle: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+impl LocalLlmManager {
+    pub fn new(app_handle: &AppHandle, model_manager: Arc<ModelManager>) -> Self {
+        let manager = Self {
+            model_manager,
+            app_handle: app_handle.clone(),
+            loaded: Arc::new(Mutex::new(None)),
+            last_activity: Arc::new(AtomicU64::new(now_ms())),
+            shutdown_signal: Arc::new(AtomicBool::new(false)),
+            watcher_handle: Mutex::new(None),
+        };
+
+        let app_handle = app_handle.clone();
+        let loaded = manager.loaded.clone();
+        let last_activity = manager.last_activity.clone();
+        let shutdown_signal = manager.shutdown_signal.clone();
+        let handle = thread::spawn(move || {
+            while !shutdown_signal.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_secs(10));
+
+                let settings = get_settings(&app_handle);
+                let Some(limit_seconds) = settings.model_unload_timeout.to_seconds() else {
+                    continue;
+                };
+                // Held for the whole generation, so a running request is never unloaded
+                let mut loaded = loaded.lock().unwrap();
+                let idle_ms = now_ms().saturating_sub(last_activity.load(Ordering::Relaxed));
+                if idle_ms > limit_seconds * 1000 {
+                    if let Some(unloaded) = loaded.take() {
+                        debug!("Local LLM {} unloaded due to inactivity", unloaded.model_id);
+                    }
+                }
+            }
+            debug!("Local LLM idle watcher shutting down");
+        });
+        *manager.watcher_handle.lock().unwrap() = Some(handle);
+
+        manager
+    }
+
+    pub fn loaded_model(&self) -> Option<String> {
+        self.loaded
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|loaded| loaded.model_id.clone())
+    }
+
+    pub fn unload(&self) {
+        if let Some(unloaded) = self.loaded.lock().unwrap().take() {
+            info!("Local LLM {} unloaded", unloaded.model_id);
+        }
+    }
+
+    /// Generates an answer with `model_id`, loading it first if needed.
+    /// Blocks until generation finishes.
+    pub fn generate(
+        &self,
+        model_id: &str,
+        request: &ChatRequest,
+        on_delta: &mut dyn FnMut(&str),
+    ) -> Result<String> {
+        let info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow!("Local model '{}' not found", model_id))?;
+        if !matches!(info.engine_type, EngineType::Llm) {
+            return Err(anyhow!("{} is not a language model", info.name));
+        }
+        if !info.is_downloaded {
+            return Err(anyhow!("{} has not been downloaded yet", info.name));
+        }
+
+        let mut loaded = self.loaded.lock().unwrap();
+        if loaded.as_ref().map(|l| l.model_id.as_str()) != Some(model_id) {
+            *loaded = None;
+            let load_start = std::time::Instant::now();
+            let path = self.model_manager.get_model_path(model_id)?;
+            let llm = LocalLlm::load(&path)?;
+            info!(
+                "Loaded local LLM {} in {}ms",
+                model_id,
+                load_start.elapsed().as_millis()
+            );
+            *loaded = Some(LoadedLlm {
+                model_id: model_id.to_string(),
+                llm,
+            });
+        }
+
+        self.last_activity.store(now_ms(), Ordering::Relaxed);
+        let result = loaded
+            .as_ref()
+            .expect("Local LLM loaded above")
+            .llm
+            .generate(request, on_delta);
+        self.last_activity.store(now_ms(), Ordering::Relaxed);
+
+        if get_settings(&self.app_handle).model_unload_timeout == ModelUnloadTimeout::Immediately {
+            *loaded = None;
+        }
+        result
+    }
+}
+
+impl Drop for LocalLlmManager {
+    fn drop(&mut self) {
+        self.shutdown_signal.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.watcher_handle.lock().unwrap().take() {
+            if let Err(e) = handle.join() {
+                warn!("Failed to join local LLM idle watcher: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,513 @@
+use anyhow::Result;
+use chrono::Local;
+use hound::{WavIntoSamples, WavReader, WavSpec, WavWriter};
+use log::{debug, info, warn};
+use serde::Serialize;
+use specta::Type;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::audio_toolkit::{
+    constants::WHISPER_SAMPLE_RATE, save_audio_stream, AudioCodec, CaptureFrame,
+    OnnxSpeakerEmbedder, SpeakerClusterer, SpeakerEmbedder,
+};
+use crate::managers::audio::pad_short_recording;
+use crate::managers::history::{HistoryManager, TranscriptionMetrics};
+use crate::managers::model::ModelManager;
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::get_settings;
+
+/// Model used to tell speakers apart when speaker detection is on
+pub const SPEAKER_MODEL_ID: &str = "wespeaker-resnet34";
+
+const SAMPLE_RATE: usize = WHISPER_SAMPLE_RATE as usize;
+/// Length of each audio file written while a meeting runs (5 min)
+const SEGMENT_SECS: usize = 300;
+/// Longest chunk handed to the transcription model
+const MAX_CHUNK_SECS: usize = 30;
+/// Chunks end at the first pause once they are this long
+const MIN_CHUNK_SECS: usize = 5;
+/// Non-speech frames (30 ms each) that count as a pause
+const PAUSE_FRAMES: usize = 10;
+/// A pause this long (1.2 s) ends a chunk however short it is, as it
+/// often marks a change of speaker
+const TURN_PAUSE_FRAMES: usize = 40;
+/// Frames kept from before the first speech of a chunk
+const LEAD_IN_FRAMES: usize = 10;
+/// Shorter chunks are too short for a reliable voice print and keep the
+/// previous speaker
+const MIN_EMBED_SECS: usize = 2;
+/// Cosine similarity above which two chunks count as the same voice
+const SPEAKER_SIMILARITY: f32 = 0.5;
+
+/// One transcribed chunk of a meeting.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Zero-based speaker number; `None` without speaker detection
+    pub speaker: Option<u32>,
+    pub text: String,
+}
+
+/// A stretch of audio to transcribe, starting `start` samples into the meeting.
+struct Chunk {
+    start: usize,
+    samples: Vec<f32>,
+}
+
+/// Cuts the continuous meeting audio into chunks at pauses in speech, so
+/// each can be transcribed while the meeting goes on.
+#[derive(Default)]
+struct Chunker {
+    samples: Vec<f32>,
+    start: usize,
+    position: usize,
+    has_speech: bool,
+    pause_frames: usize,
+}
+
+impl Chunker {
+    fn push(&mut self, frame: &[f32], speech: bool) -> Option<Chunk> {
+        self.samples.extend_from_slice(frame);
+        self.position += frame.len();
+
+        if speech {
+            self.has_speech = true;
+            self.pause_frames = 0;
+        } else if self.has_speech {
+            self.pause_frames += 1;
+        } else {
+            // Nothing said yet; keep only a short lead-in
+            let keep = LEAD_IN_FRAMES * frame.len();
+            let excess = self.samples.len().saturating_sub(keep);
+            self.samples.drain(..excess);
+            self.start = self.position - self.samples.len();
+            return None;
+        }
+
+        let length = self.samples.len();
+        let cut = length >= MAX_CHUNK_SECS * SAMPLE_RATE
+            || (self.pause_frames >= PAUSE_FRAMES && length >= MIN_CHUNK_SECS * SAMPLE_RATE)
+            || self.pause_frames >= TURN_PAUSE_FRAMES;
+        if cut {
+            self.take()
+        } else {
+            None
+        }
+    }
+
+    /// The chunk in progress, if anything was said in it.
+    fn finish(&mut self) -> Option<Chunk> {
+        if self.has_speech {
+            self.take()
+        } else {
+            None
+        }
+    }
+
+    fn take(&mut self) -> Option<Chunk> {
+        let chunk = Chunk {
+            start: self.start,
+            samples: std::mem::take(&mut self.samples),
+        };
+        self.start = self.position;
+        self.has_speech = false;
+        self.pause_frames = 0;
+        Some(chunk)
+    }
+}
+
+/// Writes the meeting audio to numbered WAV files of `SEGMENT_SECS` each,
+/// so nothing long is held in memory.
+struct SegmentWriter {
+    dir: PathBuf,
+    writer: Option<WavWriter<BufWriter<File>>>,
+    segments: Vec<PathBuf>,
+    samples_in_segment: usize,
+}
+
+impl SegmentWriter {
+    fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            writer: None,
+            segments: Vec::new(),
+            samples_in_segment: 0,
+        }
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<()> {
+        if self.writer.is_none() || self.samples_in_segment >= SEGMENT_SECS * SAMPLE_RATE {
+            self.next_segment()?;
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            for sample in samples {
+                writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+            }
+        }
+        self.samples_in_segment += samples.len();
+        Ok(())
+    }
+
+    fn next_segment(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.finalize()?;
+        }
+        let path = self
+            .dir
+            .join(format!("segment-{:04}.wav", self.segments.len()));
+        self.writer = Some(WavWriter::create(&path, wav_spec())?);
+        self.segments.push(path);
+        self.samples_in_segment = 0;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<PathBuf>> {
+        if let Some(writer) = self.writer.take() {
+            writer.finalize()?;
+        }
+        Ok(self.segments)
+    }
+}
+
+fn wav_spec() -> WavSpec {
+    WavSpec {
+        channels: 1,
+        sample_rate: WHISPER_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    }
+}
+
+/// The samples of the segment files in order, one file open at a time.
+/// Stops at the first read error, which is kept in `error`.
+struct SegmentSamples<'a> {
+    paths: std::slice::Iter<'a, PathBuf>,
+    reader: Option<WavIntoSamples<BufReader<File>, i16>>,
+    error: Option<hound::Error>,
+}
+
+impl Iterator for SegmentSamples<'_> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        loop {
+            if let Some(reader) = self.reader.as_mut() {
+                match reader.next() {
+                    Some(Ok(sample)) => return Some(sample as f32 / i16::MAX as f32),
+                    Some(Err(e)) => {
+                        self.error = Some(e);
+                        return None;
+                    }
+                    None => self.reader = None,
+                }
+            }
+            match WavReader::open(self.paths.next()?) {
+                Ok(reader) => self.reader = Some(reader.into_samples()),
+                Err(e) => {
+                    self.error = Some(e);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Encodes the segment files into one recording in `codec`, reading them as
+/// it goes rather than loading them whole.
+fn merge_segments(segments: &[PathBuf], output: &Path, codec: AudioCodec) -> Result<()> {
+    let mut samples = SegmentSamples {
+        paths: segments.iter(),
+        reader: None,
+        error: None,
+    };
+    save_audio_stream(output, &mut samples, codec)?;
+    match samples.error {
+        Some(e) => Err(e.into()),
+        None => Ok(()),
+    }
+}
+
+/// One line per segment: `[hh:mm:ss] Speaker 2: text`.
+fn format_transcript(segments: &[TranscriptSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| {
+            let secs = segment.start_ms / 1000;
+            let time = format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+            match segment.speaker {
+                Some(speaker) => format!("[{}] Speaker {}: {}", time, speaker + 1, segment.text),
+                None => format!("[{}] {}", time, segment.text),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn samples_to_ms(samples: usize) -> u64 {
+    (samples * 1000 / SAMPLE_RATE) as u64
+}
+
+/// What a running meeting leaves behind. Dropping it, on any way out of
+/// `MeetingManager::record`, closes the chunk channel, waits for the
+/// transcription worker and removes the meeting's working directory.
+struct MeetingSession {
+    dir: PathBuf,
+    chunk_tx: Option<mpsc::Sender<Chunk>>,
+    worker: Option<JoinHandle<(Vec<TranscriptSegment>, Duration)>>,
+}
+
+impl MeetingSession {
+    fn send(&self, chunk: Chunk) {
+        if let Some(chunk_tx) = &self.chunk_tx {
+            let _ = chunk_tx.send(chunk);
+        }
+    }
+
+    /// Closes the chunk channel and waits for the worker's segments and the
+    /// time it spent transcribing.
+    fn finish_transcription(&mut self) -> Result<(Vec<TranscriptSegment>, Duration)> {
+        self.chunk_tx = None;
+        self.worker
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Meeting transcription already finished"))?
+            .join()
+            .map_err(|_| anyhow::anyhow!("Meeting transcription worker panicked"))
+    }
+}
+
+impl Drop for MeetingSession {
+    fn drop(&mut self) {
+        self.chunk_tx = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        if let Err(e) = fs::remove_dir_all(&self.dir) {
+            warn!("Failed to remove meeting directory {:?}: {}", self.dir, e);
+        }
+    }
+}
+
+pub struct MeetingManager {
+    app_handle: AppHandle,
+    meetings_dir: PathBuf,
+}
+
+impl MeetingManager {
+    pub fn new(app_handle: &AppHandle) -> Result<Self> {
+        let meetings_dir = app_handle.path().app_data_dir()?.join("meetings");
+        Ok(Self {
+            app_handle: app_handle.clone(),
+            meetings_dir,
+        })
+    }
+
+    /// Records a meeting from the captured `frames` until the channel closes,
+    /// transcribing it chunk by chunk in the background, then saves the audio
+    /// and the timestamped transcript as one history entry.
+    pub fn record(&self, frames: mpsc::Receiver<CaptureFrame>) -> Result<()> {
+        let dir = self
+            .meetings_dir
+            .join(Local::now().format("%Y%m%d-%H%M%S").to_string());
+        fs::create_dir_all(&dir)?;
+        info!("Meeting recording started in {:?}", dir);
+
+        let settings = get_settings(&self.app_handle);
+        let embedder = if settings.meeting_speaker_detection {
+            self.load_speaker_embedder()
+        } else {
+            None
+        };
+        let (chunk_tx, chunk_rx) = mpsc::channel();
+        let app = self.app_handle.clone();
+        let max_speakers = settings.meeting_max_speakers as usize;
+        let worker =
+            std::thread::spawn(move || transcribe_chunks(app, chunk_rx, embedder, max_speakers));
+        let mut session = MeetingSession {
+            dir: dir.clone(),
+            chunk_tx: Some(chunk_tx),
+            worker: Some(worker),
+        };
+
+        let mut writer = SegmentWriter::new(dir.clone());
+        let mut chunker = Chunker::default();
+        for frame in frames {
+            writer.write(&frame.samples)?;
+            if let Some(chunk) = chunker.push(&frame.samples, frame.speech) {
+                session.send(chunk);
+            }
+        }
+        if let Some(chunk) = chunker.finish() {
+            session.send(chunk);
+        }
+        let segment_files = writer.finish()?;
+        debug!("Meeting capture ended, waiting for the last chunks");
+
+        let (segments, transcription_time) = session.finish_transcription()?;
+
+        let codec = settings.recording_format;
+        let merged = dir.join(format!("meeting.{}", codec.extension()));
+        merge_segments(&segment_files, &merged, codec)?;
+
+        let tm = self.app_handle.state::<Arc<TranscriptionManager>>();
+        let metrics = TranscriptionMetrics {
+            model_id: tm.get_current_model(),
+            transcription_ms: Some(transcription_time.as_millis() as i64),
+            ..Default::default()
+        };
+        let hm = self.app_handle.state::<Arc<HistoryManager>>();
+        tauri::async_runtime::block_on(hm.save_recording_file(
+            &merged,
+            samples_to_ms(chunker.position) as i64,
+            format_transcript(&segments),
+            metrics,
+        ))?;
+
+        info!(
+            "Meeting saved: {} segments, {}s of audio",
+            segments.len(),
+            chunker.position / SAMPLE_RATE
+        );
+        Ok(())
+    }
+
+    fn load_speaker_embedder(&self) -> Option<Box<dyn SpeakerEmbedder>> {
+        let mm = self.app_handle.state::<Arc<ModelManager>>();
+        let path = match mm.get_model_path(SPEAKER_MODEL_ID) {
+            Ok(path) => path,
+            Err(e) => {
+                warn!("Speaker detection is on but the speaker model is unavailable: {e}");
+                return None;
+            }
+        };
+        match OnnxSpeakerEmbedder::new(&path) {
+            Ok(embedder) => Some(Box::new(embedder)),
+            Err(e) => {
+                warn!("Failed to load the speaker model: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Transcribes chunks in order as they arrive and labels their speakers.
+/// Returns the segments and the time spent transcribing.
+fn transcribe_chunks(
+    app: AppHandle,
+    chunks: mpsc::Receiver<Chunk>,
+    mut embedder: Option<Box<dyn SpeakerEmbedder>>,
+    max_speakers: usize,
+) -> (Vec<TranscriptSegment>, Duration) {
+    let tm = Arc::clone(&app.state::<Arc<TranscriptionManager>>());
+    let mut clusterer = SpeakerClusterer::new(SPEAKER_SIMILARITY, max_speakers);
+    let mut speaker = None;
+    let mut segments = Vec::new();
+    let mut busy = Duration::ZERO;
+
+    for chunk in chunks {
+        let end = chunk.start + chunk.samples.len();
+        if let Some(embedder) = embedder.as_mut() {
+            if chunk.samples.len() >= MIN_EMBED_SECS * SAMPLE_RATE {
+                match embedder.embed(&chunk.samples) {
+                    Ok(embedding) => speaker = Some(clusterer.assign(&embedding) as u32),
+                    Err(e) => warn!("Speaker embedding failed: {e}"),
+                }
+            }
+        }
+
+        // The model may have been unloaded since the last chunk
+        tm.initiate_model_load();
+        let started = Instant::now();
+        let text = match tm.transcribe(pad_short_recording(chunk.samples)) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!("Meeting chunk transcription failed: {e}");
+                continue;
+            }
+        };
+        busy += started.elapsed();
+
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let segment = TranscriptSegment {
+            start_ms: samples_to_ms(chunk.start),
+            end_ms: samples_to_ms(end),
+            speaker,
+            text: text.to_string(),
+        };
+        let _ = app.emit("meeting-transcript-updated", &segment);
+        segments.push(segment);
+    }
+    (segments, busy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: usize = 480;
+
+    fn feed(chunker: &mut Chunker, frames: usize, speech: bool) -> Vec<Chunk> {
+        (0..frames)
+            .filter_map(|_| chunker.push(&[0.0; FRAME], speech))
+            .collect()
+    }
+
+    #[test]
+    fn chunks_end_at_pauses_and_keep_their_position() {
+        let mut chunker = Chunker::default();
+        assert!(feed(&mut chunker, 100, false).is_empty());
+        // 6 s of speech, then a pause
+        assert!(feed(&mut chunker, 200, true).is_empty());
+        let chunks = feed(&mut chunker, PAUSE_FRAMES, false);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].start, (100 - LEAD_IN_FRAMES) * FRAME);
+        assert_eq!(
+            chunks[0].samples.len(),
+            (LEAD_IN_FRAMES + 200 + PAUSE_FRAMES) * FRAME
+        );
+        assert!(chunker.finish().is_none());
+    }
+
+    #[test]
+    fn long_speech_is_split() {
+        let mut chunker = Chunker::default();
+        let chunks = feed(&mut chunker, 2100, true);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks
+            .iter()
+            .all(|c| c.samples.len() == MAX_CHUNK_SECS * SAMPLE_RATE));
+        assert_eq!(chunks[1].start, MAX_CHUNK_SECS * SAMPLE_RATE);
+        assert!(chunker.finish().is_some());
+    }
+
+    #[test]
+    fn transcript_lines_carry_time_and_speaker() {
+        let segments = [
+            TranscriptSegment {
+                start_ms: 3_723_000,
+                end_ms: 3_730_000,
+                speaker: Some(1),
+                text: "Next item.".to_string(),
+            },
+            TranscriptSegment {
+                start_ms: 5_000,
+                end_ms: 9_000,
+                speaker: None,
+                text: "Hello.".to_string(),
+            },
+        ];
+        assert_eq!(
+            format_transcript(&segments),
+            "[01:02:03] Speaker 2: Next item.\n[00:00:05] Hello."
+        );
+    }
+}
@@ -1,5 +1,8 @@
 pub mod audio;
 pub mod gemini_conversation;
 pub mod history;
+#[cfg(feature = "local-llm")]
+pub mod local_llm;
+pub mod meeting;
 pub mod model;
 pub mod transcription;
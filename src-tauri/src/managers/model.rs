@@ -19,6 +19,16 @@ pub enum EngineType {
     Whisper,
     Parakeet,
     Moonshine,
+    /// GGUF language model used for local post-processing, not transcription
+    Llm,
+    /// ONNX voice print model used to tell speakers apart in meetings
+    SpeakerEmbedding,
+}
+
+impl EngineType {
+    pub fn is_speech(&self) -> bool {
+        !matches!(self, EngineType::Llm | EngineType::SpeakerEmbedding)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -203,6 +213,67 @@ impl ModelManager {
             },
         );
 
+        // Language models for offline post-processing
+        #[cfg(feature = "local-llm")]
+        available_models.insert(
+            "qwen2.5-1.5b-instruct".to_string(),
+            ModelInfo {
+                id: "qwen2.5-1.5b-instruct".to_string(),
+                name: "Qwen 2.5 1.5B".to_string(),
+                description: "Offline transcript cleanup. Multilingual.".to_string(),
+                filename: "qwen2.5-1.5b-instruct-q4_k_m.gguf".to_string(),
+                url: Some("https://huggingface.co/Qwen/Qwen2.5-1.5B-Instruct-GGUF/resolve/main/qwen2.5-1.5b-instruct-q4_k_m.gguf".to_string()),
+                size_mb: 1120,
+                is_downloaded: false,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: false,
+                engine_type: EngineType::Llm,
+                accuracy_score: 0.70,
+                speed_score: 0.60,
+            },
+        );
+
+        #[cfg(feature = "local-llm")]
+        available_models.insert(
+            "llama-3.2-1b-instruct".to_string(),
+            ModelInfo {
+                id: "llama-3.2-1b-instruct".to_string(),
+                name: "Llama 3.2 1B".to_string(),
+                description: "Smaller and faster offline cleanup, best in English.".to_string(),
+                filename: "Llama-3.2-1B-Instruct-Q4_K_M.gguf".to_string(),
+                url: Some("https://huggingface.co/bartowski/Llama-3.2-1B-Instruct-GGUF/resolve/main/Llama-3.2-1B-Instruct-Q4_K_M.gguf".to_string()),
+                size_mb: 808,
+                is_downloaded: false,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: false,
+                engine_type: EngineType::Llm,
+                accuracy_score: 0.60,
+                speed_score: 0.75,
+            },
+        );
+
+        // Speaker embeddings for meeting transcripts
+        available_models.insert(
+            "wespeaker-resnet34".to_string(),
+            ModelInfo {
+                id: "wespeaker-resnet34".to_string(),
+                name: "WeSpeaker ResNet34".to_string(),
+                description: "Labels who is speaking in meeting recordings.".to_string(),
+                filename: "wespeaker_en_voxceleb_resnet34.onnx".to_string(),
+                url: Some("https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-recongition-models/wespeaker_en_voxceleb_resnet34.onnx".to_string()),
+                size_mb: 27,
+                is_downloaded: false,
+                is_downloading: false,
+                partial_size: 0,
+                is_directory: false,
+                engine_type: EngineType::SpeakerEmbedding,
+                accuracy_score: 0.70,
+                speed_score: 0.80,
+            },
+        );
+
         let manager = Self {
             app_handle: app_handle.clone(),
             models_dir,
@@ -313,7 +384,10 @@ impl ModelManager {
         if settings.selected_model.is_empty() {
             // Find the first available (downloaded) model
             let models = self.available_models.lock().unwrap();
-            if let Some(available_model) = models.values().find(|model| model.is_downloaded) {
+            if let Some(available_model) = models
+                .values()
+                .find(|model| model.is_downloaded && model.engine_type.is_speech())
+            {
                 info!(
                     "Auto-selecting model: {} ({})",
                     available_model.id, available_model.name
@@ -1,9 +1,13 @@
-use crate::audio_toolkit::apply_custom_words;
+use crate::audio_toolkit::{
+    apply_vocabulary_index, normalize_text, scorer_for_language, VocabularyEntry, VocabularyIndex,
+};
 use crate::managers::model::{EngineType, ModelManager};
-use crate::settings::{get_settings, ModelUnloadTimeout};
+use crate::settings::{get_dictation_settings, get_settings, AppSettings, ModelUnloadTimeout};
 use anyhow::Result;
 use log::{debug, error, info, warn};
 use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
@@ -20,6 +24,10 @@ use transcribe_rs::{
     TranscriptionEngine,
 };
 
+// Whisper's prompt window is 224 tokens; stay well inside it so the prompt
+// never crowds out the audio context.
+const WHISPER_PROMPT_MAX_CHARS: usize = 600;
+
 #[derive(Clone, Debug, Serialize)]
 pub struct ModelStateEvent {
     pub event_type: String,
@@ -34,6 +42,9 @@ enum LoadedEngine {
     Moonshine(MoonshineEngine),
 }
 
+/// Vocabulary index together with the fingerprint of the settings it was built from.
+type CachedVocabularyIndex = (u64, Arc<VocabularyIndex>);
+
 #[derive(Clone)]
 pub struct TranscriptionManager {
     engine: Arc<Mutex<Option<LoadedEngine>>>,
@@ -45,6 +56,8 @@ pub struct TranscriptionManager {
     watcher_handle: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
     is_loading: Arc<Mutex<bool>>,
     loading_condvar: Arc<Condvar>,
+    last_transcription: Arc<Mutex<Option<String>>>,
+    vocabulary_index: Arc<Mutex<Option<CachedVocabularyIndex>>>,
 }
 
 impl TranscriptionManager {
@@ -64,6 +77,8 @@ impl TranscriptionManager {
             watcher_handle: Arc::new(Mutex::new(None)),
             is_loading: Arc::new(Mutex::new(false)),
             loading_condvar: Arc::new(Condvar::new()),
+            last_transcription: Arc::new(Mutex::new(None)),
+            vocabulary_index: Arc::new(Mutex::new(None)),
         };
 
         // Start the idle watcher
@@ -284,6 +299,19 @@ impl TranscriptionManager {
                     })?;
                 LoadedEngine::Moonshine(engine)
             }
+            EngineType::Llm | EngineType::SpeakerEmbedding => {
+                let error_msg = format!("{} is not a speech recognition model", model_info.name);
+                let _ = self.app_handle.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "loading_failed".to_string(),
+                        model_id: Some(model_id.to_string()),
+                        model_name: Some(model_info.name.clone()),
+                        error: Some(error_msg.clone()),
+                    },
+                );
+                return Err(anyhow::anyhow!(error_msg));
+            }
         };
 
         // Update the current engine and model ID
@@ -316,19 +344,29 @@ impl TranscriptionManager {
         Ok(())
     }
 
-    /// Kicks off the model loading in a background thread if it's not already loaded
+    /// Kicks off loading the dictation model (the active profile's, if it
+    /// sets one) in a background thread unless it is already loaded.
+    /// Transcriptions wait until the load has finished.
     pub fn initiate_model_load(&self) {
         let mut is_loading = self.is_loading.lock().unwrap();
-        if *is_loading || self.is_model_loaded() {
+        if *is_loading || self.is_dictation_model_loaded() {
             return;
         }
 
         *is_loading = true;
         let self_clone = self.clone();
         thread::spawn(move || {
-            let settings = get_settings(&self_clone.app_handle);
-            if let Err(e) = self_clone.load_model(&settings.selected_model) {
-                error!("Failed to load model: {}", e);
+            // A profile switch can change the model while one loads; keep
+            // going until the loaded model is the one dictation wants
+            loop {
+                let model_id = get_dictation_settings(&self_clone.app_handle).selected_model;
+                if self_clone.is_dictation_model_loaded() {
+                    break;
+                }
+                if let Err(e) = self_clone.load_model(&model_id) {
+                    error!("Failed to load model: {}", e);
+                    break;
+                }
             }
             let mut is_loading = self_clone.is_loading.lock().unwrap();
             *is_loading = false;
@@ -336,11 +374,58 @@ impl TranscriptionManager {
         });
     }
 
+    fn is_dictation_model_loaded(&self) -> bool {
+        let wanted = get_dictation_settings(&self.app_handle).selected_model;
+        self.is_model_loaded() && self.get_current_model().as_deref() == Some(wanted.as_str())
+    }
+
     pub fn get_current_model(&self) -> Option<String> {
         let current_model = self.current_model_id.lock().unwrap();
         current_model.clone()
     }
 
+    /// Index over the configured vocabulary. Building it encodes every entry
+    /// phonetically, so it is cached until the vocabulary or language changes.
+    fn vocabulary_index(&self, settings: &AppSettings) -> Arc<VocabularyIndex> {
+        let mut hasher = DefaultHasher::new();
+        settings.selected_language.hash(&mut hasher);
+        settings.custom_words.hash(&mut hasher);
+        settings.vocabulary.hash(&mut hasher);
+        let fingerprint = hasher.finish();
+
+        let mut cached = self.vocabulary_index.lock().unwrap();
+        if let Some((cached_fingerprint, index)) = cached.as_ref() {
+            if *cached_fingerprint == fingerprint {
+                return Arc::clone(index);
+            }
+        }
+
+        let entries: Vec<VocabularyEntry> = settings
+            .custom_words
+            .iter()
+            .map(|word| VocabularyEntry::fuzzy_word(word))
+            .chain(settings.vocabulary.iter().cloned())
+            .collect();
+        let index = Arc::new(VocabularyIndex::new(
+            &entries,
+            scorer_for_language(&settings.selected_language),
+        ));
+        debug!(
+            "Built vocabulary index with {} entries using {}",
+            index.len(),
+            index.scorer_name()
+        );
+        *cached = Some((fingerprint, Arc::clone(&index)));
+        index
+    }
+
+    /// Keep a dictation as the previous text Whisper's next prompt can
+    /// include. Only live dictations belong here; a retranscribed history
+    /// entry or a meeting chunk has nothing to do with what comes next.
+    pub fn remember_dictation(&self, text: &str) {
+        *self.last_transcription.lock().unwrap() = Some(text.to_string());
+    }
+
     pub fn transcribe(&self, audio: Vec<f32>) -> Result<String> {
         // Update last activity timestamp
         self.last_activity.store(
@@ -376,7 +461,7 @@ impl TranscriptionManager {
         }
 
         // Get current settings for configuration
-        let settings = get_settings(&self.app_handle);
+        let settings = get_dictation_settings(&self.app_handle);
 
         // Perform transcription with the appropriate engine
         let result = {
@@ -404,9 +489,28 @@ impl TranscriptionManager {
                         Some(normalized)
                     };
 
+                    let initial_prompt = if settings.whisper_initial_prompt_enabled {
+                        let previous = if settings.whisper_prompt_include_previous {
+                            self.last_transcription.lock().unwrap().clone()
+                        } else {
+                            None
+                        };
+                        build_initial_prompt(
+                            &prompt_vocabulary(&settings),
+                            previous.as_deref(),
+                            WHISPER_PROMPT_MAX_CHARS,
+                        )
+                    } else {
+                        None
+                    };
+                    if let Some(prompt) = &initial_prompt {
+                        debug!("Using Whisper initial prompt: {}", prompt);
+                    }
+
                     let params = WhisperInferenceParams {
                         language: whisper_language,
                         translate: settings.translate_to_english,
+                        initial_prompt,
                         ..Default::default()
                     };
 
@@ -429,17 +533,33 @@ impl TranscriptionManager {
             }
         };
 
-        // Apply word correction if custom words are configured
-        let corrected_result = if !settings.custom_words.is_empty() {
-            apply_custom_words(
+        // Apply word correction if a custom vocabulary is configured
+        let corrected_result = if !settings.custom_words.is_empty()
+            || !settings.vocabulary.is_empty()
+            || !settings.vocabulary_regex_rules.is_empty()
+        {
+            apply_vocabulary_index(
                 &result.text,
-                &settings.custom_words,
+                &self.vocabulary_index(&settings),
+                &settings.vocabulary_regex_rules,
                 settings.word_correction_threshold,
             )
         } else {
             result.text
         };
 
+        // Spoken numbers, dates and punctuation to written form
+        let corrected_result = if settings.text_normalization_enabled {
+            let language = if settings.translate_to_english {
+                "en"
+            } else {
+                settings.selected_language.as_str()
+            };
+            normalize_text(&corrected_result, language, &settings.text_normalization)
+        } else {
+            corrected_result
+        };
+
         let et = std::time::Instant::now();
         let translation_note = if settings.translate_to_english {
             " (translated)"
@@ -466,6 +586,86 @@ impl TranscriptionManager {
     }
 }
 
+/// Terms from the custom vocabulary worth biasing Whisper towards, in order
+/// and without duplicates.
+fn prompt_vocabulary(settings: &AppSettings) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    let candidates = settings
+        .custom_words
+        .iter()
+        .chain(settings.vocabulary.iter().map(|entry| &entry.written));
+    for term in candidates {
+        let term = term.trim();
+        if !term.is_empty() && !terms.iter().any(|t| t.eq_ignore_ascii_case(term)) {
+            terms.push(term.to_string());
+        }
+    }
+    terms
+}
+
+/// Build a Whisper initial prompt from vocabulary terms and, optionally, the
+/// previous dictation.
+///
+/// Whisper treats the prompt as preceding text, so spelling terms out makes it
+/// more likely to produce them. Terms take priority; whatever room is left
+/// under `max_chars` is filled with the end of the previous dictation, which
+/// sits last because Whisper weights the most recent context most.
+fn build_initial_prompt(
+    terms: &[String],
+    previous: Option<&str>,
+    max_chars: usize,
+) -> Option<String> {
+    let mut vocabulary = String::new();
+    for term in terms {
+        let separator = if vocabulary.is_empty() { "" } else { ", " };
+        // Leave room for the closing period
+        if vocabulary.chars().count() + separator.len() + term.chars().count() + 1 > max_chars {
+            break;
+        }
+        vocabulary.push_str(separator);
+        vocabulary.push_str(term);
+    }
+    if !vocabulary.is_empty() {
+        vocabulary.push('.');
+    }
+
+    let mut prompt = vocabulary;
+    if let Some(previous) = previous.map(str::trim).filter(|p| !p.is_empty()) {
+        let separator = if prompt.is_empty() { 0 } else { 1 };
+        let budget = max_chars.saturating_sub(prompt.chars().count() + separator);
+        let tail = tail_at_word_boundary(previous, budget);
+        if !tail.is_empty() {
+            if !prompt.is_empty() {
+                prompt.push(' ');
+            }
+            prompt.push_str(tail);
+        }
+    }
+
+    (!prompt.is_empty()).then_some(prompt)
+}
+
+/// The longest suffix of `text` that fits in `max_chars` and starts on a word.
+fn tail_at_word_boundary(text: &str, max_chars: usize) -> &str {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text;
+    }
+
+    let start = text
+        .char_indices()
+        .nth(total - max_chars)
+        .map_or(text.len(), |(idx, _)| idx);
+    let tail = &text[start..];
+    // Drop a partially cut word unless the cut already landed on a boundary
+    if text[..start].ends_with(char::is_whitespace) {
+        tail.trim_start()
+    } else {
+        tail.split_once(char::is_whitespace)
+            .map_or("", |(_, rest)| rest.trim_start())
+    }
+}
+
 impl Drop for TranscriptionManager {
     fn drop(&mut self) {
         debug!("Shutting down TranscriptionManager");
@@ -483,3 +683,51 @@ impl Drop for TranscriptionManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_build_initial_prompt_vocabulary() {
+        assert_eq!(
+            build_initial_prompt(&terms(&["Tauri", "Handy"]), None, 100),
+            Some("Tauri, Handy.".to_string())
+        );
+        assert_eq!(build_initial_prompt(&[], None, 100), None);
+        assert_eq!(build_initial_prompt(&[], Some("  "), 100), None);
+    }
+
+    #[test]
+    fn test_build_initial_prompt_respects_cap() {
+        let prompt = build_initial_prompt(
+            &terms(&["Kubernetes", "PostgreSQL", "Terraform"]),
+            Some("we deployed the new cluster yesterday"),
+            30,
+        )
+        .unwrap();
+        assert_eq!(prompt, "Kubernetes, PostgreSQL.");
+        assert!(prompt.chars().count() <= 30);
+
+        let prompt = build_initial_prompt(
+            &terms(&["Tauri"]),
+            Some("we deployed the new cluster yesterday"),
+            30,
+        )
+        .unwrap();
+        assert_eq!(prompt, "Tauri. new cluster yesterday");
+        assert!(prompt.chars().count() <= 30);
+    }
+
+    #[test]
+    fn test_tail_at_word_boundary() {
+        assert_eq!(tail_at_word_boundary("short text", 20), "short text");
+        assert_eq!(tail_at_word_boundary("one two three", 8), "three");
+        assert_eq!(tail_at_word_boundary("one two three", 9), "two three");
+        assert_eq!(tail_at_word_boundary("one two three", 3), "");
+    }
+}
@@ -1,6 +1,9 @@
 use crate::input;
+use crate::post_process::PostProcessNotice;
 use crate::settings;
 use crate::settings::OverlayPosition;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize};
 
 #[cfg(not(target_os = "macos"))]
@@ -25,6 +28,10 @@ tauri_panel! {
     })
 }
 
+/// Bumped whenever a recording or transcription shows the overlay, so a
+/// delayed hide can tell that the overlay now belongs to a later dictation.
+static OVERLAY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 const OVERLAY_WIDTH: f64 = 172.0;
 const OVERLAY_HEIGHT: f64 = 36.0;
 
@@ -206,6 +213,8 @@ pub fn create_recording_overlay(app_handle: &AppHandle) {
 
 /// Shows the recording overlay window with fade-in animation
 pub fn show_recording_overlay(app_handle: &AppHandle) {
+    OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst);
+
     // Check if overlay should be shown based on position setting
     let settings = settings::get_settings(app_handle);
     if settings.overlay_position == OverlayPosition::None {
@@ -232,6 +241,8 @@ pub fn show_recording_overlay(app_handle: &AppHandle) {
 
 /// Shows the transcribing overlay window
 pub fn show_transcribing_overlay(app_handle: &AppHandle) {
+    OVERLAY_GENERATION.fetch_add(1, Ordering::SeqCst);
+
     // Check if overlay should be shown based on position setting
     let settings = settings::get_settings(app_handle);
     if settings.overlay_position == OverlayPosition::None {
@@ -303,6 +314,24 @@ pub fn show_no_audio_overlay(app_handle: &AppHandle) {
     }
 }
 
+/// Updates overlay to tell that post-processing was skipped or fell back
+/// to another provider
+pub fn show_post_process_notice_overlay(app_handle: &AppHandle, notice: PostProcessNotice) {
+    let settings = settings::get_settings(app_handle);
+    if settings.overlay_position == OverlayPosition::None {
+        return;
+    }
+
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.show();
+        let state = match notice {
+            PostProcessNotice::Degraded => "post-process-degraded",
+            PostProcessNotice::Skipped => "post-process-skipped",
+        };
+        let _ = overlay_window.emit("show-overlay", state);
+    }
+}
+
 /// Updates the overlay window position based on current settings
 pub fn update_overlay_position(app_handle: &AppHandle) {
     if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
@@ -329,6 +358,19 @@ pub fn hide_recording_overlay(app_handle: &AppHandle) {
     }
 }
 
+/// Hides the recording overlay after `delay`, unless a new recording or
+/// transcription has shown it again by then.
+pub fn hide_recording_overlay_after(app_handle: &AppHandle, delay: Duration) {
+    let generation = OVERLAY_GENERATION.load(Ordering::SeqCst);
+    let app_handle = app_handle.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        if OVERLAY_GENERATION.load(Ordering::SeqCst) == generation {
+            hide_recording_overlay(&app_handle);
+        }
+    });
+}
+
 pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
     // emit levels to main app
     let _ = app_handle.emit("mic-level", levels);
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct ProviderHealth {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Tracks consecutive failures per provider and model. After `threshold`
+/// failures in a row that pair is skipped until the cooldown has passed; the
+/// next request after that is a trial, and a single failure opens the breaker
+/// again. Other models of the same provider keep being tried.
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    providers: HashMap<(String, String), ProviderHealth>,
+}
+
+fn key(provider_id: &str, model: &str) -> (String, String) {
+    (provider_id.to_string(), model.to_string())
+}
+
+impl CircuitBreaker {
+    /// Whether a request to `model` on `provider_id` should be attempted at
+    /// `now`.
+    pub fn allows(&self, provider_id: &str, model: &str, now: Instant) -> bool {
+        self.providers
+            .get(&key(provider_id, model))
+            .and_then(|health| health.open_until)
+            .is_none_or(|until| now >= until)
+    }
+
+    pub fn record_success(&mut self, provider_id: &str, model: &str) {
+        self.providers.remove(&key(provider_id, model));
+    }
+
+    /// Records a failed request. Returns true when this failure opened the
+    /// breaker. A `threshold` of 0 never opens it.
+    pub fn record_failure(
+        &mut self,
+        provider_id: &str,
+        model: &str,
+        threshold: u32,
+        cooldown: Duration,
+        now: Instant,
+    ) -> bool {
+        let health = self.providers.entry(key(provider_id, model)).or_default();
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+        if threshold == 0 || health.consecutive_failures < threshold {
+            return false;
+        }
+        health.open_until = Some(now + cooldown);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn opens_after_threshold_consecutive_failures() {
+        let mut breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        assert!(!breaker.record_failure("openai", "gpt-4o", 3, COOLDOWN, now));
+        assert!(!breaker.record_failure("openai", "gpt-4o", 3, COOLDOWN, now));
+        assert!(breaker.allows("openai", "gpt-4o", now));
+        assert!(breaker.record_failure("openai", "gpt-4o", 3, COOLDOWN, now));
+        assert!(!breaker.allows("openai", "gpt-4o", now));
+        assert!(breaker.allows("openai", "gpt-4o-mini", now));
+        assert!(breaker.allows("anthropic", "gpt-4o", now));
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let mut breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        breaker.record_failure("openai", "gpt-4o", 2, COOLDOWN, now);
+        breaker.record_success("openai", "gpt-4o");
+        assert!(!breaker.record_failure("openai", "gpt-4o", 2, COOLDOWN, now));
+        assert!(breaker.allows("openai", "gpt-4o", now));
+    }
+
+    #[test]
+    fn trial_after_cooldown_reopens_on_failure() {
+        let mut breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        breaker.record_failure("openai", "gpt-4o", 1, COOLDOWN, now);
+        let later = now + COOLDOWN;
+        assert!(breaker.allows("openai", "gpt-4o", later));
+        assert!(breaker.record_failure("openai", "gpt-4o", 1, COOLDOWN, later));
+        assert!(!breaker.allows("openai", "gpt-4o", later));
+
+        breaker.record_success("openai", "gpt-4o");
+        assert!(breaker.allows("openai", "gpt-4o", later));
+    }
+
+    #[test]
+    fn zero_threshold_never_opens() {
+        let mut breaker = CircuitBreaker::default();
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert!(!breaker.record_failure("openai", "gpt-4o", 0, COOLDOWN, now));
+        }
+        assert!(breaker.allows("openai", "gpt-4o", now));
+    }
+}
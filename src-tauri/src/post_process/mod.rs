@@ -0,0 +1,913 @@
+pub mod circuit_breaker;
+pub mod template;
+
+#[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+use crate::apple_intelligence;
+use crate::audio_toolkit::normalize_text;
+use crate::llm_client::{self, ChatRequest};
+use crate::managers::history::PostProcessStepRecord;
+#[cfg(feature = "local-llm")]
+use crate::managers::local_llm::LocalLlmManager;
+use crate::settings::{
+    AppSettings, LLMPrompt, PostProcessProvider, PostProcessStep, PostProcessStepKind,
+    APPLE_INTELLIGENCE_PROVIDER_ID, LOCAL_LLM_PROVIDER_ID,
+};
+use circuit_breaker::CircuitBreaker;
+use ferrous_opencc::{config::BuiltinConfig, OpenCC};
+use log::{debug, error, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use specta::Type;
+use std::sync::mpsc;
+#[cfg(feature = "local-llm")]
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+#[cfg(feature = "local-llm")]
+use tauri::Manager;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use template::{PromptContext, PromptTemplate};
+
+/// Prompt used by `Translate` steps; `${output}` is the text to translate.
+const TRANSLATE_PROMPT: &str = "Translate the following text into ${target_language}. \
+Reply with the translation only, without quotes or commentary.\n\n${output}";
+
+/// Below this confidence a detected language is ignored. Short dictations
+/// ("see you tomorrow") rarely get there, which is fine: they keep "auto".
+const MIN_LANGUAGE_CONFIDENCE: f64 = 0.5;
+
+/// How long reading the selection in the focused application may take.
+const SELECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Selection being read for the dictation that is being transcribed.
+static PENDING_SELECTION: Lazy<Mutex<Option<mpsc::Receiver<Option<String>>>>> =
+    Lazy::new(Default::default);
+
+/// Failure history of the post-processing providers, shared by all dictations.
+static CIRCUIT_BREAKER: Lazy<Mutex<CircuitBreaker>> = Lazy::new(Default::default);
+
+/// Why the user should know that post-processing did not go as configured.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessNotice {
+    /// An LLM step was answered by a fallback provider
+    Degraded,
+    /// An LLM step got no answer, so its input was kept as is
+    Skipped,
+}
+
+/// Result of running the post-processing pipeline over a transcription.
+#[derive(Debug, Default)]
+pub struct PostProcessOutcome {
+    pub text: String,
+    /// Steps that ran, in order, with their intermediate output
+    pub steps: Vec<PostProcessStepRecord>,
+    /// The last LLM prompt that produced output
+    pub prompt: Option<String>,
+    /// Provider used by the LLM steps, if any of them produced output
+    pub provider: Option<String>,
+    /// Text already handed to the stream sink. Equals `text` when the last
+    /// step streamed successfully; anything else means the stream broke off.
+    pub streamed: String,
+    pub notice: Option<PostProcessNotice>,
+}
+
+/// Text returned by a provider for an LLM step.
+#[derive(Debug)]
+pub struct LlmAnswer {
+    pub text: String,
+    pub provider_id: String,
+    /// Answered by a fallback instead of the selected provider
+    pub fallback: bool,
+}
+
+/// Receives LLM output piece by piece while it streams in.
+pub type StreamSink<'a> = &'a mut (dyn FnMut(&str) + Send);
+
+enum StepOutput {
+    /// The step does not apply to this dictation (disabled, not configured, ...)
+    Skipped,
+    Failed,
+    Text(String),
+}
+
+/// Runs the post-processing pipeline over `transcription`. Each step receives
+/// the previous step's output; a step that fails leaves the text unchanged.
+/// LLM prompts are rendered against `context` with `output` set to the step input.
+///
+/// With a `stream` sink, the last step streams its output into it when that
+/// step is an LLM step; earlier steps' output would be rewritten anyway.
+pub async fn run_pipeline(
+    app: &AppHandle,
+    settings: &AppSettings,
+    transcription: &str,
+    context: &PromptContext,
+    mut stream: Option<StreamSink<'_>>,
+) -> PostProcessOutcome {
+    let language = dictation_language(settings, transcription);
+    let language = language.as_str();
+    let mut outcome = PostProcessOutcome {
+        text: transcription.to_string(),
+        ..Default::default()
+    };
+
+    let steps: Vec<PostProcessStep> = settings
+        .effective_post_process_pipeline()
+        .into_iter()
+        .filter(|step| {
+            let applies = step.enabled && step.applies_to(language);
+            if !applies {
+                debug!(
+                    "Post-processing step '{}' skipped for language '{}'",
+                    step.id, language
+                );
+            }
+            applies
+        })
+        .collect();
+    let stream_index = steps.len().checked_sub(1).filter(|&last| {
+        matches!(
+            steps[last].kind,
+            PostProcessStepKind::LlmPrompt | PostProcessStepKind::Translate
+        )
+    });
+
+    for (index, step) in steps.iter().enumerate() {
+        let sink = if stream_index == Some(index) {
+            stream.take()
+        } else {
+            None
+        };
+        let started = Instant::now();
+        let input = outcome.text.clone();
+        let output = run_step(
+            app,
+            settings,
+            step,
+            language,
+            &input,
+            context,
+            sink,
+            &mut outcome,
+        )
+        .await;
+        let text = match output {
+            StepOutput::Skipped => continue,
+            StepOutput::Failed => None,
+            StepOutput::Text(text) => Some(text),
+        };
+        debug!(
+            "Post-processing step '{}' finished in {:?}",
+            step.id,
+            started.elapsed()
+        );
+
+        outcome.steps.push(PostProcessStepRecord {
+            step_id: step.id.clone(),
+            kind: step.kind,
+            output: text.clone(),
+            duration_ms: started.elapsed().as_millis() as i64,
+        });
+        if let Some(text) = text {
+            outcome.text = text;
+        }
+    }
+
+    outcome
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_step(
+    app: &AppHandle,
+    settings: &AppSettings,
+    step: &PostProcessStep,
+    language: &str,
+    input: &str,
+    context: &PromptContext,
+    stream: Option<StreamSink<'_>>,
+    outcome: &mut PostProcessOutcome,
+) -> StepOutput {
+    match step.kind {
+        PostProcessStepKind::ChineseConversion => match convert_chinese_variant(settings, input) {
+            Some(Ok(text)) => StepOutput::Text(text),
+            Some(Err(())) => StepOutput::Failed,
+            None => StepOutput::Skipped,
+        },
+        // Transcription already normalized the text; a second pass could only
+        // undo or garble the first
+        PostProcessStepKind::Normalization if settings.text_normalization_enabled => {
+            debug!("Skipping step '{}': transcription normalizes text", step.id);
+            StepOutput::Skipped
+        }
+        PostProcessStepKind::Normalization => StepOutput::Text(normalize_text(
+            input,
+            language,
+            &settings.text_normalization,
+        )),
+        PostProcessStepKind::RegexReplace => {
+            let Some(rule) = &step.regex_rule else {
+                return StepOutput::Skipped;
+            };
+            match rule.compile() {
+                Ok(regex) => StepOutput::Text(
+                    regex
+                        .replace_all(input, rule.replacement.as_str())
+                        .into_owned(),
+                ),
+                Err(e) => {
+                    warn!(
+                        "Skipping invalid regex '{}' in step '{}': {}",
+                        rule.pattern, step.id, e
+                    );
+                    StepOutput::Failed
+                }
+            }
+        }
+        PostProcessStepKind::LlmPrompt => {
+            let prompt_id = step
+                .prompt_id
+                .as_ref()
+                .or(settings.post_process_selected_prompt_id.as_ref());
+            let Some(prompt_id) = prompt_id else {
+                debug!("Post-processing skipped because no prompt is selected");
+                return StepOutput::Skipped;
+            };
+            let Some(prompt) = settings
+                .post_process_prompts
+                .iter()
+                .find(|prompt| &prompt.id == prompt_id)
+            else {
+                debug!(
+                    "Post-processing skipped because prompt '{}' was not found",
+                    prompt_id
+                );
+                return StepOutput::Skipped;
+            };
+            run_llm_step(app, settings, prompt, input, context, stream, outcome).await
+        }
+        PostProcessStepKind::Translate => {
+            let Some(target) = step
+                .target_language
+                .as_deref()
+                .filter(|t| !t.trim().is_empty())
+            else {
+                return StepOutput::Skipped;
+            };
+            let prompt = LLMPrompt {
+                id: step.id.clone(),
+                name: step.id.clone(),
+                prompt: TRANSLATE_PROMPT.replace("${target_language}", target),
+                system_prompt: String::new(),
+                temperature: None,
+                max_tokens: None,
+            };
+            run_llm_step(app, settings, &prompt, input, context, stream, outcome).await
+        }
+    }
+}
+
+async fn run_llm_step(
+    app: &AppHandle,
+    settings: &AppSettings,
+    prompt: &LLMPrompt,
+    input: &str,
+    context: &PromptContext,
+    stream: Option<StreamSink<'_>>,
+    outcome: &mut PostProcessOutcome,
+) -> StepOutput {
+    if !settings.post_process_enabled {
+        return StepOutput::Skipped;
+    }
+    if prompt.prompt.trim().is_empty() {
+        debug!("Post-processing skipped because the selected prompt is empty");
+        return StepOutput::Skipped;
+    }
+    let request = chat_request(settings, prompt, input, context);
+    let result = match stream {
+        Some(sink) => {
+            let mut streamed = String::new();
+            let mut forward = |delta: &str| {
+                streamed.push_str(delta);
+                sink(delta);
+            };
+            let forward: StreamSink<'_> = &mut forward;
+            let result = run_llm_prompt(app, settings, &request, Some(forward)).await;
+            outcome.streamed = streamed;
+            result
+        }
+        None => run_llm_prompt(app, settings, &request, None).await,
+    };
+    match result {
+        Some(answer) => {
+            outcome.prompt = Some(prompt.prompt.clone());
+            outcome.provider = Some(answer.provider_id);
+            if answer.fallback && outcome.notice.is_none() {
+                outcome.notice = Some(PostProcessNotice::Degraded);
+            }
+            StepOutput::Text(answer.text)
+        }
+        None => {
+            outcome.notice = Some(PostProcessNotice::Skipped);
+            StepOutput::Failed
+        }
+    }
+}
+
+/// Renders a prompt template for the text `output`. Prompts saved before
+/// templates were validated fall back to plain `${output}` substitution.
+pub fn render_prompt(prompt: &str, output: &str, context: &PromptContext) -> String {
+    match PromptTemplate::parse(prompt) {
+        Ok(template) => template.render(&PromptContext {
+            output: output.to_string(),
+            ..context.clone()
+        }),
+        Err(e) => {
+            warn!(
+                "Invalid prompt template, substituting ${{output}} only: {}",
+                e
+            );
+            prompt.replace("${output}", output)
+        }
+    }
+}
+
+/// Renders both halves of `prompt` for the text `output`.
+pub fn chat_request(
+    settings: &AppSettings,
+    prompt: &LLMPrompt,
+    output: &str,
+    context: &PromptContext,
+) -> ChatRequest {
+    ChatRequest {
+        system: render_prompt(&prompt.system_prompt, output, context),
+        user: render_prompt(&prompt.prompt, output, context),
+        temperature: prompt.temperature,
+        max_tokens: prompt.max_tokens,
+        timeout: Duration::from_secs(settings.post_process_timeout_secs.max(1)),
+    }
+}
+
+/// Sends a rendered request to the active post-processing provider, then to
+/// each fallback in turn until one answers. Providers that failed repeatedly
+/// are skipped while their circuit breaker is open. Only the first provider
+/// tried streams into `stream`; a fallback's answer arrives in one piece.
+/// Returns `None` if nothing usable came back.
+pub async fn run_llm_prompt(
+    app: &AppHandle,
+    settings: &AppSettings,
+    request: &ChatRequest,
+    mut stream: Option<StreamSink<'_>>,
+) -> Option<LlmAnswer> {
+    let chain = provider_chain(settings);
+    if chain.is_empty() {
+        debug!("Post-processing enabled but no provider is selected");
+        return None;
+    }
+
+    for (index, (provider, model)) in chain.iter().enumerate() {
+        if model.trim().is_empty() {
+            debug!(
+                "Post-processing skipped provider '{}' because it has no model configured",
+                provider.id
+            );
+            continue;
+        }
+        if !CIRCUIT_BREAKER
+            .lock()
+            .unwrap()
+            .allows(&provider.id, model, Instant::now())
+        {
+            debug!(
+                "Post-processing skipped model '{}' of provider '{}' after repeated failures",
+                model, provider.id
+            );
+            continue;
+        }
+
+        let request = ChatRequest {
+            timeout: settings.post_process_timeout(&provider.id),
+            ..request.clone()
+        };
+        let result = run_provider(app, provider, model, settings, &request, stream.take()).await;
+
+        let mut breaker = CIRCUIT_BREAKER.lock().unwrap();
+        match result {
+            Some(text) => {
+                breaker.record_success(&provider.id, model);
+                return Some(LlmAnswer {
+                    text,
+                    provider_id: provider.id.clone(),
+                    fallback: index > 0,
+                });
+            }
+            None => {
+                let cooldown = Duration::from_secs(settings.post_process_breaker_cooldown_secs);
+                if breaker.record_failure(
+                    &provider.id,
+                    model,
+                    settings.post_process_breaker_threshold,
+                    cooldown,
+                    Instant::now(),
+                ) {
+                    warn!(
+                        "Model '{}' of provider '{}' failed {} times in a row, skipping it for {:?}",
+                        model, provider.id, settings.post_process_breaker_threshold, cooldown
+                    );
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// The selected provider followed by the configured fallbacks, each with the
+/// model to use. Unknown providers and repeated entries are left out.
+fn provider_chain(settings: &AppSettings) -> Vec<(PostProcessProvider, String)> {
+    let configured_model = |provider_id: &str| {
+        settings
+            .post_process_models
+            .get(provider_id)
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let mut chain: Vec<(PostProcessProvider, String)> = Vec::new();
+    if let Some(provider) = settings.active_post_process_provider() {
+        chain.push((provider.clone(), configured_model(&provider.id)));
+    }
+    for fallback in &settings.post_process_fallbacks {
+        let Some(provider) = settings.post_process_provider(&fallback.provider_id) else {
+            warn!(
+                "Post-processing fallback provider '{}' not found",
+                fallback.provider_id
+            );
+            continue;
+        };
+        let model = if fallback.model.trim().is_empty() {
+            configured_model(&provider.id)
+        } else {
+            fallback.model.clone()
+        };
+        if !chain
+            .iter()
+            .any(|(p, m)| p.id == provider.id && *m == model)
+        {
+            chain.push((provider.clone(), model));
+        }
+    }
+    chain
+}
+
+/// Sends `request` to one provider. Returns `None` if nothing usable came back.
+async fn run_provider(
+    app: &AppHandle,
+    provider: &PostProcessProvider,
+    model: &str,
+    settings: &AppSettings,
+    request: &ChatRequest,
+    stream: Option<StreamSink<'_>>,
+) -> Option<String> {
+    debug!(
+        "Starting LLM post-processing with provider '{}' (model: {})",
+        provider.id, model
+    );
+
+    debug!(
+        "Processed prompt length: {} chars (system: {} chars)",
+        request.user.len(),
+        request.system.len()
+    );
+
+    if provider.id == LOCAL_LLM_PROVIDER_ID {
+        #[cfg(feature = "local-llm")]
+        {
+            // Generation is CPU bound, so it runs on a blocking thread and
+            // hands its output back piece by piece
+            let manager = Arc::clone(&app.state::<Arc<LocalLlmManager>>());
+            let (delta_tx, mut delta_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+            let worker = {
+                let model = model.to_string();
+                let request = request.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    manager.generate(&model, &request, &mut |delta: &str| {
+                        let _ = delta_tx.send(delta.to_string());
+                    })
+                })
+            };
+            let mut stream = stream;
+            let generation = async {
+                // The channel closes once the worker is done with the sender
+                while let Some(delta) = delta_rx.recv().await {
+                    if let Some(sink) = stream.as_mut() {
+                        sink(&delta);
+                    }
+                }
+                worker.await
+            };
+            let result = match tokio::time::timeout(request.timeout, generation).await {
+                Ok(Ok(result)) => result,
+                Ok(Err(e)) => Err(anyhow::anyhow!("Local generation task failed: {}", e)),
+                Err(_) => Err(anyhow::anyhow!(
+                    "Local generation timed out after {:?}",
+                    request.timeout
+                )),
+            };
+            return match result {
+                Ok(result) if !result.trim().is_empty() => {
+                    debug!(
+                        "Local LLM post-processing succeeded. Output length: {} chars",
+                        result.len()
+                    );
+                    Some(result)
+                }
+                Ok(_) => {
+                    debug!("Local LLM returned an empty response");
+                    None
+                }
+                Err(e) => {
+                    error!("Local LLM post-processing failed: {}", e);
+                    None
+                }
+            };
+        }
+
+        #[cfg(not(feature = "local-llm"))]
+        {
+            let _ = app;
+            debug!("Local LLM provider selected but this build has no local LLM support");
+            return None;
+        }
+    }
+
+    if provider.id == APPLE_INTELLIGENCE_PROVIDER_ID {
+        #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
+        {
+            if !apple_intelligence::check_apple_intelligence_availability() {
+                debug!("Apple Intelligence selected but not currently available on this device");
+                return None;
+            }
+
+            let token_limit = model.trim().parse::<i32>().unwrap_or(0);
+            let prompt = request.combined_prompt();
+            return match apple_intelligence::process_text(&prompt, token_limit) {
+                Ok(result) => {
+                    if result.trim().is_empty() {
+                        debug!("Apple Intelligence returned an empty response");
+                        None
+                    } else {
+                        debug!(
+                            "Apple Intelligence post-processing succeeded. Output length: {} chars",
+                            result.len()
+                        );
+                        Some(result)
+                    }
+                }
+                Err(err) => {
+                    error!("Apple Intelligence post-processing failed: {}", err);
+                    None
+                }
+            };
+        }
+
+        #[cfg(not(all(target_os = "macos", target_arch = "aarch64")))]
+        {
+            debug!("Apple Intelligence provider selected on unsupported platform");
+            return None;
+        }
+    }
+
+    let api_key = settings
+        .post_process_api_keys
+        .get(&provider.id)
+        .cloned()
+        .unwrap_or_default();
+
+    // Send the chat completion request
+    let result = match stream {
+        Some(sink) => {
+            llm_client::stream_chat_completion(provider, api_key, model, request, sink).await
+        }
+        None => llm_client::send_chat_completion(provider, api_key, model, request).await,
+    };
+    match result {
+        Ok(Some(content)) => {
+            debug!(
+                "LLM post-processing succeeded for provider '{}'. Output length: {} chars",
+                provider.id,
+                content.len()
+            );
+            Some(content)
+        }
+        Ok(None) => {
+            error!("LLM API response has no content");
+            None
+        }
+        Err(e) => {
+            error!(
+                "LLM post-processing failed for provider '{}': {}",
+                provider.id, e
+            );
+            None
+        }
+    }
+}
+
+/// Language the dictation ends up in, as seen by post-processing. The
+/// transcription engines don't report the language they detected, so with
+/// automatic detection it is guessed from `text`; "auto" is kept when the
+/// guess is unreliable.
+fn dictation_language(settings: &AppSettings, text: &str) -> String {
+    if settings.translate_to_english {
+        "en".to_string()
+    } else if settings.selected_language == "auto" {
+        detect_language(text).unwrap_or("auto").to_string()
+    } else {
+        settings.selected_language.clone()
+    }
+}
+
+/// ISO 639-1 code of the language `text` is written in, limited to the
+/// languages the app offers. Chinese is reported as "zh" since the script
+/// variant can't be told apart reliably.
+fn detect_language(text: &str) -> Option<&'static str> {
+    use whatlang::Lang;
+
+    let info =
+        whatlang::detect(text).filter(|info| info.confidence() >= MIN_LANGUAGE_CONFIDENCE)?;
+    let code = match info.lang() {
+        Lang::Eng => "en",
+        Lang::Cmn => "zh",
+        Lang::Deu => "de",
+        Lang::Spa => "es",
+        Lang::Rus => "ru",
+        Lang::Kor => "ko",
+        Lang::Fra => "fr",
+        Lang::Jpn => "ja",
+        Lang::Por => "pt",
+        Lang::Tur => "tr",
+        Lang::Pol => "pl",
+        Lang::Cat => "ca",
+        Lang::Nld => "nl",
+        Lang::Ara => "ar",
+        Lang::Swe => "sv",
+        Lang::Ita => "it",
+        Lang::Ind => "id",
+        Lang::Hin => "hi",
+        Lang::Fin => "fi",
+        Lang::Vie => "vi",
+        Lang::Heb => "he",
+        Lang::Ukr => "uk",
+        Lang::Ell => "el",
+        Lang::Ces => "cs",
+        Lang::Ron => "ro",
+        Lang::Dan => "da",
+        Lang::Hun => "hu",
+        Lang::Tam => "ta",
+        Lang::Nob => "no",
+        Lang::Tha => "th",
+        Lang::Urd => "ur",
+        Lang::Hrv => "hr",
+        Lang::Bul => "bg",
+        Lang::Lit => "lt",
+        Lang::Lat => "la",
+        Lang::Mal => "ml",
+        Lang::Slk => "sk",
+        Lang::Tel => "te",
+        Lang::Pes => "fa",
+        Lang::Lav => "lv",
+        Lang::Ben => "bn",
+        Lang::Srp => "sr",
+        Lang::Aze => "az",
+        Lang::Slv => "sl",
+        Lang::Kan => "kn",
+        Lang::Est => "et",
+        Lang::Mkd => "mk",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Whether any LLM prompt the pipeline would run reads `variable`, in its
+/// user or system template. Used to skip gathering context that is
+/// expensive or intrusive to collect.
+pub fn pipeline_uses_variable(settings: &AppSettings, variable: &str) -> bool {
+    settings
+        .effective_post_process_pipeline()
+        .iter()
+        .filter(|step| step.enabled && step.kind == PostProcessStepKind::LlmPrompt)
+        .filter_map(|step| {
+            let prompt_id = step
+                .prompt_id
+                .as_ref()
+                .or(settings.post_process_selected_prompt_id.as_ref())?;
+            settings
+                .post_process_prompts
+                .iter()
+                .find(|prompt| &prompt.id == prompt_id)
+        })
+        .flat_map(|prompt| [&prompt.prompt, &prompt.system_prompt])
+        .filter_map(|template| PromptTemplate::parse(template).ok())
+        .any(|template| template.uses(variable))
+}
+
+/// Template context shared by every dictation: language, date, custom words
+/// and whatever the caller knows about the target application. `text` is the
+/// dictation the language is detected from.
+pub fn prompt_context(
+    settings: &AppSettings,
+    text: &str,
+    app_name: Option<String>,
+    previous_output: Option<String>,
+) -> PromptContext {
+    let mut custom_words = settings.custom_words.clone();
+    for entry in &settings.vocabulary {
+        if !custom_words.contains(&entry.written) {
+            custom_words.push(entry.written.clone());
+        }
+    }
+
+    PromptContext {
+        language: dictation_language(settings, text),
+        app_name,
+        date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        previous_output,
+        custom_words,
+        ..Default::default()
+    }
+}
+
+/// Template context for a dictation that was just recorded. The clipboard and
+/// the selection are only read when a prompt in the pipeline asks for them.
+pub fn dictation_prompt_context(
+    app: &AppHandle,
+    settings: &AppSettings,
+    text: &str,
+    app_name: Option<String>,
+    previous_output: Option<String>,
+) -> PromptContext {
+    let mut context = prompt_context(settings, text, app_name, previous_output);
+    if pipeline_uses_variable(settings, "clipboard") {
+        context.clipboard = app.clipboard().read_text().ok();
+    }
+    if pipeline_uses_variable(settings, "selection") {
+        context.selection = take_pending_selection();
+    }
+    context
+}
+
+/// Start reading the selection in `focused_app` when a prompt in the pipeline
+/// asks for it. This runs while the recording is transcribed; the result is
+/// picked up by [`dictation_prompt_context`].
+pub fn begin_reading_selection(
+    app: &AppHandle,
+    settings: &AppSettings,
+    focused_app: Option<String>,
+) {
+    let pending = pipeline_uses_variable(settings, "selection").then(|| {
+        let (tx, rx) = mpsc::channel();
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(read_selection(&app, focused_app.as_deref()));
+        });
+        rx
+    });
+    if let Ok(mut slot) = PENDING_SELECTION.lock() {
+        *slot = pending;
+    }
+}
+
+fn take_pending_selection() -> Option<String> {
+    let pending = PENDING_SELECTION.lock().ok()?.take()?;
+    pending.recv_timeout(SELECTION_TIMEOUT).unwrap_or_else(|_| {
+        warn!("Timed out reading the selected text");
+        None
+    })
+}
+
+/// The selection in the focused application. On Linux this is the primary
+/// selection, which needs no key presses. Otherwise the selection is copied,
+/// except in terminals where Ctrl+C would interrupt the running program.
+fn read_selection(app: &AppHandle, focused_app: Option<&str>) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    match crate::clipboard::read_primary_selection() {
+        Ok(text) => return (!text.is_empty()).then_some(text),
+        Err(e) => debug!("Primary selection unavailable, copying instead: {}", e),
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    if focused_app.is_some_and(crate::helpers::focused_app::is_terminal) {
+        debug!("Not copying the selection from terminal {:?}", focused_app);
+        return None;
+    }
+    #[cfg(target_os = "macos")]
+    let _ = focused_app;
+
+    // Key events have to be sent from the main thread
+    let (tx, rx) = mpsc::channel();
+    let app_clone = app.clone();
+    app.run_on_main_thread(move || {
+        let _ = tx.send(crate::utils::read_selected_text(&app_clone));
+    })
+    .ok()?;
+
+    match rx.recv_timeout(SELECTION_TIMEOUT) {
+        Ok(Ok(text)) if !text.is_empty() => Some(text),
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => {
+            warn!("Failed to read the selected text: {}", e);
+            None
+        }
+        Err(_) => {
+            warn!("Timed out reading the selected text");
+            None
+        }
+    }
+}
+
+/// OpenCC conversion to the selected Chinese variant. `None` when the selected
+/// language is not zh-Hans / zh-Hant, `Some(Err)` if OpenCC failed to load.
+fn convert_chinese_variant(
+    settings: &AppSettings,
+    transcription: &str,
+) -> Option<Result<String, ()>> {
+    // Check if language is set to Simplified or Traditional Chinese
+    let is_simplified = settings.selected_language == "zh-Hans";
+    let is_traditional = settings.selected_language == "zh-Hant";
+
+    if !is_simplified && !is_traditional {
+        debug!("selected_language is not Simplified or Traditional Chinese; skipping translation");
+        return None;
+    }
+
+    debug!(
+        "Starting Chinese translation using OpenCC for language: {}",
+        settings.selected_language
+    );
+
+    // Use OpenCC to convert based on selected language
+    let config = if is_simplified {
+        // Convert Traditional Chinese to Simplified Chinese
+        BuiltinConfig::Tw2sp
+    } else {
+        // Convert Simplified Chinese to Traditional Chinese
+        BuiltinConfig::S2twp
+    };
+
+    match OpenCC::from_config(config) {
+        Ok(converter) => {
+            let converted = converter.convert(transcription);
+            debug!(
+                "OpenCC translation completed. Input length: {}, Output length: {}",
+                transcription.len(),
+                converted.len()
+            );
+            Some(Ok(converted))
+        }
+        Err(e) => {
+            error!("Failed to initialize OpenCC converter: {}. Falling back to original transcription.", e);
+            Some(Err(()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language("Please send the report to the team before Friday."),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language("Envía el informe al equipo antes del viernes, por favor."),
+            Some("es")
+        );
+        assert_eq!(
+            detect_language("请在星期五之前把报告发给团队。"),
+            Some("zh")
+        );
+        assert_eq!(detect_language("ok"), None);
+    }
+
+    #[test]
+    fn variables_in_the_system_prompt_count_as_used() {
+        let mut settings = crate::settings::get_default_settings();
+        settings.post_process_prompts = vec![LLMPrompt {
+            id: "reply".to_string(),
+            name: "Reply".to_string(),
+            prompt: "${output}".to_string(),
+            system_prompt: "You are answering a message in ${app_name}.".to_string(),
+            temperature: None,
+            max_tokens: None,
+        }];
+        settings.post_process_selected_prompt_id = Some("reply".to_string());
+
+        assert!(pipeline_uses_variable(&settings, "app_name"));
+        assert!(pipeline_uses_variable(&settings, "output"));
+        assert!(!pipeline_uses_variable(&settings, "selection"));
+    }
+}
@@ -0,0 +1,412 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Variables a post-processing prompt can reference as `${name}`.
+pub const PROMPT_VARIABLES: &[&str] = &[
+    "output",
+    "language",
+    "app_name",
+    "clipboard",
+    "selection",
+    "date",
+    "previous_output",
+    "custom_words",
+];
+
+/// Values substituted into a prompt template. Unset values render as empty
+/// text and are false in `${if ...}` blocks.
+#[derive(Debug, Clone, Default)]
+pub struct PromptContext {
+    /// Text being post-processed (the previous step's output)
+    pub output: String,
+    pub language: String,
+    pub app_name: Option<String>,
+    pub clipboard: Option<String>,
+    pub selection: Option<String>,
+    pub date: String,
+    /// Text inserted by the previous dictation
+    pub previous_output: Option<String>,
+    pub custom_words: Vec<String>,
+}
+
+impl PromptContext {
+    fn value(&self, name: &str) -> Cow<'_, str> {
+        let value = match name {
+            "output" => Some(&self.output),
+            "language" => Some(&self.language),
+            "app_name" => self.app_name.as_ref(),
+            "clipboard" => self.clipboard.as_ref(),
+            "selection" => self.selection.as_ref(),
+            "date" => Some(&self.date),
+            "previous_output" => self.previous_output.as_ref(),
+            "custom_words" => return Cow::Owned(self.custom_words.join(", ")),
+            _ => None,
+        };
+        Cow::Borrowed(value.map_or("", String::as_str))
+    }
+}
+
+/// Why a template could not be parsed. `offset` counts the characters before
+/// the offending tag in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    pub message: String,
+    pub offset: usize,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at character {})", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    /// `${if name}`: the value is not blank
+    Set(String),
+    /// `${if !name}`
+    NotSet(String),
+    /// `${if name == "value"}`, compared case-insensitively
+    Equals(String, String),
+    /// `${if name != "value"}`
+    NotEquals(String, String),
+}
+
+impl Condition {
+    fn variable(&self) -> &str {
+        match self {
+            Condition::Set(name)
+            | Condition::NotSet(name)
+            | Condition::Equals(name, _)
+            | Condition::NotEquals(name, _) => name,
+        }
+    }
+
+    fn evaluate(&self, context: &PromptContext) -> bool {
+        let value = context.value(self.variable());
+        match self {
+            Condition::Set(_) => !value.trim().is_empty(),
+            Condition::NotSet(_) => value.trim().is_empty(),
+            Condition::Equals(_, expected) => value.trim().eq_ignore_ascii_case(expected),
+            Condition::NotEquals(_, expected) => !value.trim().eq_ignore_ascii_case(expected),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Variable(String),
+    If {
+        condition: Condition,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+/// A parsed post-processing prompt.
+///
+/// Besides `${variable}` substitution, templates support conditional blocks:
+///
+/// ```text
+/// ${if selection}Rewrite the selection "${selection}" using: ${output}
+/// ${else}Clean up: ${output}${end}
+/// ```
+///
+/// Conditions are `${if name}`, `${if !name}`, `${if name == "value"}` and
+/// `${if name != "value"}`. Write `$${` for a literal `${`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    nodes: Vec<Node>,
+}
+
+struct OpenBlock {
+    condition: Condition,
+    then: Vec<Node>,
+    otherwise: Option<Vec<Node>>,
+    offset: usize,
+}
+
+impl PromptTemplate {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut root = Vec::new();
+        let mut blocks: Vec<OpenBlock> = Vec::new();
+        let mut text = String::new();
+        let mut rest = source;
+
+        while let Some(idx) = rest.find("${") {
+            let offset = source[..source.len() - rest.len() + idx].chars().count();
+            // "$${" is an escaped literal "${"
+            if idx > 0 && rest[..idx].ends_with('$') {
+                text.push_str(&rest[..idx - 1]);
+                text.push_str("${");
+                rest = &rest[idx + 2..];
+                continue;
+            }
+            text.push_str(&rest[..idx]);
+
+            let Some(close) = rest[idx..].find('}') else {
+                return Err(error("Unterminated '${'", offset));
+            };
+            let tag = rest[idx + 2..idx + close].trim();
+            rest = &rest[idx + close + 1..];
+
+            let target = current_nodes(&mut root, &mut blocks);
+            if !text.is_empty() {
+                target.push(Node::Text(std::mem::take(&mut text)));
+            }
+
+            match tag {
+                "else" => {
+                    let block = blocks
+                        .last_mut()
+                        .ok_or_else(|| error("'${else}' outside of an '${if}' block", offset))?;
+                    if block.otherwise.is_some() {
+                        return Err(error("Second '${else}' in the same block", offset));
+                    }
+                    block.otherwise = Some(Vec::new());
+                }
+                "end" => {
+                    let block = blocks
+                        .pop()
+                        .ok_or_else(|| error("'${end}' without a matching '${if}'", offset))?;
+                    let node = Node::If {
+                        condition: block.condition,
+                        then: block.then,
+                        otherwise: block.otherwise.unwrap_or_default(),
+                    };
+                    current_nodes(&mut root, &mut blocks).push(node);
+                }
+                _ => {
+                    if let Some(condition) = tag.strip_prefix("if ") {
+                        blocks.push(OpenBlock {
+                            condition: parse_condition(condition, offset)?,
+                            then: Vec::new(),
+                            otherwise: None,
+                            offset,
+                        });
+                    } else {
+                        check_variable(tag, offset)?;
+                        target.push(Node::Variable(tag.to_string()));
+                    }
+                }
+            }
+        }
+
+        text.push_str(rest);
+        if let Some(block) = blocks.last() {
+            return Err(error(
+                "'${if}' block is never closed with '${end}'",
+                block.offset,
+            ));
+        }
+        if !text.is_empty() {
+            root.push(Node::Text(text));
+        }
+        Ok(Self { nodes: root })
+    }
+
+    /// Variables the template reads, including those only used in conditions.
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut variables = BTreeSet::new();
+        collect_variables(&self.nodes, &mut variables);
+        variables
+    }
+
+    pub fn uses(&self, variable: &str) -> bool {
+        self.variables().contains(variable)
+    }
+
+    pub fn render(&self, context: &PromptContext) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, context, &mut out);
+        out
+    }
+}
+
+fn error(message: &str, offset: usize) -> TemplateError {
+    TemplateError {
+        message: message.to_string(),
+        offset,
+    }
+}
+
+fn check_variable(name: &str, offset: usize) -> Result<(), TemplateError> {
+    if PROMPT_VARIABLES.contains(&name) {
+        Ok(())
+    } else {
+        Err(TemplateError {
+            message: format!(
+                "Unknown variable '{}'; available: {}",
+                name,
+                PROMPT_VARIABLES.join(", ")
+            ),
+            offset,
+        })
+    }
+}
+
+fn parse_condition(source: &str, offset: usize) -> Result<Condition, TemplateError> {
+    let source = source.trim();
+    let comparison = source
+        .split_once("==")
+        .map(|(l, r)| (l, r, true))
+        .or_else(|| source.split_once("!=").map(|(l, r)| (l, r, false)));
+
+    let condition = match comparison {
+        Some((name, value, equals)) => {
+            let name = name.trim().to_string();
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value)
+                .to_string();
+            if equals {
+                Condition::Equals(name, value)
+            } else {
+                Condition::NotEquals(name, value)
+            }
+        }
+        None => match source.strip_prefix('!') {
+            Some(name) => Condition::NotSet(name.trim().to_string()),
+            None => Condition::Set(source.to_string()),
+        },
+    };
+    check_variable(condition.variable(), offset)?;
+    Ok(condition)
+}
+
+/// Where new nodes go: the innermost open block, or the top level.
+fn current_nodes<'a>(root: &'a mut Vec<Node>, blocks: &'a mut [OpenBlock]) -> &'a mut Vec<Node> {
+    match blocks.last_mut() {
+        Some(block) => block.otherwise.as_mut().unwrap_or(&mut block.then),
+        None => root,
+    }
+}
+
+fn collect_variables<'a>(nodes: &'a [Node], variables: &mut BTreeSet<&'a str>) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Variable(name) => {
+                variables.insert(name);
+            }
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                variables.insert(condition.variable());
+                collect_variables(then, variables);
+                collect_variables(otherwise, variables);
+            }
+        }
+    }
+}
+
+fn render_nodes(nodes: &[Node], context: &PromptContext, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Variable(name) => out.push_str(&context.value(name)),
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let branch = if condition.evaluate(context) {
+                    then
+                } else {
+                    otherwise
+                };
+                render_nodes(branch, context, out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> PromptContext {
+        PromptContext {
+            output: "hello world".to_string(),
+            language: "de".to_string(),
+            app_name: Some("Slack".to_string()),
+            date: "2024-05-01".to_string(),
+            custom_words: vec!["Tauri".to_string(), "Handy".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn render(source: &str) -> String {
+        PromptTemplate::parse(source).unwrap().render(&context())
+    }
+
+    #[test]
+    fn test_legacy_output_placeholder() {
+        assert_eq!(render("Fix: ${output}"), "Fix: hello world");
+        assert_eq!(render("No placeholders"), "No placeholders");
+    }
+
+    #[test]
+    fn test_variables() {
+        assert_eq!(
+            render("${app_name} on ${date} in ${language}: ${custom_words}"),
+            "Slack on 2024-05-01 in de: Tauri, Handy"
+        );
+        // Unset values are empty
+        assert_eq!(render("[${selection}]"), "[]");
+    }
+
+    #[test]
+    fn test_conditionals() {
+        assert_eq!(render("${if app_name}in ${app_name}${end}"), "in Slack");
+        assert_eq!(render("${if selection}sel${else}no sel${end}"), "no sel");
+        assert_eq!(render("${if !clipboard}empty${end}"), "empty");
+        assert_eq!(render("${if language == \"DE\"}Deutsch${end}"), "Deutsch");
+        assert_eq!(render("${if language != de}other${else}same${end}"), "same");
+    }
+
+    #[test]
+    fn test_nested_conditionals() {
+        let source = "${if app_name}A${if selection}B${else}C${end}D${end}E";
+        assert_eq!(render(source), "ACDE");
+    }
+
+    #[test]
+    fn test_escaped_placeholder() {
+        assert_eq!(render("literal $${output}"), "literal ${output}");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = PromptTemplate::parse("Hi ${nope}").unwrap_err();
+        assert!(err.message.contains("Unknown variable 'nope'"));
+        assert_eq!(err.offset, 3);
+        let err = PromptTemplate::parse("Résumé: ${nope}").unwrap_err();
+        assert_eq!(err.offset, 8);
+
+        assert!(PromptTemplate::parse("${if output}never closed").is_err());
+        assert!(PromptTemplate::parse("stray ${end}").is_err());
+        assert!(PromptTemplate::parse("stray ${else}").is_err());
+        assert!(PromptTemplate::parse("${if output}a${else}b${else}c${end}").is_err());
+        assert!(PromptTemplate::parse("open ${output").is_err());
+        assert!(PromptTemplate::parse("${if bogus}x${end}").is_err());
+    }
+
+    #[test]
+    fn test_variables_used() {
+        let template = PromptTemplate::parse("${if selection}${clipboard}${end}${output}").unwrap();
+        let used: Vec<&str> = template.variables().into_iter().collect();
+        assert_eq!(used, vec!["clipboard", "output", "selection"]);
+        assert!(template.uses("selection"));
+        assert!(!template.uses("app_name"));
+    }
+}
@@ -3,11 +3,20 @@ use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
 use specta::Type;
 use std::collections::HashMap;
+use std::time::Duration;
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
+use crate::audio_toolkit::{
+    AudioCodec, NormalizationOptions, RegexRule, VocabularyEntry, VoiceCommandPhrase,
+};
+
 pub const APPLE_INTELLIGENCE_PROVIDER_ID: &str = "apple_intelligence";
 pub const APPLE_INTELLIGENCE_DEFAULT_MODEL_ID: &str = "Apple Intelligence";
+pub const LOCAL_LLM_PROVIDER_ID: &str = "local_llm";
+pub const LOCAL_LLM_DEFAULT_MODEL_ID: &str = "qwen2.5-1.5b-instruct";
+/// Profile ids double as their shortcut binding ids, so they share this prefix.
+pub const PROFILE_ID_PREFIX: &str = "profile_";
 
 #[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "lowercase")]
@@ -90,6 +99,208 @@ pub struct LLMPrompt {
     pub id: String,
     pub name: String,
     pub prompt: String,
+    /// Template sent as a separate system message; empty sends only `prompt`
+    #[serde(default)]
+    pub system_prompt: String,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+/// What a post-processing pipeline step does with its input.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessStepKind {
+    /// OpenCC Simplified/Traditional conversion for zh-Hans / zh-Hant
+    ChineseConversion,
+    /// Local inverse text normalization using `text_normalization`; skipped
+    /// while `text_normalization_enabled` normalizes every transcription
+    Normalization,
+    /// An LLM prompt; `prompt_id` or the selected prompt
+    LlmPrompt,
+    /// A single regex replacement
+    RegexReplace,
+    /// LLM translation into `target_language`
+    Translate,
+}
+
+/// One step of the post-processing pipeline. Each step receives the output of
+/// the previous one.
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+pub struct PostProcessStep {
+    pub id: String,
+    pub kind: PostProcessStepKind,
+    #[serde(default = "default_post_process_step_enabled")]
+    pub enabled: bool,
+    /// Only run when dictating in one of these languages ("de" also covers
+    /// "de-AT"). Empty runs the step for every language.
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Prompt for `LlmPrompt`; `None` uses the selected prompt
+    #[serde(default)]
+    pub prompt_id: Option<String>,
+    #[serde(default)]
+    pub regex_rule: Option<RegexRule>,
+    #[serde(default)]
+    pub target_language: Option<String>,
+}
+
+impl PostProcessStep {
+    fn new(id: &str, kind: PostProcessStepKind) -> Self {
+        Self {
+            id: id.to_string(),
+            kind,
+            enabled: true,
+            languages: Vec::new(),
+            prompt_id: None,
+            regex_rule: None,
+            target_language: None,
+        }
+    }
+
+    pub fn applies_to(&self, language: &str) -> bool {
+        self.languages.is_empty()
+            || self.languages.iter().any(|filter| {
+                language.eq_ignore_ascii_case(filter)
+                    || language
+                        .to_lowercase()
+                        .starts_with(&format!("{}-", filter.to_lowercase()))
+            })
+    }
+}
+
+/// A provider to retry an LLM step with when the selected one fails.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Type)]
+pub struct PostProcessFallback {
+    pub provider_id: String,
+    /// Empty uses the model configured for the provider
+    #[serde(default)]
+    pub model: String,
+}
+
+/// A named bundle of dictation settings. While the profile is active its
+/// fields are laid over the global settings for each dictation; fields left
+/// as `None` fall through to the global setting. The globals themselves are
+/// never changed by a profile.
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+pub struct DictationProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub selected_model: Option<String>,
+    #[serde(default)]
+    pub selected_language: Option<String>,
+    #[serde(default)]
+    pub translate_to_english: Option<bool>,
+    #[serde(default)]
+    pub post_process_enabled: Option<bool>,
+    #[serde(default)]
+    pub post_process_provider_id: Option<String>,
+    #[serde(default)]
+    pub post_process_selected_prompt_id: Option<String>,
+    #[serde(default)]
+    pub paste_method: Option<PasteMethod>,
+    #[serde(default)]
+    pub append_trailing_space: Option<bool>,
+}
+
+impl DictationProfile {
+    /// Snapshot the profile-controlled fields of the current settings.
+    pub fn from_settings(id: String, name: String, settings: &AppSettings) -> Self {
+        Self {
+            id,
+            name,
+            selected_model: Some(settings.selected_model.clone()),
+            selected_language: Some(settings.selected_language.clone()),
+            translate_to_english: Some(settings.translate_to_english),
+            post_process_enabled: Some(settings.post_process_enabled),
+            post_process_provider_id: Some(settings.post_process_provider_id.clone()),
+            post_process_selected_prompt_id: settings.post_process_selected_prompt_id.clone(),
+            paste_method: Some(settings.paste_method),
+            append_trailing_space: Some(settings.append_trailing_space),
+        }
+    }
+
+    /// Lay the profile's overrides over `settings`. Only used on a copy;
+    /// the result must not be written back to the store.
+    pub fn apply_to(&self, settings: &mut AppSettings) {
+        if let Some(model) = &self.selected_model {
+            settings.selected_model = model.clone();
+        }
+        if let Some(language) = &self.selected_language {
+            settings.selected_language = language.clone();
+        }
+        if let Some(translate) = self.translate_to_english {
+            settings.translate_to_english = translate;
+        }
+        if let Some(enabled) = self.post_process_enabled {
+            settings.post_process_enabled = enabled;
+        }
+        if let Some(provider_id) = &self.post_process_provider_id {
+            settings.post_process_provider_id = provider_id.clone();
+        }
+        if self.post_process_selected_prompt_id.is_some() {
+            settings.post_process_selected_prompt_id = self.post_process_selected_prompt_id.clone();
+        }
+        if let Some(method) = self.paste_method {
+            settings.paste_method = method;
+        }
+        if let Some(append) = self.append_trailing_space {
+            settings.append_trailing_space = append;
+        }
+    }
+}
+
+/// Output overrides applied when dictating into a matching application.
+/// `app_match` is compared case-insensitively against the focused app's
+/// identifier (WM_CLASS, process name or executable name).
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+pub struct AppRule {
+    pub app_match: String,
+    #[serde(default)]
+    pub paste_method: Option<PasteMethod>,
+    #[serde(default)]
+    pub append_trailing_space: Option<bool>,
+    #[serde(default)]
+    pub post_process_enabled: Option<bool>,
+    #[serde(default)]
+    pub post_process_selected_prompt_id: Option<String>,
+}
+
+impl AppRule {
+    pub fn matches(&self, app: &str) -> bool {
+        let pattern = self.app_match.trim().to_lowercase();
+        !pattern.is_empty() && app.to_lowercase().contains(&pattern)
+    }
+
+    pub fn apply_to(&self, settings: &mut AppSettings) {
+        if let Some(method) = self.paste_method {
+            settings.paste_method = method;
+        }
+        if let Some(append) = self.append_trailing_space {
+            settings.append_trailing_space = append;
+        }
+        if let Some(enabled) = self.post_process_enabled {
+            settings.post_process_enabled = enabled;
+        }
+        if self.post_process_selected_prompt_id.is_some() {
+            settings.post_process_selected_prompt_id = self.post_process_selected_prompt_id.clone();
+        }
+    }
+}
+
+/// Wire format a post-processing provider speaks.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderDialect {
+    /// `POST /chat/completions`
+    #[default]
+    OpenAi,
+    /// Anthropic `POST /messages`
+    Anthropic,
+    /// Gemini `POST /models/{model}:generateContent`
+    Gemini,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
@@ -101,6 +312,8 @@ pub struct PostProcessProvider {
     pub allow_base_url_edit: bool,
     #[serde(default)]
     pub models_endpoint: Option<String>,
+    #[serde(default)]
+    pub dialect: ProviderDialect,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
@@ -151,6 +364,24 @@ pub enum RecordingRetentionPeriod {
     Months3,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureSource {
+    #[default]
+    Microphone,
+    SystemAudio,
+    Both,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VadBackend {
+    #[default]
+    Silero,
+    Energy,
+    Off,
+}
+
 impl Default for ModelUnloadTimeout {
     fn default() -> Self {
         ModelUnloadTimeout::Never
@@ -251,8 +482,22 @@ pub struct AppSettings {
     pub selected_microphone: Option<String>,
     #[serde(default)]
     pub clamshell_microphone: Option<String>,
+    /// Microphones to fall back to, in order, when the selected one is
+    /// unplugged; the default input comes last
+    #[serde(default)]
+    pub microphone_priority: Vec<String>,
     #[serde(default)]
     pub selected_output_device: Option<String>,
+    /// Record the microphone, what the system plays, or both mixed
+    #[serde(default)]
+    pub capture_source: CaptureSource,
+    /// `None` picks the default loopback device
+    #[serde(default)]
+    pub system_audio_device: Option<String>,
+    #[serde(default = "default_capture_gain")]
+    pub microphone_gain: f32,
+    #[serde(default = "default_capture_gain")]
+    pub system_audio_gain: f32,
     #[serde(default = "default_translate_to_english")]
     pub translate_to_english: bool,
     #[serde(default = "default_selected_language")]
@@ -266,6 +511,18 @@ pub struct AppSettings {
     #[serde(default)]
     pub custom_words: Vec<String>,
     #[serde(default)]
+    pub vocabulary: Vec<VocabularyEntry>,
+    #[serde(default)]
+    pub vocabulary_regex_rules: Vec<RegexRule>,
+    #[serde(default)]
+    pub whisper_initial_prompt_enabled: bool,
+    #[serde(default)]
+    pub whisper_prompt_include_previous: bool,
+    #[serde(default)]
+    pub text_normalization_enabled: bool,
+    #[serde(default)]
+    pub text_normalization: NormalizationOptions,
+    #[serde(default)]
     pub model_unload_timeout: ModelUnloadTimeout,
     #[serde(default = "default_word_correction_threshold")]
     pub word_correction_threshold: f64,
@@ -292,7 +549,72 @@ pub struct AppSettings {
     #[serde(default)]
     pub post_process_selected_prompt_id: Option<String>,
     #[serde(default)]
+    pub post_process_pipeline: Vec<PostProcessStep>,
+    /// Type LLM output as it streams in instead of pasting it at the end
+    #[serde(default)]
+    pub post_process_streaming: bool,
+    #[serde(default = "default_post_process_timeout_secs")]
+    pub post_process_timeout_secs: u64,
+    /// Per-provider override of `post_process_timeout_secs`
+    #[serde(default)]
+    pub post_process_provider_timeouts: HashMap<String, u64>,
+    /// Tried in order when the selected provider fails
+    #[serde(default)]
+    pub post_process_fallbacks: Vec<PostProcessFallback>,
+    /// Consecutive failures after which a provider is skipped for
+    /// `post_process_breaker_cooldown_secs`; 0 never skips
+    #[serde(default = "default_post_process_breaker_threshold")]
+    pub post_process_breaker_threshold: u32,
+    #[serde(default = "default_post_process_breaker_cooldown_secs")]
+    pub post_process_breaker_cooldown_secs: u64,
+    /// Hands-free sessions end after this long without speech; 0 keeps
+    /// listening until stopped
+    #[serde(default = "default_hands_free_silence_timeout_secs")]
+    pub hands_free_silence_timeout_secs: u64,
+    /// Label who is speaking in meeting transcripts; needs the speaker model
+    #[serde(default)]
+    pub meeting_speaker_detection: bool,
+    /// Most distinct voices a meeting transcript tells apart
+    #[serde(default = "default_meeting_max_speakers")]
+    pub meeting_max_speakers: u32,
+    /// `Off` keeps every frame of a recording; hands-free mode needs a VAD
+    #[serde(default)]
+    pub vad_backend: VadBackend,
+    /// Speech probability above which Silero counts a frame as voice
+    #[serde(default = "default_vad_threshold")]
+    pub vad_threshold: f32,
+    /// Level in dBFS a frame must exceed for the energy VAD
+    #[serde(default = "default_vad_energy_threshold_db")]
+    pub vad_energy_threshold_db: f32,
+    /// Frames (30 ms each) kept from before speech starts
+    #[serde(default = "default_vad_prefill_frames")]
+    pub vad_prefill_frames: u32,
+    /// Frames still kept after speech stops
+    #[serde(default = "default_vad_hangover_frames")]
+    pub vad_hangover_frames: u32,
+    /// Consecutive voiced frames needed before speech starts
+    #[serde(default = "default_vad_onset_frames")]
+    pub vad_onset_frames: u32,
+    /// Audio clean-up ahead of the VAD
+    #[serde(default)]
+    pub noise_suppression: bool,
+    #[serde(default)]
+    pub high_pass_filter: bool,
+    #[serde(default)]
+    pub auto_gain_control: bool,
+    /// Audio from just before a recording starts that is kept with it;
+    /// always-on microphone only, 0 turns it off
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+    #[serde(default)]
     pub mute_while_recording: bool,
+    /// Share of the output volume taken away while recording; 1.0 mutes
+    #[serde(default = "default_duck_amount")]
+    pub duck_amount: f32,
+    /// Applications whose streams are ducked instead of the whole output
+    /// (PipeWire/PulseAudio only); empty ducks everything
+    #[serde(default)]
+    pub duck_apps: Vec<String>,
     #[serde(default)]
     pub append_trailing_space: bool,
     #[serde(default = "default_app_language")]
@@ -307,6 +629,20 @@ pub struct AppSettings {
     pub gemini_send_audio: bool,
     #[serde(default = "default_screenshot_mode")]
     pub screenshot_mode: ScreenshotMode,
+    #[serde(default = "default_typing_wpm")]
+    pub typing_wpm: u32,
+    #[serde(default)]
+    pub recording_format: AudioCodec,
+    #[serde(default)]
+    pub dictation_profiles: Vec<DictationProfile>,
+    #[serde(default)]
+    pub active_profile_id: Option<String>,
+    #[serde(default)]
+    pub app_rules: Vec<AppRule>,
+    #[serde(default = "default_voice_commands_enabled")]
+    pub voice_commands_enabled: bool,
+    #[serde(default)]
+    pub custom_voice_commands: Vec<VoiceCommandPhrase>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
@@ -383,6 +719,10 @@ fn default_post_process_enabled() -> bool {
     false
 }
 
+fn default_post_process_step_enabled() -> bool {
+    true
+}
+
 fn default_app_language() -> String {
     tauri_plugin_os::locale()
         .and_then(|l| l.split(['-', '_']).next().map(String::from))
@@ -409,6 +749,14 @@ fn default_screenshot_mode() -> ScreenshotMode {
     ScreenshotMode::ActiveWindow
 }
 
+fn default_typing_wpm() -> u32 {
+    40
+}
+
+fn default_voice_commands_enabled() -> bool {
+    false
+}
+
 fn default_post_process_provider_id() -> String {
     "openai".to_string()
 }
@@ -421,6 +769,7 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://api.openai.com/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            dialect: ProviderDialect::OpenAi,
         },
         PostProcessProvider {
             id: "openrouter".to_string(),
@@ -428,6 +777,7 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://openrouter.ai/api/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            dialect: ProviderDialect::OpenAi,
         },
         PostProcessProvider {
             id: "anthropic".to_string(),
@@ -435,6 +785,15 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://api.anthropic.com/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            dialect: ProviderDialect::Anthropic,
+        },
+        PostProcessProvider {
+            id: "gemini".to_string(),
+            label: "Google Gemini".to_string(),
+            base_url: "https://generativelanguage.googleapis.com/v1beta".to_string(),
+            allow_base_url_edit: false,
+            models_endpoint: Some("/models".to_string()),
+            dialect: ProviderDialect::Gemini,
         },
         PostProcessProvider {
             id: "groq".to_string(),
@@ -442,6 +801,7 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://api.groq.com/openai/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            dialect: ProviderDialect::OpenAi,
         },
         PostProcessProvider {
             id: "cerebras".to_string(),
@@ -449,6 +809,7 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "https://api.cerebras.ai/v1".to_string(),
             allow_base_url_edit: false,
             models_endpoint: Some("/models".to_string()),
+            dialect: ProviderDialect::OpenAi,
         },
     ];
 
@@ -464,6 +825,19 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
             base_url: "apple-intelligence://local".to_string(),
             allow_base_url_edit: false,
             models_endpoint: None,
+            dialect: ProviderDialect::OpenAi,
+        });
+    }
+
+    #[cfg(feature = "local-llm")]
+    {
+        providers.push(PostProcessProvider {
+            id: LOCAL_LLM_PROVIDER_ID.to_string(),
+            label: "Local (offline)".to_string(),
+            base_url: "local://gguf".to_string(),
+            allow_base_url_edit: false,
+            models_endpoint: None,
+            dialect: ProviderDialect::OpenAi,
         });
     }
 
@@ -474,6 +848,7 @@ fn default_post_process_providers() -> Vec<PostProcessProvider> {
         base_url: "http://localhost:11434/v1".to_string(),
         allow_base_url_edit: true,
         models_endpoint: Some("/models".to_string()),
+        dialect: ProviderDialect::OpenAi,
     });
 
     providers
@@ -491,6 +866,9 @@ fn default_model_for_provider(provider_id: &str) -> String {
     if provider_id == APPLE_INTELLIGENCE_PROVIDER_ID {
         return APPLE_INTELLIGENCE_DEFAULT_MODEL_ID.to_string();
     }
+    if provider_id == LOCAL_LLM_PROVIDER_ID {
+        return LOCAL_LLM_DEFAULT_MODEL_ID.to_string();
+    }
     String::new()
 }
 
@@ -505,24 +883,88 @@ fn default_post_process_models() -> HashMap<String, String> {
     map
 }
 
+fn default_post_process_timeout_secs() -> u64 {
+    30
+}
+
+fn default_hands_free_silence_timeout_secs() -> u64 {
+    60
+}
+
+fn default_meeting_max_speakers() -> u32 {
+    6
+}
+
+fn default_vad_threshold() -> f32 {
+    0.3
+}
+
+fn default_vad_energy_threshold_db() -> f32 {
+    -45.0
+}
+
+fn default_vad_prefill_frames() -> u32 {
+    15
+}
+
+fn default_vad_hangover_frames() -> u32 {
+    15
+}
+
+fn default_vad_onset_frames() -> u32 {
+    2
+}
+
+fn default_capture_gain() -> f32 {
+    1.0
+}
+
+fn default_pre_roll_ms() -> u32 {
+    400
+}
+
+fn default_duck_amount() -> f32 {
+    1.0
+}
+
+fn default_post_process_breaker_threshold() -> u32 {
+    3
+}
+
+fn default_post_process_breaker_cooldown_secs() -> u64 {
+    300
+}
+
 fn default_post_process_prompts() -> Vec<LLMPrompt> {
     vec![LLMPrompt {
         id: "default_improve_transcriptions".to_string(),
         name: "Improve Transcriptions".to_string(),
         prompt: "Clean this transcript:\n1. Fix spelling, capitalization, and punctuation errors\n2. Convert number words to digits (twenty-five → 25, ten percent → 10%, five dollars → $5)\n3. Replace spoken punctuation with symbols (period → ., comma → ,, question mark → ?)\n4. Remove filler words (um, uh, like as filler)\n5. Keep the language in the original version (if it was french, keep it in french for example)\n\nPreserve exact meaning and word order. Do not paraphrase or reorder content.\n\nReturn only the cleaned transcript.\n\nTranscript:\n${output}".to_string(),
+        system_prompt: String::new(),
+        temperature: None,
+        max_tokens: None,
     }]
 }
 
 fn ensure_post_process_defaults(settings: &mut AppSettings) -> bool {
     let mut changed = false;
     for provider in default_post_process_providers() {
-        if settings
+        match settings
             .post_process_providers
-            .iter()
-            .all(|existing| existing.id != provider.id)
+            .iter_mut()
+            .find(|existing| existing.id == provider.id)
         {
-            settings.post_process_providers.push(provider.clone());
-            changed = true;
+            // Built-in providers saved before dialects existed default to OpenAI
+            Some(existing) => {
+                if !existing.allow_base_url_edit && existing.dialect != provider.dialect {
+                    existing.dialect = provider.dialect;
+                    changed = true;
+                }
+            }
+            None => {
+                settings.post_process_providers.push(provider.clone());
+                changed = true;
+            }
         }
 
         if !settings.post_process_api_keys.contains_key(&provider.id) {
@@ -575,6 +1017,30 @@ pub fn get_default_settings() -> AppSettings {
             current_binding: default_shortcut.to_string(),
         },
     );
+    // Hands-free and meeting start unbound so they don't claim key
+    // combinations other apps already use; users pick their own
+    bindings.insert(
+        "hands_free".to_string(),
+        ShortcutBinding {
+            id: "hands_free".to_string(),
+            name: "Hands-free Dictation".to_string(),
+            description: "Listens continuously and types each sentence as you finish it."
+                .to_string(),
+            default_binding: String::new(),
+            current_binding: String::new(),
+        },
+    );
+    bindings.insert(
+        "meeting".to_string(),
+        ShortcutBinding {
+            id: "meeting".to_string(),
+            name: "Meeting Recording".to_string(),
+            description: "Records a whole meeting and saves a timestamped transcript to history."
+                .to_string(),
+            default_binding: String::new(),
+            current_binding: String::new(),
+        },
+    );
     bindings.insert(
         "cancel".to_string(),
         ShortcutBinding {
@@ -599,13 +1065,24 @@ pub fn get_default_settings() -> AppSettings {
         always_on_microphone: false,
         selected_microphone: None,
         clamshell_microphone: None,
+        microphone_priority: Vec::new(),
         selected_output_device: None,
+        capture_source: CaptureSource::default(),
+        system_audio_device: None,
+        microphone_gain: default_capture_gain(),
+        system_audio_gain: default_capture_gain(),
         translate_to_english: false,
         selected_language: "auto".to_string(),
         overlay_position: default_overlay_position(),
         debug_mode: false,
         log_level: default_log_level(),
         custom_words: Vec::new(),
+        vocabulary: Vec::new(),
+        vocabulary_regex_rules: Vec::new(),
+        whisper_initial_prompt_enabled: false,
+        whisper_prompt_include_previous: false,
+        text_normalization_enabled: false,
+        text_normalization: NormalizationOptions::default(),
         model_unload_timeout: ModelUnloadTimeout::Never,
         word_correction_threshold: default_word_correction_threshold(),
         history_limit: default_history_limit(),
@@ -619,7 +1096,29 @@ pub fn get_default_settings() -> AppSettings {
         post_process_models: default_post_process_models(),
         post_process_prompts: default_post_process_prompts(),
         post_process_selected_prompt_id: None,
+        post_process_pipeline: Vec::new(),
+        post_process_streaming: false,
+        post_process_timeout_secs: default_post_process_timeout_secs(),
+        post_process_provider_timeouts: HashMap::new(),
+        post_process_fallbacks: Vec::new(),
+        post_process_breaker_threshold: default_post_process_breaker_threshold(),
+        post_process_breaker_cooldown_secs: default_post_process_breaker_cooldown_secs(),
+        hands_free_silence_timeout_secs: default_hands_free_silence_timeout_secs(),
+        meeting_speaker_detection: false,
+        meeting_max_speakers: default_meeting_max_speakers(),
+        vad_backend: VadBackend::default(),
+        vad_threshold: default_vad_threshold(),
+        vad_energy_threshold_db: default_vad_energy_threshold_db(),
+        vad_prefill_frames: default_vad_prefill_frames(),
+        vad_hangover_frames: default_vad_hangover_frames(),
+        vad_onset_frames: default_vad_onset_frames(),
+        noise_suppression: false,
+        high_pass_filter: false,
+        auto_gain_control: false,
+        pre_roll_ms: default_pre_roll_ms(),
         mute_while_recording: false,
+        duck_amount: default_duck_amount(),
+        duck_apps: Vec::new(),
         append_trailing_space: false,
         app_language: default_app_language(),
         gemini_enabled: default_gemini_enabled(),
@@ -627,6 +1126,13 @@ pub fn get_default_settings() -> AppSettings {
         gemini_api_key: default_gemini_api_key(),
         gemini_send_audio: default_gemini_send_audio(),
         screenshot_mode: default_screenshot_mode(),
+        typing_wpm: default_typing_wpm(),
+        recording_format: AudioCodec::default(),
+        dictation_profiles: Vec::new(),
+        active_profile_id: None,
+        app_rules: Vec::new(),
+        voice_commands_enabled: default_voice_commands_enabled(),
+        custom_voice_commands: Vec::new(),
     }
 }
 
@@ -643,6 +1149,52 @@ impl AppSettings {
             .find(|provider| provider.id == provider_id)
     }
 
+    /// How long a request to `provider_id` may take.
+    pub fn post_process_timeout(&self, provider_id: &str) -> Duration {
+        let secs = self
+            .post_process_provider_timeouts
+            .get(provider_id)
+            .copied()
+            .unwrap_or(self.post_process_timeout_secs);
+        Duration::from_secs(secs.max(1))
+    }
+
+    pub fn dictation_profile(&self, profile_id: &str) -> Option<&DictationProfile> {
+        self.dictation_profiles
+            .iter()
+            .find(|profile| profile.id == profile_id)
+    }
+
+    pub fn active_dictation_profile(&self) -> Option<&DictationProfile> {
+        self.active_profile_id
+            .as_deref()
+            .and_then(|id| self.dictation_profile(id))
+    }
+
+    /// The configured pipeline, or the legacy behaviour when none is set up:
+    /// OpenCC conversion for Chinese variants, otherwise the selected prompt.
+    pub fn effective_post_process_pipeline(&self) -> Vec<PostProcessStep> {
+        if !self.post_process_pipeline.is_empty() {
+            return self.post_process_pipeline.clone();
+        }
+        if matches!(self.selected_language.as_str(), "zh-Hans" | "zh-Hant") {
+            vec![PostProcessStep::new(
+                "chinese_conversion",
+                PostProcessStepKind::ChineseConversion,
+            )]
+        } else {
+            vec![PostProcessStep::new(
+                "selected_prompt",
+                PostProcessStepKind::LlmPrompt,
+            )]
+        }
+    }
+
+    /// First app rule matching the given application, in user-defined order.
+    pub fn app_rule_for(&self, app: &str) -> Option<&AppRule> {
+        self.app_rules.iter().find(|rule| rule.matches(app))
+    }
+
     pub fn post_process_provider_mut(
         &mut self,
         provider_id: &str,
@@ -728,6 +1280,17 @@ pub fn get_settings(app: &AppHandle) -> AppSettings {
     settings
 }
 
+/// The settings a dictation runs with: the stored settings with the active
+/// profile's overrides on top. Read-only; use `get_settings` to change
+/// anything, or the profile would be baked into the globals.
+pub fn get_dictation_settings(app: &AppHandle) -> AppSettings {
+    let mut settings = get_settings(app);
+    if let Some(profile) = settings.active_dictation_profile().cloned() {
+        profile.apply_to(&mut settings);
+    }
+    settings
+}
+
 pub fn write_settings(app: &AppHandle, settings: AppSettings) {
     let store = app
         .store(SETTINGS_STORE_PATH)
@@ -759,3 +1322,48 @@ pub fn get_recording_retention_period(app: &AppHandle) -> RecordingRetentionPeri
     let settings = get_settings(app);
     settings.recording_retention_period
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_language_filter() {
+        let mut step = PostProcessStep::new("step", PostProcessStepKind::Normalization);
+        assert!(step.applies_to("fr"));
+
+        step.languages = vec!["de".to_string(), "zh-Hant".to_string()];
+        assert!(step.applies_to("de"));
+        assert!(step.applies_to("de-AT"));
+        assert!(step.applies_to("zh-hant"));
+        assert!(!step.applies_to("zh-Hans"));
+        assert!(!step.applies_to("auto"));
+    }
+
+    #[test]
+    fn test_legacy_pipeline() {
+        let mut settings = get_default_settings();
+        let kinds = |s: &AppSettings| -> Vec<PostProcessStepKind> {
+            s.effective_post_process_pipeline()
+                .iter()
+                .map(|step| step.kind)
+                .collect()
+        };
+        assert_eq!(kinds(&settings), vec![PostProcessStepKind::LlmPrompt]);
+
+        settings.selected_language = "zh-Hans".to_string();
+        assert_eq!(kinds(&settings), vec![PostProcessStepKind::ChineseConversion]);
+
+        settings.post_process_pipeline = vec![
+            PostProcessStep::new("a", PostProcessStepKind::Normalization),
+            PostProcessStep::new("b", PostProcessStepKind::Translate),
+        ];
+        assert_eq!(
+            kinds(&settings),
+            vec![
+                PostProcessStepKind::Normalization,
+                PostProcessStepKind::Translate
+            ]
+        );
+    }
+}
@@ -4,14 +4,21 @@ use specta::Type;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-use crate::actions::ACTION_MAP;
+use crate::actions::{self, action_for_binding};
+use crate::audio_toolkit::{
+    AudioCodec, NormalizationOptions, RegexRule, VocabularyEntry, VoiceCommandPhrase,
+};
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::model::{EngineType, ModelManager};
+use crate::post_process::{self, template::PromptTemplate};
 use crate::settings::ShortcutBinding;
 use crate::settings::{
-    self, get_settings, ClipboardHandling, LLMPrompt, OverlayPosition, PasteMethod, SoundTheme,
-    APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID,
+    self, get_settings, ClipboardHandling, LLMPrompt, OverlayPosition, PasteMethod,
+    PostProcessFallback, SoundTheme, VadBackend,
+    APPLE_INTELLIGENCE_DEFAULT_MODEL_ID, APPLE_INTELLIGENCE_PROVIDER_ID, LOCAL_LLM_PROVIDER_ID,
 };
 use crate::tray;
 use crate::ManagedToggleState;
@@ -35,6 +42,16 @@ pub fn init_shortcuts(app: &AppHandle) {
             error!("Failed to register shortcut {} during init: {}", id, e);
         }
     }
+
+    // Profile shortcuts only exist in the user's settings
+    for (id, binding) in user_settings.bindings {
+        if !id.starts_with(settings::PROFILE_ID_PREFIX) || binding.current_binding.is_empty() {
+            continue;
+        }
+        if let Err(e) = register_shortcut(app, binding) {
+            error!("Failed to register profile shortcut {} during init: {}", id, e);
+        }
+    }
 }
 
 #[derive(Serialize, Type)]
@@ -87,10 +104,11 @@ pub fn change_binding(
         error!("change_binding error: {}", error_msg);
     }
 
-    // Validate the new shortcut before we touch the current registration
-    if let Err(e) = validate_shortcut_string(&binding) {
-        warn!("change_binding validation error: {}", e);
-        return Err(e);
+    // Validate the new shortcut before we touch the current registration;
+    // an empty string unbinds it
+    if !binding.is_empty() {
+        validate_shortcut_string(&binding)
+            .inspect_err(|e| warn!("change_binding validation error: {}", e))?;
     }
 
     // Create an updated binding
@@ -130,6 +148,77 @@ pub fn reset_binding(app: AppHandle, id: String) -> Result<BindingResponse, Stri
     return change_binding(app, id, binding.default_binding);
 }
 
+/// Bind (or with an empty string, unbind) a shortcut that dictates with the given profile.
+#[tauri::command]
+#[specta::specta]
+pub fn set_dictation_profile_shortcut(
+    app: AppHandle,
+    profile_id: String,
+    binding: String,
+) -> Result<BindingResponse, String> {
+    let mut settings = settings::get_settings(&app);
+
+    let profile_name = settings
+        .dictation_profile(&profile_id)
+        .map(|p| p.name.clone())
+        .ok_or_else(|| format!("Profile with id '{}' not found", profile_id))?;
+
+    if !binding.is_empty() {
+        validate_shortcut_string(&binding)?;
+    }
+
+    let mut profile_binding = settings
+        .bindings
+        .get(&profile_id)
+        .cloned()
+        .unwrap_or_else(|| ShortcutBinding {
+            id: profile_id.clone(),
+            name: profile_name.clone(),
+            description: format!("Dictate using the {} profile.", profile_name),
+            default_binding: String::new(),
+            current_binding: String::new(),
+        });
+
+    if !profile_binding.current_binding.is_empty() {
+        if let Err(e) = unregister_shortcut(&app, profile_binding.clone()) {
+            warn!("Failed to unregister profile shortcut: {}", e);
+        }
+    }
+
+    profile_binding.current_binding = binding;
+
+    if profile_binding.current_binding.is_empty() {
+        settings.bindings.remove(&profile_id);
+        settings::write_settings(&app, settings);
+        return Ok(BindingResponse {
+            success: true,
+            binding: None,
+            error: None,
+        });
+    }
+
+    if let Err(e) = register_shortcut(&app, profile_binding.clone()) {
+        let error_msg = format!("Failed to register shortcut: {}", e);
+        error!("set_dictation_profile_shortcut error: {}", error_msg);
+        return Ok(BindingResponse {
+            success: false,
+            binding: None,
+            error: Some(error_msg),
+        });
+    }
+
+    settings
+        .bindings
+        .insert(profile_id, profile_binding.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(BindingResponse {
+        success: true,
+        binding: Some(profile_binding),
+        error: None,
+    })
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_ptt_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -312,6 +401,112 @@ pub fn update_custom_words(app: AppHandle, words: Vec<String>) -> Result<(), Str
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn update_vocabulary(app: AppHandle, entries: Vec<VocabularyEntry>) -> Result<(), String> {
+    if entries.iter().any(|e| e.written.trim().is_empty()) {
+        return Err("Vocabulary entries need a written form".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.vocabulary = entries;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_vocabulary_regex_rules(app: AppHandle, rules: Vec<RegexRule>) -> Result<(), String> {
+    for rule in &rules {
+        rule.compile()
+            .map_err(|e| format!("Invalid pattern '{}': {}", rule.pattern, e))?;
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.vocabulary_regex_rules = rules;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_app_rules(app: AppHandle, rules: Vec<settings::AppRule>) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.app_rules = rules;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_whisper_initial_prompt_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.whisper_initial_prompt_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_whisper_prompt_include_previous_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.whisper_prompt_include_previous = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_text_normalization_enabled_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.text_normalization_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_text_normalization_options(
+    app: AppHandle,
+    options: NormalizationOptions,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.text_normalization = options;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_voice_commands_enabled_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.voice_commands_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_custom_voice_commands(
+    app: AppHandle,
+    commands: Vec<VoiceCommandPhrase>,
+) -> Result<(), String> {
+    if commands.iter().any(|c| c.phrase.trim().is_empty()) {
+        return Err("Voice command phrase cannot be empty".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.custom_voice_commands = commands;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_word_correction_threshold_setting(
@@ -324,6 +519,18 @@ pub fn change_word_correction_threshold_setting(
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_typing_wpm_setting(app: AppHandle, wpm: u32) -> Result<(), String> {
+    if wpm == 0 {
+        return Err("Typing speed must be greater than zero".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.typing_wpm = wpm;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_paste_method_setting(app: AppHandle, method: String) -> Result<(), String> {
@@ -344,6 +551,24 @@ pub fn change_paste_method_setting(app: AppHandle, method: String) -> Result<(),
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_recording_format_setting(app: AppHandle, format: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let parsed = match format.as_str() {
+        "wav" => AudioCodec::Wav,
+        "flac" => AudioCodec::Flac,
+        "opus" => AudioCodec::Opus,
+        other => {
+            warn!("Invalid recording format '{}', defaulting to wav", other);
+            AudioCodec::Wav
+        }
+    };
+    settings.recording_format = parsed;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_clipboard_handling_setting(app: AppHandle, handling: String) -> Result<(), String> {
@@ -452,6 +677,32 @@ pub fn change_post_process_base_url_setting(
     Ok(())
 }
 
+/// Set the wire format of the custom provider, e.g. for a self-hosted
+/// Anthropic- or Gemini-compatible endpoint.
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_dialect_setting(
+    app: AppHandle,
+    provider_id: String,
+    dialect: settings::ProviderDialect,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let provider = settings
+        .post_process_provider_mut(&provider_id)
+        .ok_or_else(|| format!("Provider '{}' not found", provider_id))?;
+
+    if !provider.allow_base_url_edit {
+        return Err(format!(
+            "Provider '{}' does not allow changing its API format",
+            provider.label
+        ));
+    }
+
+    provider.dialect = dialect;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 /// Generic helper to validate provider exists
 fn validate_provider_exists(
     settings: &settings::AppSettings,
@@ -512,6 +763,7 @@ pub fn add_post_process_prompt(
     name: String,
     prompt: String,
 ) -> Result<LLMPrompt, String> {
+    PromptTemplate::parse(&prompt).map_err(|e| format!("Invalid prompt template: {}", e))?;
     let mut settings = settings::get_settings(&app);
 
     // Generate unique ID using timestamp and random component
@@ -521,6 +773,9 @@ pub fn add_post_process_prompt(
         id: id.clone(),
         name,
         prompt,
+        system_prompt: String::new(),
+        temperature: None,
+        max_tokens: None,
     };
 
     settings.post_process_prompts.push(new_prompt.clone());
@@ -537,6 +792,7 @@ pub fn update_post_process_prompt(
     name: String,
     prompt: String,
 ) -> Result<(), String> {
+    PromptTemplate::parse(&prompt).map_err(|e| format!("Invalid prompt template: {}", e))?;
     let mut settings = settings::get_settings(&app);
 
     if let Some(existing_prompt) = settings
@@ -553,6 +809,326 @@ pub fn update_post_process_prompt(
     }
 }
 
+/// Set the system message and sampling options of a prompt. `None` leaves
+/// temperature and max tokens to the provider's defaults.
+#[tauri::command]
+#[specta::specta]
+pub fn update_post_process_prompt_options(
+    app: AppHandle,
+    id: String,
+    system_prompt: String,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+) -> Result<(), String> {
+    PromptTemplate::parse(&system_prompt)
+        .map_err(|e| format!("Invalid system prompt template: {}", e))?;
+    if let Some(temperature) = temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err("Temperature must be between 0 and 2".to_string());
+        }
+    }
+    if max_tokens == Some(0) {
+        return Err("Max tokens must be greater than 0".to_string());
+    }
+
+    let mut settings = settings::get_settings(&app);
+    let existing_prompt = settings
+        .post_process_prompts
+        .iter_mut()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Prompt with id '{}' not found", id))?;
+    existing_prompt.system_prompt = system_prompt;
+    existing_prompt.temperature = temperature;
+    existing_prompt.max_tokens = max_tokens;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_streaming_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.post_process_streaming = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_timeout_setting(app: AppHandle, seconds: u64) -> Result<(), String> {
+    if seconds == 0 {
+        return Err("Timeout must be at least one second".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.post_process_timeout_secs = seconds;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_hands_free_silence_timeout_setting(
+    app: AppHandle,
+    seconds: u64,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.hands_free_silence_timeout_secs = seconds;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_meeting_speaker_detection_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.meeting_speaker_detection = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+// Most voices a meeting transcript tells apart
+const MAX_MEETING_SPEAKERS: u32 = 20;
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_meeting_max_speakers_setting(app: AppHandle, speakers: u32) -> Result<(), String> {
+    if !(1..=MAX_MEETING_SPEAKERS).contains(&speakers) {
+        return Err(format!(
+            "Speaker count must be between 1 and {}, got {}",
+            MAX_MEETING_SPEAKERS, speakers
+        ));
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.meeting_max_speakers = speakers;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_noise_suppression_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.noise_suppression = enabled;
+    settings::write_settings(&app, settings);
+    app.state::<Arc<AudioRecordingManager>>()
+        .update_audio_processing();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_high_pass_filter_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.high_pass_filter = enabled;
+    settings::write_settings(&app, settings);
+    app.state::<Arc<AudioRecordingManager>>()
+        .update_audio_processing();
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_auto_gain_control_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.auto_gain_control = enabled;
+    settings::write_settings(&app, settings);
+    app.state::<Arc<AudioRecordingManager>>()
+        .update_audio_processing();
+    Ok(())
+}
+
+// Longest pre-roll the recorder keeps ahead of a recording
+const MAX_PRE_ROLL_MS: u32 = 1000;
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_pre_roll_setting(app: AppHandle, milliseconds: u32) -> Result<(), String> {
+    if milliseconds > MAX_PRE_ROLL_MS {
+        return Err(format!(
+            "Pre-roll can be at most {} ms, got {}",
+            MAX_PRE_ROLL_MS, milliseconds
+        ));
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.pre_roll_ms = milliseconds;
+    settings::write_settings(&app, settings);
+    app.state::<Arc<AudioRecordingManager>>().update_pre_roll();
+    Ok(())
+}
+
+// Longest pre-roll or hangover the VAD settings accept, in 30 ms frames
+const MAX_VAD_SMOOTHING_FRAMES: u32 = 100;
+
+fn apply_vad_settings(app: &AppHandle) -> Result<(), String> {
+    app.state::<Arc<AudioRecordingManager>>()
+        .update_vad()
+        .map_err(|e| format!("Failed to update VAD: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_backend_setting(app: AppHandle, backend: VadBackend) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.vad_backend = backend;
+    settings::write_settings(&app, settings);
+    apply_vad_settings(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_threshold_setting(app: AppHandle, threshold: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(format!(
+            "VAD threshold must be between 0 and 1, got {}",
+            threshold
+        ));
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.vad_threshold = threshold;
+    settings::write_settings(&app, settings);
+    apply_vad_settings(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_energy_threshold_setting(
+    app: AppHandle,
+    threshold_db: f32,
+) -> Result<(), String> {
+    if !(-90.0..=0.0).contains(&threshold_db) {
+        return Err(format!(
+            "Energy threshold must be between -90 and 0 dBFS, got {}",
+            threshold_db
+        ));
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.vad_energy_threshold_db = threshold_db;
+    settings::write_settings(&app, settings);
+    apply_vad_settings(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_smoothing_setting(
+    app: AppHandle,
+    prefill_frames: u32,
+    hangover_frames: u32,
+    onset_frames: u32,
+) -> Result<(), String> {
+    if prefill_frames > MAX_VAD_SMOOTHING_FRAMES || hangover_frames > MAX_VAD_SMOOTHING_FRAMES {
+        return Err(format!(
+            "Prefill and hangover can be at most {} frames",
+            MAX_VAD_SMOOTHING_FRAMES
+        ));
+    }
+    if !(1..=10).contains(&onset_frames) {
+        return Err(format!(
+            "Onset must be between 1 and 10 frames, got {}",
+            onset_frames
+        ));
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.vad_prefill_frames = prefill_frames;
+    settings.vad_hangover_frames = hangover_frames;
+    settings.vad_onset_frames = onset_frames;
+    settings::write_settings(&app, settings);
+    apply_vad_settings(&app)
+}
+
+/// Override the request timeout for one provider; `None` goes back to the
+/// global post-processing timeout.
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_provider_timeout_setting(
+    app: AppHandle,
+    provider_id: String,
+    seconds: Option<u64>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    if settings.post_process_provider(&provider_id).is_none() {
+        return Err(format!("Provider '{}' not found", provider_id));
+    }
+    match seconds {
+        Some(0) => return Err("Timeout must be at least one second".to_string()),
+        Some(seconds) => {
+            settings
+                .post_process_provider_timeouts
+                .insert(provider_id, seconds);
+        }
+        None => {
+            settings.post_process_provider_timeouts.remove(&provider_id);
+        }
+    }
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_post_process_fallbacks(
+    app: AppHandle,
+    fallbacks: Vec<PostProcessFallback>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    for (index, fallback) in fallbacks.iter().enumerate() {
+        if settings.post_process_provider(&fallback.provider_id).is_none() {
+            return Err(format!("Provider '{}' not found", fallback.provider_id));
+        }
+        if fallbacks[..index].contains(fallback) {
+            return Err(format!(
+                "Fallback '{}' is listed more than once",
+                fallback.provider_id
+            ));
+        }
+    }
+    settings.post_process_fallbacks = fallbacks;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Set how many consecutive failures make post-processing skip a provider,
+/// and for how long. A threshold of 0 never skips.
+#[tauri::command]
+#[specta::specta]
+pub fn change_post_process_breaker_setting(
+    app: AppHandle,
+    threshold: u32,
+    cooldown_secs: u64,
+) -> Result<(), String> {
+    if threshold > 0 && cooldown_secs == 0 {
+        return Err("Cooldown must be at least one second".to_string());
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.post_process_breaker_threshold = threshold;
+    settings.post_process_breaker_cooldown_secs = cooldown_secs;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+/// Render a prompt template against `sample_text` as if it had just been
+/// dictated. The focused application and the selection are left empty.
+#[tauri::command]
+#[specta::specta]
+pub fn preview_post_process_prompt(
+    app: AppHandle,
+    prompt: String,
+    sample_text: String,
+) -> Result<String, String> {
+    let template =
+        PromptTemplate::parse(&prompt).map_err(|e| format!("Invalid prompt template: {}", e))?;
+    let settings = settings::get_settings(&app);
+    let mut context =
+        post_process::prompt_context(&settings, &sample_text, None, actions::last_dictation());
+    context.output = sample_text;
+    if template.uses("clipboard") {
+        context.clipboard = app.clipboard().read_text().ok();
+    }
+    Ok(template.render(&context))
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn delete_post_process_prompt(app: AppHandle, id: String) -> Result<(), String> {
@@ -608,6 +1184,24 @@ pub async fn fetch_post_process_models(
         }
     }
 
+    // The local provider offers the language models that are downloaded
+    if provider.id == LOCAL_LLM_PROVIDER_ID {
+        let model_manager = app.state::<Arc<ModelManager>>();
+        let models: Vec<String> = model_manager
+            .get_available_models()
+            .into_iter()
+            .filter(|m| matches!(m.engine_type, EngineType::Llm) && m.is_downloaded)
+            .map(|m| m.id)
+            .collect();
+        if models.is_empty() {
+            return Err(
+                "No local language model is downloaded. Download one under Local Models first."
+                    .to_string(),
+            );
+        }
+        return Ok(models);
+    }
+
     // Get API key
     let api_key = settings
         .post_process_api_keys
@@ -641,6 +1235,56 @@ pub fn set_post_process_selected_prompt(app: AppHandle, id: String) -> Result<()
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn update_post_process_pipeline(
+    app: AppHandle,
+    steps: Vec<settings::PostProcessStep>,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    for (i, step) in steps.iter().enumerate() {
+        if step.id.trim().is_empty() {
+            return Err("Pipeline steps need an id".to_string());
+        }
+        if steps[..i].iter().any(|other| other.id == step.id) {
+            return Err(format!("Duplicate pipeline step id '{}'", step.id));
+        }
+        match step.kind {
+            settings::PostProcessStepKind::LlmPrompt => {
+                if let Some(prompt_id) = &step.prompt_id {
+                    if !settings.post_process_prompts.iter().any(|p| &p.id == prompt_id) {
+                        return Err(format!("Prompt with id '{}' not found", prompt_id));
+                    }
+                }
+            }
+            settings::PostProcessStepKind::RegexReplace => {
+                let rule = step
+                    .regex_rule
+                    .as_ref()
+                    .ok_or_else(|| format!("Step '{}' has no regex rule", step.id))?;
+                rule.compile()
+                    .map_err(|e| format!("Invalid pattern '{}': {}", rule.pattern, e))?;
+            }
+            settings::PostProcessStepKind::Translate => {
+                if step
+                    .target_language
+                    .as_deref()
+                    .is_none_or(|t| t.trim().is_empty())
+                {
+                    return Err(format!("Step '{}' has no target language", step.id));
+                }
+            }
+            settings::PostProcessStepKind::ChineseConversion
+            | settings::PostProcessStepKind::Normalization => {}
+        }
+    }
+
+    settings.post_process_pipeline = steps;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_mute_while_recording_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -651,6 +1295,34 @@ pub fn change_mute_while_recording_setting(app: AppHandle, enabled: bool) -> Res
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_duck_amount_setting(app: AppHandle, amount: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&amount) {
+        return Err(format!(
+            "Duck amount must be between 0.0 and 1.0, got {}",
+            amount
+        ));
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.duck_amount = amount;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_duck_apps_setting(app: AppHandle, apps: Vec<String>) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.duck_apps = apps
+        .into_iter()
+        .map(|app| app.trim().to_string())
+        .filter(|app| !app.is_empty())
+        .collect();
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_append_trailing_space_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -790,6 +1462,11 @@ pub fn unregister_cancel_shortcut(app: &AppHandle) {
 }
 
 pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    // An empty binding is unbound, so there is nothing to register
+    if binding.current_binding.is_empty() {
+        return Ok(());
+    }
+
     // Helper to normalize shortcut string for platform compatibility
     // On macOS, "ctrl" should be "control" for tauri-plugin-global-shortcut
     let normalize = |s: &str| -> String {
@@ -915,15 +1592,20 @@ fn handle_shortcut_event(
     has_ctrl: bool,
 ) {
     let settings = get_settings(app);
+    // Hands-free sessions and meetings outlast a key press, so they always toggle
+    let push_to_talk =
+        settings.push_to_talk && binding_id != "hands_free" && binding_id != "meeting";
 
-    if let Some(action) = ACTION_MAP.get(binding_id) {
+    if let Some(action) = action_for_binding(binding_id) {
         if binding_id == "cancel" {
             let audio_manager = app.state::<Arc<AudioRecordingManager>>();
-            if audio_manager.is_recording() && event.state == ShortcutState::Pressed {
+            if (audio_manager.is_recording() || audio_manager.is_listening())
+                && event.state == ShortcutState::Pressed
+            {
                 action.start(app, binding_id, shortcut_string);
             }
             return;
-        } else if settings.push_to_talk {
+        } else if push_to_talk {
             if event.state == ShortcutState::Pressed {
                 // Pass has_ctrl flag via shortcut string - we'll append a marker
                 let shortcut_with_flag = if has_ctrl {
@@ -973,13 +1655,17 @@ fn handle_shortcut_event(
         }
     } else {
         warn!(
-            "No action defined in ACTION_MAP for shortcut ID '{}'. Shortcut: '{}', State: {:?}",
+            "No action defined for shortcut ID '{}'. Shortcut: '{}', State: {:?}",
             binding_id, shortcut_string, event.state
         );
     }
 }
 
 pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<(), String> {
+    if binding.current_binding.is_empty() {
+        return Ok(());
+    }
+
     // Helper to normalize shortcut string for platform compatibility
     // On macOS, "ctrl" should be "control" for tauri-plugin-global-shortcut
     let normalize = |s: &str| -> String {
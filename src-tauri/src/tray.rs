@@ -1,10 +1,14 @@
 use crate::settings;
 use crate::tray_i18n::get_tray_translations;
 use tauri::image::Image;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::TrayIcon;
 use tauri::{AppHandle, Manager, Theme};
 
+/// Menu id of the profile picker entry that goes back to the global settings.
+/// Profile ids all start with `profile_`, so it cannot clash with one.
+pub const NO_PROFILE_MENU_ID: &str = "no_profile";
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum TrayIconState {
     Idle,
@@ -148,6 +152,51 @@ pub fn update_tray_menu(app: &AppHandle, state: &TrayIconState, locale: Option<&
         .expect("failed to create menu"),
     };
 
+    // Profile picker, only shown once the user has created profiles.
+    // Menu ids are the profile ids themselves, see the menu handler in lib.rs.
+    if !settings.dictation_profiles.is_empty() {
+        let active = settings.active_dictation_profile();
+        let active_name = active
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| strings.no_profile.clone());
+        let no_profile_i = CheckMenuItem::with_id(
+            app,
+            NO_PROFILE_MENU_ID,
+            &strings.no_profile,
+            true,
+            active.is_none(),
+            None::<&str>,
+        )
+        .expect("failed to create no-profile item");
+        let profile_items: Vec<CheckMenuItem<_>> = std::iter::once(no_profile_i)
+            .chain(settings.dictation_profiles.iter().map(|profile| {
+                CheckMenuItem::with_id(
+                    app,
+                    &profile.id,
+                    &profile.name,
+                    true,
+                    settings.active_profile_id.as_deref() == Some(profile.id.as_str()),
+                    None::<&str>,
+                )
+                .expect("failed to create profile item")
+            }))
+            .collect();
+        let profile_refs: Vec<&dyn IsMenuItem<_>> = profile_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem<_>)
+            .collect();
+        let profiles_i = Submenu::with_id_and_items(
+            app,
+            "profiles",
+            format!("{}: {}", strings.profile, active_name),
+            *state == TrayIconState::Idle,
+            &profile_refs,
+        )
+        .expect("failed to create profiles submenu");
+        let _ = menu.insert(&profiles_i, 2);
+        let _ = menu.insert(&separator(), 3);
+    }
+
     let tray = app.state::<TrayIcon>();
     let _ = tray.set_menu(Some(menu));
     let _ = tray.set_icon_as_template(true);
@@ -109,6 +109,14 @@ async changeWordCorrectionThresholdSetting(threshold: number) : Promise<Result<n
     else return { status: "error", error: e  as any };
 }
 },
+async changeTypingWpmSetting(wpm: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_typing_wpm_setting", { wpm }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePasteMethodSetting(method: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_paste_method_setting", { method }) };
@@ -117,6 +125,14 @@ async changePasteMethodSetting(method: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async changeRecordingFormatSetting(format: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_recording_format_setting", { format }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeClipboardHandlingSetting(handling: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_clipboard_handling_setting", { handling }) };
@@ -141,6 +157,18 @@ async changePostProcessBaseUrlSetting(providerId: string, baseUrl: string) : Pro
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Set the wire format of the custom provider, e.g. for a self-hosted
+ * Anthropic- or Gemini-compatible endpoint.
+ */
+async changePostProcessDialectSetting(providerId: string, dialect: ProviderDialect) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_dialect_setting", { providerId, dialect }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changePostProcessApiKeySetting(providerId: string, apiKey: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_post_process_api_key_setting", { providerId, apiKey }) };
@@ -189,6 +217,154 @@ async updatePostProcessPrompt(id: string, name: string, prompt: string) : Promis
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Set the system message and sampling options of a prompt. `None` leaves
+ * temperature and max tokens to the provider's defaults.
+ */
+async updatePostProcessPromptOptions(id: string, systemPrompt: string, temperature: number | null, maxTokens: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_post_process_prompt_options", { id, systemPrompt, temperature, maxTokens }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePostProcessStreamingSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_streaming_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePostProcessTimeoutSetting(seconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_timeout_setting", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Override the request timeout for one provider; `None` goes back to the
+ * global post-processing timeout.
+ */
+async changePostProcessProviderTimeoutSetting(providerId: string, seconds: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_provider_timeout_setting", { providerId, seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updatePostProcessFallbacks(fallbacks: PostProcessFallback[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_post_process_fallbacks", { fallbacks }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set how many consecutive failures make post-processing skip a provider,
+ * and for how long. A threshold of 0 never skips.
+ */
+async changePostProcessBreakerSetting(threshold: number, cooldownSecs: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_post_process_breaker_setting", { threshold, cooldownSecs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeHandsFreeSilenceTimeoutSetting(seconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_hands_free_silence_timeout_setting", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeMeetingSpeakerDetectionSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_meeting_speaker_detection_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeMeetingMaxSpeakersSetting(speakers: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_meeting_max_speakers_setting", { speakers }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeVadBackendSetting(backend: VadBackend) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_vad_backend_setting", { backend }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeVadThresholdSetting(threshold: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_vad_threshold_setting", { threshold }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeVadEnergyThresholdSetting(thresholdDb: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_vad_energy_threshold_setting", { thresholdDb }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeVadSmoothingSetting(prefillFrames: number, hangoverFrames: number, onsetFrames: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_vad_smoothing_setting", { prefillFrames, hangoverFrames, onsetFrames }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeNoiseSuppressionSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_noise_suppression_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeHighPassFilterSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_high_pass_filter_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeAutoGainControlSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_auto_gain_control_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changePreRollSetting(milliseconds: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_pre_roll_setting", { milliseconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async deletePostProcessPrompt(id: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("delete_post_process_prompt", { id }) };
@@ -205,6 +381,26 @@ async setPostProcessSelectedPrompt(id: string) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Render a prompt template against `sample_text` as if it had just been
+ * dictated. The focused application and the selection are left empty.
+ */
+async previewPostProcessPrompt(prompt: string, sampleText: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("preview_post_process_prompt", { prompt, sampleText }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updatePostProcessPipeline(steps: PostProcessStep[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_post_process_pipeline", { steps }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async updateCustomWords(words: string[]) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_custom_words", { words }) };
@@ -213,6 +409,78 @@ async updateCustomWords(words: string[]) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async updateVocabulary(entries: VocabularyEntry[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_vocabulary", { entries }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateVocabularyRegexRules(rules: RegexRule[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_vocabulary_regex_rules", { rules }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateAppRules(rules: AppRule[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_app_rules", { rules }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeWhisperInitialPromptSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_whisper_initial_prompt_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeWhisperPromptIncludePreviousSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_whisper_prompt_include_previous_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeTextNormalizationEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_text_normalization_enabled_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateTextNormalizationOptions(options: NormalizationOptions) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_text_normalization_options", { options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeVoiceCommandsEnabledSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_voice_commands_enabled_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateCustomVoiceCommands(commands: VoiceCommandPhrase[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_custom_voice_commands", { commands }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Temporarily unregister a binding while the user is editing it in the UI.
  * This avoids firing the action while keys are being recorded.
@@ -244,6 +512,22 @@ async changeMuteWhileRecordingSetting(enabled: boolean) : Promise<Result<null, s
     else return { status: "error", error: e  as any };
 }
 },
+async changeDuckAmountSetting(amount: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_duck_amount_setting", { amount }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async changeDuckAppsSetting(apps: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_duck_apps_setting", { apps }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeAppendTrailingSpaceSetting(enabled: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_append_trailing_space_setting", { enabled }) };
@@ -585,6 +869,22 @@ async setSelectedMicrophone(deviceName: string) : Promise<Result<null, string>>
     else return { status: "error", error: e  as any };
 }
 },
+async setMicrophonePriority(devices: string[]) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_microphone_priority", { devices }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async calibrateMicrophone(seconds: number) : Promise<Result<CalibrationReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("calibrate_microphone", { seconds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async getSelectedMicrophone() : Promise<Result<string, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_selected_microphone") };
@@ -642,6 +942,38 @@ async getClamshellMicrophone() : Promise<Result<string, string>> {
 async isRecording() : Promise<boolean> {
     return await TAURI_INVOKE("is_recording");
 },
+async getSystemAudioDevices() : Promise<Result<AudioDevice[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_system_audio_devices") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setSystemAudioDevice(deviceName: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_system_audio_device", { deviceName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setCaptureSource(source: CaptureSource) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_capture_source", { source }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setCaptureGains(microphoneGain: number, systemAudioGain: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_capture_gains", { microphoneGain, systemAudioGain }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async setModelUnloadTimeout(timeout: ModelUnloadTimeout) : Promise<void> {
     await TAURI_INVOKE("set_model_unload_timeout", { timeout });
 },
@@ -693,6 +1025,87 @@ async deleteHistoryEntry(id: number) : Promise<Result<null, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async getUsageStatistics() : Promise<Result<UsageStatistics, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_usage_statistics") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDictationProfiles() : Promise<Result<DictationProfile[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_dictation_profiles") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create a profile from the current dictation settings.
+ */
+async addDictationProfile(name: string) : Promise<Result<DictationProfile, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_dictation_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async updateDictationProfile(profile: DictationProfile) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_dictation_profile", { profile }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteDictationProfile(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_dictation_profile", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Activate a profile, or pass `None` to dictate with the global settings.
+ */
+async setActiveDictationProfile(id: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_active_dictation_profile", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Bind (or with an empty string, unbind) a shortcut that dictates with the given profile.
+ */
+async setDictationProfileShortcut(profileId: string, binding: string) : Promise<Result<BindingResponse, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_dictation_profile_shortcut", { profileId, binding }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async retranscribeHistoryEntry(id: number) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("retranscribe_history_entry", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async convertRecordingsFormat() : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("convert_recordings_format") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async updateHistoryLimit(limit: number) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_history_limit", { limit }) };
@@ -735,26 +1148,308 @@ async isLaptop() : Promise<Result<boolean, string>> {
 
 /** user-defined types **/
 
-export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; selected_output_device?: string | null; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; mute_while_recording?: boolean; append_trailing_space?: boolean; app_language?: string; gemini_enabled?: boolean; gemini_model?: string; gemini_api_key?: string; gemini_send_audio?: boolean; screenshot_mode?: ScreenshotMode }
+/**
+ * Output overrides applied when dictating into a matching application.
+ * `app_match` is compared case-insensitively against the focused app's
+ * identifier (WM_CLASS, process name or executable name).
+ */
+export type AppRule = { app_match: string; paste_method?: PasteMethod | null; append_trailing_space?: boolean | null; post_process_enabled?: boolean | null; post_process_selected_prompt_id?: string | null }
+export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding }>; push_to_talk: boolean; audio_feedback: boolean; audio_feedback_volume?: number; sound_theme?: SoundTheme; start_hidden?: boolean; autostart_enabled?: boolean; update_checks_enabled?: boolean; selected_model?: string; always_on_microphone?: boolean; selected_microphone?: string | null; clamshell_microphone?: string | null; 
+/**
+ * Microphones to fall back to, in order, when the selected one is
+ * unplugged; the default input comes last
+ */
+microphone_priority?: string[]; selected_output_device?: string | null; 
+/**
+ * Record the microphone, what the system plays, or both mixed
+ */
+capture_source?: CaptureSource; 
+/**
+ * `None` picks the default loopback device
+ */
+system_audio_device?: string | null; microphone_gain?: number; system_audio_gain?: number; translate_to_english?: boolean; selected_language?: string; overlay_position?: OverlayPosition; debug_mode?: boolean; log_level?: LogLevel; custom_words?: string[]; vocabulary?: VocabularyEntry[]; vocabulary_regex_rules?: RegexRule[]; whisper_initial_prompt_enabled?: boolean; whisper_prompt_include_previous?: boolean; text_normalization_enabled?: boolean; text_normalization?: NormalizationOptions; model_unload_timeout?: ModelUnloadTimeout; word_correction_threshold?: number; history_limit?: number; recording_retention_period?: RecordingRetentionPeriod; paste_method?: PasteMethod; clipboard_handling?: ClipboardHandling; post_process_enabled?: boolean; post_process_provider_id?: string; post_process_providers?: PostProcessProvider[]; post_process_api_keys?: Partial<{ [key in string]: string }>; post_process_models?: Partial<{ [key in string]: string }>; post_process_prompts?: LLMPrompt[]; post_process_selected_prompt_id?: string | null; post_process_pipeline?: PostProcessStep[]; 
+/**
+ * Type LLM output as it streams in instead of pasting it at the end
+ */
+post_process_streaming?: boolean; post_process_timeout_secs?: number; 
+/**
+ * Per-provider override of `post_process_timeout_secs`
+ */
+post_process_provider_timeouts?: Partial<{ [key in string]: number }>; 
+/**
+ * Tried in order when the selected provider fails
+ */
+post_process_fallbacks?: PostProcessFallback[]; 
+/**
+ * Consecutive failures after which a provider is skipped for
+ * `post_process_breaker_cooldown_secs`; 0 never skips
+ */
+post_process_breaker_threshold?: number; post_process_breaker_cooldown_secs?: number; 
+/**
+ * Hands-free sessions end after this long without speech; 0 keeps
+ * listening until stopped
+ */
+hands_free_silence_timeout_secs?: number; 
+/**
+ * Label who is speaking in meeting transcripts; needs the speaker model
+ */
+meeting_speaker_detection?: boolean; 
+/**
+ * Most distinct voices a meeting transcript tells apart
+ */
+meeting_max_speakers?: number; 
+/**
+ * `Off` keeps every frame of a recording; hands-free mode needs a VAD
+ */
+vad_backend?: VadBackend; 
+/**
+ * Speech probability above which Silero counts a frame as voice
+ */
+vad_threshold?: number; 
+/**
+ * Level in dBFS a frame must exceed for the energy VAD
+ */
+vad_energy_threshold_db?: number; 
+/**
+ * Frames (30 ms each) kept from before speech starts
+ */
+vad_prefill_frames?: number; 
+/**
+ * Frames still kept after speech stops
+ */
+vad_hangover_frames?: number; 
+/**
+ * Consecutive voiced frames needed before speech starts
+ */
+vad_onset_frames?: number; 
+/**
+ * Audio clean-up ahead of the VAD
+ */
+noise_suppression?: boolean; high_pass_filter?: boolean; auto_gain_control?: boolean; 
+/**
+ * Audio from just before a recording starts that is kept with it;
+ * always-on microphone only, 0 turns it off
+ */
+pre_roll_ms?: number; mute_while_recording?: boolean; 
+/**
+ * Share of the output volume taken away while recording; 1.0 mutes
+ */
+duck_amount?: number; 
+/**
+ * Applications whose streams are ducked instead of the whole output
+ * (PipeWire/PulseAudio only); empty ducks everything
+ */
+duck_apps?: string[]; append_trailing_space?: boolean; app_language?: string; gemini_enabled?: boolean; gemini_model?: string; gemini_api_key?: string; gemini_send_audio?: boolean; screenshot_mode?: ScreenshotMode; typing_wpm?: number; recording_format?: AudioCodec; dictation_profiles?: DictationProfile[]; active_profile_id?: string | null; app_rules?: AppRule[]; voice_commands_enabled?: boolean; custom_voice_commands?: VoiceCommandPhrase[] }
+/**
+ * Container/codec used when writing recordings to disk.
+ */
+export type AudioCodec = "wav" | "flac" | "opus"
 export type AudioDevice = { index: string; name: string; is_default: boolean }
 export type BindingResponse = { success: boolean; binding: ShortcutBinding | null; error: string | null }
+/**
+ * Result of a microphone test. Levels are in dBFS, measured at the input
+ * before the gain and clean-up stages.
+ */
+export type CalibrationReport = { device: string | null; sample_rate: number | null; rms_db: number; peak_db: number; noise_floor_db: number; speech_level_db: number; clipped_samples: number; 
+/**
+ * Whether the VAD heard speech; `None` when no VAD is in use
+ */
+speech_detected: boolean | null; suggested_gain: number | null; 
+/**
+ * Only suggested while the energy VAD is in use; Silero's threshold is
+ * a speech probability the levels say nothing about
+ */
+suggested_energy_threshold_db: number | null }
+export type CaptureSource = "microphone" | "system_audio" | "both"
+/**
+ * How a vocabulary replacement is cased in the output
+ */
+export type CaseRule = 
+/**
+ * Follow the case of what was transcribed (all caps, capitalized)
+ */
+"preserve" | 
+/**
+ * Always write the entry exactly as configured, e.g. "iPhone"
+ */
+"exact" | "upper" | "lower"
 export type ClipboardHandling = "dont_modify" | "copy_to_clipboard"
 export type CustomSounds = { start: boolean; stop: boolean }
-export type EngineType = "Whisper" | "Parakeet" | "Moonshine"
+/**
+ * A named bundle of dictation settings. While the profile is active its
+ * fields are laid over the global settings for each dictation; fields left
+ * as `None` fall through to the global setting. The globals themselves are
+ * never changed by a profile.
+ */
+export type DictationProfile = { id: string; name: string; selected_model?: string | null; selected_language?: string | null; translate_to_english?: boolean | null; post_process_enabled?: boolean | null; post_process_provider_id?: string | null; post_process_selected_prompt_id?: string | null; paste_method?: PasteMethod | null; append_trailing_space?: boolean | null }
+export type EngineType = "Whisper" | "Parakeet" | "Moonshine" | 
+/**
+ * GGUF language model used for local post-processing, not transcription
+ */
+"Llm" | 
+/**
+ * ONNX voice print model used to tell speakers apart in meetings
+ */
+"SpeakerEmbedding"
 export type GoogleAuthStatus = { is_authenticated: boolean; email: string | null; name: string | null }
-export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null }
-export type LLMPrompt = { id: string; name: string; prompt: string }
+export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; post_process_prompt: string | null; post_process_steps: PostProcessStepRecord[] }
+export type LLMPrompt = { id: string; name: string; prompt: string; 
+/**
+ * Template sent as a separate system message; empty sends only `prompt`
+ */
+system_prompt?: string; temperature?: number | null; max_tokens?: number | null }
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
 export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number }
+export type ModelLatency = { model_id: string; transcriptions: number; average_latency_ms: number; average_audio_seconds: number }
 export type ModelLoadStatus = { is_loaded: boolean; current_model: string | null }
 export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
+/**
+ * Which inverse text normalization rules to apply. Rules a language has no
+ * word tables for are skipped for that language.
+ */
+export type NormalizationOptions = { 
+/**
+ * "twenty five" -> "25". Single words below ten stay spelled out.
+ */
+numbers: boolean; 
+/**
+ * "twenty first" -> "21st"
+ */
+ordinals: boolean; 
+/**
+ * "five dollars and fifty cents" -> "$5.50"
+ */
+currency: boolean; 
+/**
+ * "twelve percent" -> "12%"
+ */
+percentages: boolean; 
+/**
+ * "March fifth twenty twenty four" -> "March 5, 2024"
+ */
+dates: boolean; 
+/**
+ * "three thirty pm" -> "3:30 PM"
+ */
+times: boolean; 
+/**
+ * Runs of seven or more spoken digits -> "555-123-4567"
+ */
+phone_numbers: boolean; 
+/**
+ * "comma", "question mark", ... -> ",", "?"
+ */
+punctuation: boolean }
 export type OverlayPosition = "none" | "top" | "bottom"
 export type PasteMethod = "ctrl_v" | "direct" | "none" | "shift_insert" | "ctrl_shift_v"
-export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null }
+/**
+ * A provider to retry an LLM step with when the selected one fails.
+ */
+export type PostProcessFallback = { provider_id: string; 
+/**
+ * Empty uses the model configured for the provider
+ */
+model?: string }
+export type PostProcessProvider = { id: string; label: string; base_url: string; allow_base_url_edit?: boolean; models_endpoint?: string | null; dialect?: ProviderDialect }
+/**
+ * One step of the post-processing pipeline. Each step receives the output of
+ * the previous one.
+ */
+export type PostProcessStep = { id: string; kind: PostProcessStepKind; enabled?: boolean; 
+/**
+ * Only run when dictating in one of these languages ("de" also covers
+ * "de-AT"). Empty runs the step for every language.
+ */
+languages?: string[]; 
+/**
+ * Prompt for `LlmPrompt`; `None` uses the selected prompt
+ */
+prompt_id?: string | null; regex_rule?: RegexRule | null; target_language?: string | null }
+/**
+ * What a post-processing pipeline step does with its input.
+ */
+export type PostProcessStepKind = 
+/**
+ * OpenCC Simplified/Traditional conversion for zh-Hans / zh-Hant
+ */
+"chinese_conversion" | 
+/**
+ * Local inverse text normalization using `text_normalization`; skipped
+ * while `text_normalization_enabled` normalizes every transcription
+ */
+"normalization" | 
+/**
+ * An LLM prompt; `prompt_id` or the selected prompt
+ */
+"llm_prompt" | 
+/**
+ * A single regex replacement
+ */
+"regex_replace" | 
+/**
+ * LLM translation into `target_language`
+ */
+"translate"
+/**
+ * Output of one post-processing pipeline step, kept for debugging.
+ */
+export type PostProcessStepRecord = { step_id: string; kind: PostProcessStepKind; 
+/**
+ * `None` when the step failed or returned nothing
+ */
+output: string | null; duration_ms: number }
+/**
+ * Wire format a post-processing provider speaks.
+ */
+export type ProviderDialect = 
+/**
+ * `POST /chat/completions`
+ */
+"open_ai" | 
+/**
+ * Anthropic `POST /messages`
+ */
+"anthropic" | 
+/**
+ * Gemini `POST /models/{model}:generateContent`
+ */
+"gemini"
+export type ProviderUsage = { provider_id: string; count: number }
 export type RecordingRetentionPeriod = "never" | "preserve_limit" | "days_3" | "weeks_2" | "months_3"
+/**
+ * A regex replacement applied to the whole transcription after vocabulary
+ * matching. `replacement` may reference capture groups as `$1` or `${name}`.
+ */
+export type RegexRule = { pattern: string; replacement: string; case_insensitive?: boolean }
 export type ScreenshotMode = "activewindow" | "fullscreen"
 export type ShortcutBinding = { id: string; name: string; description: string; default_binding: string; current_binding: string }
 export type SoundTheme = "marimba" | "pop" | "custom"
+export type UsageStatistics = { total_entries: number; total_words: number; total_audio_minutes: number; words_per_day: WordsPerPeriod[]; words_per_week: WordsPerPeriod[]; latency_by_model: ModelLatency[]; post_process_by_provider: ProviderUsage[]; typing_wpm: number; estimated_typing_minutes: number; estimated_minutes_saved: number }
+export type VadBackend = "silero" | "energy" | "off"
+/**
+ * A word or phrase in the custom vocabulary.
+ * 
+ * `heard` lists explicit spellings that are always replaced with `written`
+ * (e.g. "jay son" -> "JSON"). When `fuzzy` is set, near misses of `written`
+ * are corrected as well, the same way plain custom words are.
+ */
+export type VocabularyEntry = { written: string; heard?: string[]; case?: CaseRule; fuzzy?: boolean }
+/**
+ * Editing commands that can be spoken during dictation.
+ */
+export type VoiceCommand = "new_line" | "new_paragraph" | "delete_last_sentence" | "scratch_that" | "caps_on" | "caps_off" | 
+/**
+ * Ends a hands-free session; anything said after it is dropped
+ */
+"stop_listening"
+/**
+ * A spoken phrase that triggers a command, e.g. "new line".
+ */
+export type VoiceCommandPhrase = { phrase: string; command: VoiceCommand }
+/**
+ * Words dictated within a single day or week, keyed by the first day of the period.
+ */
+export type WordsPerPeriod = { period_start: string; entries: number; words: number }
 
 /** tauri-specta globals **/
 
@@ -170,6 +170,11 @@ const ModelSelector: React.FC<ModelSelectorProps> = ({ onError }) => {
           if (isRecording) {
             return; // Skip auto-switch if recording in progress
           }
+          // Language models for post-processing are not speech models
+          const info = await commands.getModelInfo(modelId);
+          if (info.status === "ok" && info.data?.engine_type === "Llm") {
+            return;
+          }
           loadCurrentModel();
           handleModelSelect(modelId);
         }, 500);
@@ -250,7 +255,8 @@ const ModelSelector: React.FC<ModelSelectorProps> = ({ onError }) => {
     try {
       const result = await commands.getAvailableModels();
       if (result.status === "ok") {
-        setModels(result.data);
+        // Language models are downloaded from the post-processing settings
+        setModels(result.data.filter((m) => m.engine_type !== "Llm"));
       }
     } catch (err) {
       console.error("Failed to load models:", err);
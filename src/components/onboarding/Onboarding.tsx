@@ -22,8 +22,12 @@ const Onboarding: React.FC<OnboardingProps> = ({ onModelSelected }) => {
     try {
       const result = await commands.getAvailableModels();
       if (result.status === "ok") {
-        // Only show downloadable models for onboarding
-        setAvailableModels(result.data.filter((m) => !m.is_downloaded));
+        // Only show downloadable speech models for onboarding
+        setAvailableModels(
+          result.data.filter(
+            (m) => !m.is_downloaded && m.engine_type !== "Llm",
+          ),
+        );
       } else {
         setError(t("onboarding.errors.loadModels"));
       }
@@ -0,0 +1,114 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { listen } from "@tauri-apps/api/event";
+import { commands, type ModelInfo } from "@/bindings";
+import { Button } from "../../ui/Button";
+import { formatModelSize } from "../../../lib/utils/format";
+
+interface LocalModelListProps {
+  onDownloaded: () => void;
+}
+
+// Language models aren't offered in the speech model lists, so the offline
+// provider downloads them here.
+export const LocalModelList: React.FC<LocalModelListProps> = React.memo(
+  ({ onDownloaded }) => {
+    const { t } = useTranslation();
+    const [models, setModels] = useState<ModelInfo[]>([]);
+    const [progress, setProgress] = useState<Map<string, number>>(new Map());
+
+    const loadModels = async () => {
+      const result = await commands.getAvailableModels();
+      if (result.status === "ok") {
+        setModels(result.data.filter((m) => m.engine_type === "Llm"));
+      }
+    };
+
+    useEffect(() => {
+      loadModels();
+
+      const progressUnlisten = listen<{
+        model_id: string;
+        percentage: number;
+      }>("model-download-progress", (event) => {
+        setProgress((prev) =>
+          new Map(prev).set(event.payload.model_id, event.payload.percentage),
+        );
+      });
+      const completeUnlisten = listen<string>(
+        "model-download-complete",
+        (event) => {
+          setProgress((prev) => {
+            const next = new Map(prev);
+            next.delete(event.payload);
+            return next;
+          });
+          loadModels();
+          onDownloaded();
+        },
+      );
+
+      return () => {
+        progressUnlisten.then((fn) => fn());
+        completeUnlisten.then((fn) => fn());
+      };
+    }, [onDownloaded]);
+
+    const handleDownload = async (modelId: string) => {
+      setProgress((prev) => new Map(prev).set(modelId, 0));
+      const result = await commands.downloadModel(modelId);
+      if (result.status === "error") {
+        console.error("Failed to download model:", result.error);
+        setProgress((prev) => {
+          const next = new Map(prev);
+          next.delete(modelId);
+          return next;
+        });
+      }
+    };
+
+    return (
+      <div className="flex flex-col gap-2 w-full">
+        {models.map((model) => {
+          const percentage = progress.get(model.id);
+          return (
+            <div
+              key={model.id}
+              className="flex items-center justify-between gap-4"
+            >
+              <div className="flex flex-col">
+                <span className="text-sm font-medium">{model.name}</span>
+                <span className="text-xs text-mid-gray">
+                  {model.description}
+                </span>
+              </div>
+              {model.is_downloaded ? (
+                <span className="text-xs text-mid-gray">
+                  {t("settings.postProcessing.api.localModels.downloaded")}
+                </span>
+              ) : percentage !== undefined ? (
+                <span className="text-xs text-mid-gray">
+                  {t("settings.postProcessing.api.localModels.downloading", {
+                    percentage: Math.round(percentage),
+                  })}
+                </span>
+              ) : (
+                <Button
+                  variant="secondary"
+                  size="sm"
+                  onClick={() => handleDownload(model.id)}
+                >
+                  {t("settings.postProcessing.api.localModels.download", {
+                    size: formatModelSize(Number(model.size_mb)),
+                  })}
+                </Button>
+              )}
+            </div>
+          );
+        })}
+      </div>
+    );
+  },
+);
+
+LocalModelList.displayName = "LocalModelList";
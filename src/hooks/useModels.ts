@@ -30,7 +30,7 @@ export const useModels = () => {
     try {
       const result = await commands.getAvailableModels();
       if (result.status === "ok") {
-        setModels(result.data);
+        setModels(result.data.filter((m) => m.engine_type !== "Llm"));
         setError(null);
       } else {
         setError(`Failed to load models: ${result.error}`);
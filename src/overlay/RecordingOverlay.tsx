@@ -10,7 +10,14 @@ import "./RecordingOverlay.css";
 import { commands } from "@/bindings";
 import { syncLanguageFromSettings } from "@/i18n";
 
-type OverlayState = "recording" | "transcribing" | "gemini-sending" | "gemini-ready" | "no-audio";
+type OverlayState =
+  | "recording"
+  | "transcribing"
+  | "gemini-sending"
+  | "gemini-ready"
+  | "no-audio"
+  | "post-process-degraded"
+  | "post-process-skipped";
 
 const RecordingOverlay: React.FC = () => {
   const { t } = useTranslation();
@@ -66,7 +73,7 @@ const RecordingOverlay: React.FC = () => {
     } else if (state === "transcribing") {
       return <TranscriptionIcon />;
     } else {
-      // For gemini-sending and gemini-ready, use transcription icon
+      // Gemini and post-processing notices use the transcription icon
       return <TranscriptionIcon />;
     }
   };
@@ -103,6 +110,16 @@ const RecordingOverlay: React.FC = () => {
         {state === "no-audio" && (
           <div className="transcribing-text">{t("overlay.noAudio")}</div>
         )}
+        {state === "post-process-degraded" && (
+          <div className="transcribing-text">
+            {t("overlay.postProcessDegraded")}
+          </div>
+        )}
+        {state === "post-process-skipped" && (
+          <div className="transcribing-text">
+            {t("overlay.postProcessSkipped")}
+          </div>
+        )}
       </div>
 
       <div className="overlay-right">